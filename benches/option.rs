@@ -0,0 +1,189 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! Compares `ControlledOption` with the standard `Option`, and with hand-written code that uses a
+//! sentinel value to represent `None`.
+//!
+//! Every benchmark works on a list of `u32`s where every third element is missing.  The
+//! `controlled` and `std` variants store `NonZeroU32`s, and the `sentinel` variant stores raw
+//! `u32`s, using 0 to mean "missing".  Run them with `cargo bench`.
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use controlled_option::OptionSliceExt;
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::Throughput;
+
+const SIZES: &[usize] = &[64, 4096];
+
+fn raw_values(len: usize) -> Vec<u32> {
+    (0..len as u32)
+        .map(|i| if i % 3 == 0 { 0 } else { i })
+        .collect()
+}
+
+fn controlled_values(len: usize) -> Vec<ControlledOption<NonZeroU32>> {
+    raw_values(len)
+        .into_iter()
+        .map(|value| NonZeroU32::new(value).into())
+        .collect()
+}
+
+fn std_values(len: usize) -> Vec<Option<NonZeroU32>> {
+    raw_values(len).into_iter().map(NonZeroU32::new).collect()
+}
+
+fn construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construction");
+    for &len in SIZES {
+        let raw = raw_values(len);
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("controlled", len), &raw, |b, raw| {
+            b.iter(|| {
+                raw.iter()
+                    .map(|&value| match NonZeroU32::new(value) {
+                        Some(value) => ControlledOption::some(value),
+                        None => ControlledOption::none(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("std", len), &raw, |b, raw| {
+            b.iter(|| {
+                raw.iter()
+                    .map(|&value| NonZeroU32::new(value))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("sentinel", len), &raw, |b, raw| {
+            b.iter(|| raw.to_vec())
+        });
+    }
+    group.finish();
+}
+
+fn checking(c: &mut Criterion) {
+    let mut group = c.benchmark_group("checking");
+    let controlled = controlled_values(1);
+    let std = std_values(1);
+    let raw = raw_values(1);
+    group.bench_function("controlled", |b| {
+        b.iter(|| black_box(&controlled[0]).is_some())
+    });
+    group.bench_function("std", |b| b.iter(|| black_box(&std[0]).is_some()));
+    group.bench_function("sentinel", |b| b.iter(|| *black_box(&raw[0]) != 0));
+    group.finish();
+}
+
+fn decoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decoding");
+    let controlled = controlled_values(2);
+    let std = std_values(2);
+    let raw = raw_values(2);
+    group.bench_function("controlled", |b| {
+        b.iter(|| {
+            black_box(controlled[1])
+                .into_option()
+                .map_or(1, |value| value.get() * 2)
+        })
+    });
+    group.bench_function("std", |b| {
+        b.iter(|| black_box(std[1]).map_or(1, |value| value.get() * 2))
+    });
+    group.bench_function("sentinel", |b| {
+        b.iter(|| match black_box(raw[1]) {
+            0 => 1,
+            value => value * 2,
+        })
+    });
+    group.finish();
+}
+
+fn bulk_scanning(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk_scanning");
+    for &len in SIZES {
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(
+            BenchmarkId::new("controlled", len),
+            &controlled_values(len),
+            |b, values| {
+                b.iter(|| {
+                    values
+                        .iter()
+                        .filter_map(ControlledOption::as_option)
+                        .map(|value| value.get() as u64)
+                        .sum::<u64>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("std", len),
+            &std_values(len),
+            |b, values| {
+                b.iter(|| {
+                    values
+                        .iter()
+                        .flatten()
+                        .map(|value| value.get() as u64)
+                        .sum::<u64>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("sentinel", len),
+            &raw_values(len),
+            |b, values| {
+                b.iter(|| {
+                    values
+                        .iter()
+                        .filter(|&&value| value != 0)
+                        .map(|&value| value as u64)
+                        .sum::<u64>()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn filling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filling");
+    for &len in SIZES {
+        group.throughput(Throughput::Elements(len as u64));
+        let mut controlled = controlled_values(len);
+        group.bench_function(BenchmarkId::new("controlled", len), |b| {
+            b.iter(|| black_box(&mut controlled[..]).fill_none())
+        });
+        group.bench_function(BenchmarkId::new("controlled_zeroed", len), |b| {
+            b.iter(|| black_box(&mut controlled[..]).fill_none_zeroed())
+        });
+        let mut std = std_values(len);
+        group.bench_function(BenchmarkId::new("std", len), |b| {
+            b.iter(|| black_box(&mut std[..]).fill(None))
+        });
+        let mut raw = raw_values(len);
+        group.bench_function(BenchmarkId::new("sentinel", len), |b| {
+            b.iter(|| black_box(&mut raw[..]).fill(0))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    construction,
+    checking,
+    decoding,
+    bulk_scanning,
+    filling
+);
+criterion_main!(benches);
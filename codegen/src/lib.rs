@@ -0,0 +1,116 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! Probe functions for checking that `ControlledOption` compiles down to the same machine code as
+//! a standard `Option`.
+//!
+//! Each operation has a pair of probes: `controlled_<op>` uses `ControlledOption`, and `std_<op>`
+//! does the same thing with `Option`.  The probes are `#[no_mangle]` so that they're easy to find
+//! in the generated assembly, and the test in `tests/parity.rs` verifies that each pair compiles
+//! to the same instructions.  When you add a new probe, make sure to give it a `std_` twin.
+
+#![no_std]
+
+use core::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+
+//-------------------------------------------------------------------------------------------------
+// NonZero niches
+
+#[no_mangle]
+pub fn controlled_is_some_nonzero(value: &ControlledOption<NonZeroU32>) -> bool {
+    value.is_some()
+}
+
+#[no_mangle]
+pub fn std_is_some_nonzero(value: &Option<NonZeroU32>) -> bool {
+    value.is_some()
+}
+
+#[no_mangle]
+pub fn controlled_some_nonzero(value: NonZeroU32) -> ControlledOption<NonZeroU32> {
+    ControlledOption::some(value)
+}
+
+#[no_mangle]
+pub fn std_some_nonzero(value: NonZeroU32) -> Option<NonZeroU32> {
+    Some(value)
+}
+
+#[no_mangle]
+pub fn controlled_none_nonzero() -> ControlledOption<NonZeroU32> {
+    ControlledOption::none()
+}
+
+#[no_mangle]
+pub fn std_none_nonzero() -> Option<NonZeroU32> {
+    None
+}
+
+#[no_mangle]
+pub fn controlled_into_option_nonzero(value: ControlledOption<NonZeroU32>) -> Option<NonZeroU32> {
+    value.into_option()
+}
+
+#[no_mangle]
+pub fn std_into_option_nonzero(value: Option<NonZeroU32>) -> Option<NonZeroU32> {
+    value
+}
+
+//-------------------------------------------------------------------------------------------------
+// Reference niches
+
+#[no_mangle]
+pub fn controlled_is_some_ref(value: &ControlledOption<&u64>) -> bool {
+    value.is_some()
+}
+
+#[no_mangle]
+pub fn std_is_some_ref(value: &Option<&u64>) -> bool {
+    value.is_some()
+}
+
+#[no_mangle]
+pub fn controlled_some_ref(value: &u64) -> ControlledOption<&u64> {
+    ControlledOption::some(value)
+}
+
+#[no_mangle]
+pub fn std_some_ref(value: &u64) -> Option<&u64> {
+    Some(value)
+}
+
+#[no_mangle]
+pub fn controlled_none_ref() -> ControlledOption<&'static u64> {
+    ControlledOption::none()
+}
+
+#[no_mangle]
+pub fn std_none_ref() -> Option<&'static u64> {
+    None
+}
+
+#[no_mangle]
+pub fn controlled_into_option_ref(value: ControlledOption<&u64>) -> Option<&u64> {
+    value.into_option()
+}
+
+#[no_mangle]
+pub fn std_into_option_ref(value: Option<&u64>) -> Option<&u64> {
+    value
+}
+
+#[no_mangle]
+pub fn controlled_deref_or_zero_ref(value: ControlledOption<&u64>) -> u64 {
+    value.into_option().copied().unwrap_or(0)
+}
+
+#[no_mangle]
+pub fn std_deref_or_zero_ref(value: Option<&u64>) -> u64 {
+    value.copied().unwrap_or(0)
+}
@@ -0,0 +1,163 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! Compiles the probes in `src/lib.rs` to assembly, and verifies that each `controlled_` probe
+//! compiles to the same instructions as its `std_` twin.
+//!
+//! We only know how to parse the assembly that LLVM produces for ELF targets, so this test is
+//! skipped on other platforms.
+
+#![cfg(target_os = "linux")]
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// The probe pairs that must be present.  (This makes sure that we can't accidentally pass the
+/// test by failing to find any probes.)
+const EXPECTED_PROBES: &[&str] = &[
+    "is_some_nonzero",
+    "some_nonzero",
+    "none_nonzero",
+    "into_option_nonzero",
+    "is_some_ref",
+    "some_ref",
+    "none_ref",
+    "into_option_ref",
+    "deref_or_zero_ref",
+];
+
+/// Builds the probe library in release mode, returning the path of the generated assembly.
+fn build_probes() -> PathBuf {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["rustc", "--release", "--lib", "--target-dir"])
+        .arg(&target_dir)
+        .args(["--", "--emit", "asm"])
+        .status()
+        .expect("could not run cargo");
+    assert!(status.success(), "could not build codegen probes");
+
+    let deps = target_dir.join("release").join("deps");
+    std::fs::read_dir(&deps)
+        .expect("could not read build output")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with("controlled_option_codegen-") && name.ends_with(".s")
+        })
+        .max_by_key(|path| path.metadata().unwrap().modified().unwrap())
+        .expect("could not find generated assembly")
+}
+
+/// The instructions of each function in an assembly file.
+struct Functions {
+    bodies: BTreeMap<String, Vec<String>>,
+    aliases: BTreeMap<String, String>,
+}
+
+impl Functions {
+    fn parse(asm: &str) -> Functions {
+        let mut bodies = BTreeMap::new();
+        let mut aliases = BTreeMap::new();
+        let mut current: Option<(String, Vec<String>)> = None;
+        for line in asm.lines() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            // LLVM might merge identical functions, in which case one is an alias for the other.
+            if let Some((name, target)) = line.split_once(" = ") {
+                aliases.insert(name.trim().to_string(), target.trim().to_string());
+                continue;
+            }
+
+            if let Some((name, body)) = current.as_mut() {
+                if line.starts_with(".Lfunc_end") {
+                    bodies.insert(std::mem::take(name), std::mem::take(body));
+                    current = None;
+                } else if line.ends_with(':') || !line.starts_with('.') {
+                    body.push(line.to_string());
+                }
+            } else if let Some(name) = line.strip_suffix(':') {
+                if !name.starts_with('.') {
+                    current = Some((name.to_string(), Vec::new()));
+                }
+            }
+        }
+        Functions { bodies, aliases }
+    }
+
+    /// Returns the instructions of a function, with its local labels renumbered so that they
+    /// don't depend on where the function appears in the file.
+    fn instructions(&self, name: &str) -> Vec<String> {
+        let mut name = name;
+        while let Some(target) = self.aliases.get(name) {
+            name = target;
+        }
+        let body = self
+            .bodies
+            .get(name)
+            .unwrap_or_else(|| panic!("could not find probe {}", name));
+
+        let mut labels = body
+            .iter()
+            .filter_map(|line| line.strip_suffix(':'))
+            .enumerate()
+            .collect::<Vec<_>>();
+        // Replace the longest labels first, so that `.LBB0_1` doesn't clobber part of `.LBB0_10`.
+        labels.sort_by_key(|(_, label)| std::cmp::Reverse(label.len()));
+        body.iter()
+            .map(|line| {
+                let mut line = line.clone();
+                for (index, label) in &labels {
+                    line = line.replace(label, &format!("<label{}>", index));
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn controlled_options_compile_to_same_instructions_as_std_options() {
+    let asm_path = build_probes();
+    let asm = std::fs::read_to_string(&asm_path).expect("could not read generated assembly");
+    let functions = Functions::parse(&asm);
+
+    let probes = functions
+        .bodies
+        .keys()
+        .chain(functions.aliases.keys())
+        .filter_map(|name| name.strip_prefix("controlled_"))
+        .collect::<Vec<_>>();
+    for expected in EXPECTED_PROBES {
+        assert!(probes.contains(expected), "missing probe {}", expected);
+    }
+
+    let mut mismatches = Vec::new();
+    for probe in probes {
+        let controlled = functions.instructions(&format!("controlled_{}", probe));
+        let std = functions.instructions(&format!("std_{}", probe));
+        if controlled != std {
+            mismatches.push(format!(
+                "{}:\n  controlled:\n    {}\n  std:\n    {}",
+                probe,
+                controlled.join("\n    "),
+                std.join("\n    "),
+            ));
+        }
+    }
+    assert!(
+        mismatches.is_empty(),
+        "probes compile to different instructions:\n{}",
+        mismatches.join("\n")
+    );
+}
@@ -8,23 +8,366 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
+use proc_macro2::Ident;
+use proc_macro2::Literal;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::format_ident;
 use quote::quote;
+use quote::quote_spanned;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
 use syn::parse_macro_input;
 use syn::parse_quote;
+use syn::parse_quote_spanned;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::Attribute;
+use syn::Expr;
+use syn::ExprLit;
+use syn::ExprUnary;
 use syn::Field;
 use syn::Fields;
+use syn::GenericArgument;
+use syn::GenericParam;
 use syn::Item;
+use syn::ItemEnum;
+use syn::ItemStruct;
+use syn::ItemUnion;
+use syn::Lit;
+use syn::LitStr;
 use syn::Member;
+use syn::Meta;
+use syn::NestedMeta;
+use syn::Path;
+use syn::PathArguments;
+use syn::Token;
 use syn::Type;
+use syn::UnOp;
+use syn::Variant;
+use syn::Visibility;
 use syn::WhereClause;
+use syn::WherePredicate;
 
-fn field_is_niche(field: &&Field) -> bool {
+fn has_niche_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident("niche"))
+}
+
+/// A single argument of a `#[niche(...)]` attribute, which is either a bare flag or a
+/// `name = value` pair.
+struct NicheArg {
+    name: Ident,
+    value: Option<Expr>,
+}
+
+impl Parse for NicheArg {
+    fn parse(input: ParseStream) -> syn::parse::Result<NicheArg> {
+        let name = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(NicheArg { name, value })
+    }
+}
+
+/// Returns the arguments of all of the `#[niche(...)]` attributes in a list.  A bare `#[niche]`
+/// attribute has no arguments.
+fn niche_args(attrs: &[Attribute]) -> Result<Vec<NicheArg>, syn::parse::Error> {
+    let mut args = Vec::new();
+    for attr in attrs {
+        if attr.path.is_ident("niche") && !attr.tokens.is_empty() {
+            let parser = Punctuated::<NicheArg, Token![,]>::parse_terminated;
+            args.extend(attr.parse_args_with(parser)?);
+        }
+    }
+    Ok(args)
+}
+
+/// Returns an error for a `#[niche(...)]` argument that isn't valid in this position.  `known`
+/// lists the arguments that are valid, along with whether each one takes a value.
+fn niche_arg_error(arg: &NicheArg, position: &str, known: &[(&str, bool)]) -> syn::parse::Error {
+    let name = arg.name.to_string();
+    let msg = match known.iter().find(|(known, _)| *known == name) {
+        Some((_, true)) => format!("#[niche({})] requires a value", name),
+        Some((_, false)) => format!("#[niche({})] does not take a value", name),
+        None => {
+            let expected = known
+                .iter()
+                .map(|(name, _)| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "unknown #[niche] argument `{}` for {}; expected one of {}",
+                name, position, expected
+            )
+        }
+    };
+    syn::parse::Error::new_spanned(&arg.name, msg)
+}
+
+/// Returns an error if a field's type is a primitive number, which doesn't have a niche.  (The
+/// compiler would catch this too, since the field type wouldn't implement `Niche`, but we can
+/// suggest what to do instead.)
+fn check_niche_field_type(field: &Field) -> Result<(), syn::parse::Error> {
+    const PRIMITIVES: &[&str] = &[
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+        "f32", "f64",
+    ];
+    if let Type::Path(path) = &field.ty {
+        if PRIMITIVES
+            .iter()
+            .any(|primitive| path.path.is_ident(primitive))
+        {
+            let msg = "niche field has a primitive type with no niche; \
+                       use #[niche(sentinel = ...)], or a type like NonZero or NonMax";
+            return Err(syn::parse::Error::new_spanned(field, msg));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the string literal that is the value of an attribute argument.
+fn str_lit<'a>(expr: &'a Expr, attr: &str) -> Result<&'a LitStr, syn::parse::Error> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Ok(lit),
+        _ => {
+            let msg = format!("{} must be a string literal", attr);
+            Err(syn::parse::Error::new_spanned(expr, msg))
+        }
+    }
+}
+
+/// Parses a `"a.b.c"` string literal into the sequence of fields that it accesses.  Each field can
+/// be a name or a tuple index.
+fn parse_member_path(expr: &Expr) -> Result<Vec<Member>, syn::parse::Error> {
+    let path = str_lit(expr, "#[niche(path)]")?;
+    path.value()
+        .split('.')
+        .map(|member| {
+            syn::parse_str::<Member>(member).map_err(|_| {
+                let msg = format!("invalid field `{}` in #[niche(path)]", member);
+                syn::parse::Error::new_spanned(path, msg)
+            })
+        })
+        .collect()
+}
+
+/// How the derive fills in and checks the niche of a field.
+struct NicheFieldOps<'a> {
+    fill_with_none: TokenStream2,
+    field_is_none: TokenStream2,
+    /// The field type, if we use its `Niche` implementation (and so need a `Niche` bound for it).
+    niche_type: Option<&'a Type>,
+    /// The name of the `NicheStrategy` type to generate for this field, if any.
+    strategy: Option<Ident>,
+}
+
+impl<'a> NicheFieldOps<'a> {
+    fn from_niche_impl(niche_type: Option<&'a Type>) -> NicheFieldOps<'a> {
+        NicheFieldOps {
+            fill_with_none: quote! {
+                unsafe { ::controlled_option::fill_struct_field_with_none(field) }
+            },
+            field_is_none: quote! {
+                unsafe { ::controlled_option::struct_field_is_none(field) }
+            },
+            niche_type,
+            strategy: None,
+        }
+    }
+}
+
+/// Returns how to fill in and check the niche of a field marked #[niche].  A
+/// #[niche(with = "module")] field gets its niche from the functions in that module, and a
+/// #[niche(sentinel = expr)] field compares against a sentinel value, instead of using the field
+/// type's `Niche` implementation.
+fn niche_field_ops(field: &Field) -> Result<NicheFieldOps<'_>, syn::parse::Error> {
+    let mut with = None;
+    let mut sentinel = None;
+    let mut strategy = None;
+    for arg in niche_args(&field.attrs)? {
+        match (arg.name.to_string().as_str(), &arg.value) {
+            ("with", Some(_)) | ("sentinel", Some(_)) if with.is_some() || sentinel.is_some() => {
+                let msg = "#[niche] field can only have one of `with` or `sentinel`";
+                return Err(syn::parse::Error::new_spanned(&arg.name, msg));
+            }
+            ("with", Some(value)) => {
+                with = Some(str_lit(value, "#[niche(with)]")?.parse::<Path>()?);
+            }
+            ("sentinel", Some(value)) => sentinel = Some(value.clone()),
+            ("strategy", Some(value)) => {
+                strategy = Some(str_lit(value, "#[niche(strategy)]")?.parse::<Ident>()?);
+            }
+            ("priority", Some(_)) => {}
+            _ => {
+                let known = [
+                    ("with", true),
+                    ("sentinel", true),
+                    ("priority", true),
+                    ("strategy", true),
+                ];
+                return Err(niche_arg_error(&arg, "a field", &known));
+            }
+        }
+    }
+
+    let mut ops = match (with, sentinel) {
+        (Some(with), _) => NicheFieldOps {
+            fill_with_none: quote! {
+                unsafe { ::controlled_option::fill_struct_field_with(field, #with::none) }
+            },
+            field_is_none: quote! {
+                unsafe { ::controlled_option::struct_field_is(field, #with::is_none) }
+            },
+            niche_type: None,
+            strategy: None,
+        },
+        (_, Some(sentinel)) => NicheFieldOps {
+            fill_with_none: quote! { unsafe { field.write(#sentinel) } },
+            field_is_none: quote! { unsafe { *field == #sentinel } },
+            niche_type: None,
+            strategy: None,
+        },
+        (None, None) => {
+            check_niche_field_type(field)?;
+            NicheFieldOps::from_niche_impl(Some(&field.ty))
+        }
+    };
+    ops.strategy = strategy;
+    Ok(ops)
+}
+
+fn field_member(idx: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => idx.into(),
+    }
+}
+
+/// Returns the combined condition of a field's `#[cfg]` attributes, or `None` if it doesn't have
+/// any.
+fn field_cfg(field: &Field) -> Result<Option<TokenStream2>, syn::parse::Error> {
+    let mut predicates = Vec::new();
     for attr in &field.attrs {
-        if attr.path.is_ident("niche") {
-            return true;
+        if attr.path.is_ident("cfg") {
+            predicates.push(attr.parse_args::<TokenStream2>()?);
+        }
+    }
+    if predicates.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(quote! { all(#(#predicates),*) }))
+}
+
+/// Returns the priority of a candidate niche field, from its `#[niche(priority = N)]` argument.
+/// A field without an explicit priority has priority 0.
+fn niche_priority(field: &Field) -> Result<u32, syn::parse::Error> {
+    let mut priority = 0;
+    for arg in niche_args(&field.attrs)? {
+        if arg.name != "priority" {
+            continue;
+        }
+        match &arg.value {
+            Some(Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            })) => priority = lit.base10_parse()?,
+            Some(value) => {
+                let msg = "#[niche(priority)] must be a non-negative integer literal";
+                return Err(syn::parse::Error::new_spanned(value, msg));
+            }
+            None => return Err(niche_arg_error(&arg, "a field", &[("priority", true)])),
+        }
+    }
+    Ok(priority)
+}
+
+/// Returns the field that holds a struct's niche: the one marked #[niche], or the only field if
+/// there's just one.  If several fields are marked #[niche], we use the one with the highest
+/// priority.
+fn find_niche_field(fields: &Fields) -> Result<Option<(Member, &Field)>, syn::parse::Error> {
+    let mut members = fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| (field_member(idx, field), field));
+    if fields.len() == 1 {
+        return Ok(members.next());
+    }
+    let mut niche_field = None;
+    let mut best_priority = 0;
+    let mut tied = None;
+    for (member, field) in members.filter(|(_, field)| field_is_niche(field)) {
+        let priority = niche_priority(field)?;
+        if niche_field.is_none() || priority > best_priority {
+            niche_field = Some((member, field));
+            best_priority = priority;
+            tied = None;
+        } else if priority == best_priority {
+            tied = Some(field);
         }
     }
-    false
+    if let Some(tied) = tied {
+        let msg = "#[derive(Niche)] found more than one #[niche] field with the highest \
+                   priority; use #[niche(priority = N)] to choose between them";
+        return Err(syn::parse::Error::new_spanned(tied, msg));
+    }
+    Ok(niche_field)
+}
+
+/// Returns whether a type has a `#[repr(transparent)]` attribute.
+fn is_repr_transparent(item: &ItemStruct) -> Result<bool, syn::parse::Error> {
+    has_repr(item, "transparent")
+}
+
+/// Returns whether a type has a `#[repr]` attribute with the given representation.
+fn has_repr(item: &ItemStruct, repr: &str) -> Result<bool, syn::parse::Error> {
+    for attr in &item.attrs {
+        if !attr.path.is_ident("repr") {
+            continue;
+        }
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in &list.nested {
+                if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                    if path.is_ident(repr) {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Returns whether a field is a `PhantomData`, which a `#[repr(transparent)]` struct can have in
+/// addition to its one non-zero-sized field.
+fn field_is_phantom(field: &Field) -> bool {
+    match &field.ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData"),
+        _ => false,
+    }
+}
+
+fn field_is_niche(field: &&Field) -> bool {
+    has_niche_attr(&field.attrs)
+}
+
+fn variant_is_niche(variant: &&Variant) -> bool {
+    has_niche_attr(&variant.attrs)
+}
+
+/// Returns a where clause requiring a field type to implement `Niche`.  We give the bound the
+/// field type's span, so that if the type doesn't implement `Niche`, the compiler's error points
+/// at the field.
+fn niche_bound(ty: &Type) -> WhereClause {
+    let bound = quote_spanned! {ty.span()=> #ty: ::controlled_option::Niche };
+    parse_quote! { where #bound }
 }
 
 fn merge_where_clauses(lhs: Option<WhereClause>, rhs: WhereClause) -> WhereClause {
@@ -37,107 +380,1649 @@ fn merge_where_clauses(lhs: Option<WhereClause>, rhs: WhereClause) -> WhereClaus
     }
 }
 
+/// Returns a where clause requiring a #[niche(output = "Type")] type to implement `NicheOutput`
+/// for the struct.  Unlike the `Niche` bound, a #[niche(bound = "...")] attribute doesn't replace
+/// this one, since it's what makes reinterpreting the struct's bytes sound.
+fn niche_output_bound(item: &ItemStruct, output: &Type) -> WhereClause {
+    let ty_name = &item.ident;
+    let (_, ty_generics, _) = item.generics.split_for_impl();
+    parse_quote_spanned! {output.span()=>
+        where #output: ::controlled_option::NicheOutput<#ty_name #ty_generics>
+    }
+}
+
+/// Returns the where clause for a derived impl: the type's own where clause, plus either a bound
+/// requiring the niche field's type to implement `Niche`, or the bounds from a
+/// #[niche(bound = "...")] attribute, which replace it.
+fn impl_where_clause(
+    ty_where_clause: Option<&WhereClause>,
+    niche_type: Option<&Type>,
+    bound: Option<&WhereClause>,
+) -> Option<WhereClause> {
+    let extra = match (bound, niche_type) {
+        (Some(bound), _) => bound.clone(),
+        (None, Some(niche_type)) => niche_bound(niche_type),
+        (None, None) => return ty_where_clause.cloned(),
+    };
+    Some(merge_where_clauses(ty_where_clause.cloned(), extra))
+}
+
+/// Parses the value of a #[niche(bound = "...")] attribute, which is a comma-separated list of
+/// where predicates.  An empty string suppresses the automatic bound without adding any others.
+fn parse_bound(value: &Expr) -> Result<WhereClause, syn::parse::Error> {
+    let parser = Punctuated::<WherePredicate, Token![,]>::parse_terminated;
+    let predicates = str_lit(value, "#[niche(bound)]")?.parse_with(parser)?;
+    Ok(WhereClause {
+        where_token: Default::default(),
+        predicates,
+    })
+}
+
 #[proc_macro_derive(Niche, attributes(niche))]
 pub fn derive_decode(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as Item);
-    match &item {
-        Item::Struct(item) => {
-            let ty_name = &item.ident;
-            let ty_generics = &item.generics;
-            let ty_where_clause = item.generics.where_clause.as_ref().cloned();
-
-            // Find the field that is marked #[niche].  In a regular struct, extract its name; in a
-            // tuple struct, extract its index.  In both cases, that can be converted into a
-            // `Member`, which is the type needed down below in the field access expression.
-            let niche_field_name: Member;
-            let niche_field_type: &Type;
-            match &item.fields {
-                Fields::Named(fields) => {
-                    let niche_field = match fields.named.iter().find(field_is_niche) {
-                        Some(field) if field.ident.is_some() => field,
-                        _ => {
-                            let msg = "#[derive(Niche)] requires a field marked #[niche]";
-                            return syn::parse::Error::new_spanned(item, msg)
-                                .to_compile_error()
-                                .into();
-                        }
-                    };
-                    niche_field_name = niche_field.ident.as_ref().unwrap().clone().into();
-                    niche_field_type = &niche_field.ty;
-                }
-                Fields::Unnamed(fields) => {
-                    let (idx, niche_field) = match fields
-                        .unnamed
-                        .iter()
-                        .enumerate()
-                        .find(|(_, field)| field_is_niche(field))
-                    {
-                        Some((idx, field)) => (idx, field),
-                        None => {
-                            let msg = "#[derive(Niche)] requires a field marked #[niche]";
-                            return syn::parse::Error::new_spanned(item, msg)
-                                .to_compile_error()
-                                .into();
-                        }
-                    };
-                    niche_field_name = idx.into();
-                    niche_field_type = &niche_field.ty;
-                }
-                Fields::Unit => {
-                    let msg = "#[derive(Niche)] cannot be used on an empty tuple struct";
-                    return syn::parse::Error::new_spanned(item, msg)
-                        .to_compile_error()
-                        .into();
-                }
-            }
-
-            let where_clause = merge_where_clauses(
-                ty_where_clause,
-                parse_quote! { where #niche_field_type: ::controlled_option::Niche },
-            );
-
-            let output = quote! {
-                impl #ty_generics ::controlled_option::Niche for #ty_name #ty_generics
-                #where_clause
-                {
-                    type Output = ::std::mem::MaybeUninit<Self>;
-
-                    #[inline]
-                    fn none() -> Self::Output {
-                        let mut value = Self::Output::uninit();
-                        let ptr = value.as_mut_ptr();
-                        ::controlled_option::fill_struct_field_with_none(
-                            unsafe { ::std::ptr::addr_of_mut!((*ptr).#niche_field_name) }
-                        );
-                        value
-                    }
+    let result = match &item {
+        Item::Struct(item) => derive_struct(item, "a struct"),
+        Item::Enum(item) => derive_enum(item),
+        Item::Union(item) => derive_union(item),
+        _ => {
+            let msg = "#[derive(Niche)] is only supported on struct, enum, and union types";
+            Err(syn::parse::Error::new_spanned(&item, msg))
+        }
+    };
+    let result = result.and_then(|output| {
+        let alias = derive_alias(&item)?;
+        Ok(quote! { #output #alias })
+    });
+    match result {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
 
-                    #[inline]
-                    fn is_none(value: &Self::Output) -> bool {
-                        let ptr = value.as_ptr();
-                        ::controlled_option::struct_field_is_none(
-                            unsafe { ::std::ptr::addr_of!((*ptr).#niche_field_name) }
-                        )
-                    }
+/// Generates the `ControlledOption` type alias requested by a `#[niche(alias = "...")]`
+/// argument, if there is one.
+fn derive_alias(item: &Item) -> Result<TokenStream2, syn::parse::Error> {
+    let (attrs, vis, ty_name, generics) = match item {
+        Item::Struct(item) => (&item.attrs, &item.vis, &item.ident, &item.generics),
+        Item::Enum(item) => (&item.attrs, &item.vis, &item.ident, &item.generics),
+        Item::Union(item) => (&item.attrs, &item.vis, &item.ident, &item.generics),
+        _ => return Ok(TokenStream2::new()),
+    };
+    let mut alias = None;
+    for arg in niche_args(attrs)? {
+        if let ("alias", Some(value)) = (arg.name.to_string().as_str(), &arg.value) {
+            alias = Some(str_lit(value, "#[niche(alias)]")?.parse::<Ident>()?);
+        }
+    }
+    let alias = match alias {
+        Some(alias) => alias,
+        None => return Ok(TokenStream2::new()),
+    };
 
-                    #[inline]
-                    fn into_some(value: Self) -> Self::Output {
-                        ::std::mem::MaybeUninit::new(value)
-                    }
+    // Bounds on a type alias's parameters aren't enforced (and rustc warns about them), so the
+    // alias only repeats the parameters themselves.
+    let mut alias_generics = generics.clone();
+    alias_generics.where_clause = None;
+    for param in &mut alias_generics.params {
+        match param {
+            GenericParam::Type(param) => {
+                param.colon_token = None;
+                param.bounds.clear();
+            }
+            GenericParam::Lifetime(param) => {
+                param.colon_token = None;
+                param.bounds.clear();
+            }
+            GenericParam::Const(_) => {}
+        }
+    }
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let doc = format!("An optional [`{}`], stored using its niche.", ty_name);
 
-                    #[inline]
-                    fn from_some(value: Self::Output) -> Self {
-                        unsafe { value.assume_init() }
-                    }
+    Ok(quote! {
+        #[doc = #doc]
+        #vis type #alias #alias_generics =
+            ::controlled_option::ControlledOption<#ty_name #ty_generics>;
+    })
+}
+
+fn derive_struct(item: &ItemStruct, kind: &str) -> Result<TokenStream2, syn::parse::Error> {
+    let ty_name = &item.ident;
+    let (impl_generics, ty_generics, ty_where_clause) = item.generics.split_for_impl();
+    let ty_where_clause = ty_where_clause.cloned();
+
+    let mut niche_path = None;
+    let mut niche_output = None;
+    let mut niche_zero = false;
+    let mut niche_drop = false;
+    let mut niche_repr = false;
+    let mut niche_mirror = None;
+    let mut niche_none_value = None;
+    let mut niche_bitwise = false;
+    let mut niche_bound_override = None;
+    for arg in niche_args(&item.attrs)? {
+        match (arg.name.to_string().as_str(), &arg.value) {
+            ("path", Some(value)) => niche_path = Some(parse_member_path(value)?),
+            ("output", Some(value)) => {
+                niche_output = Some(str_lit(value, "#[niche(output)]")?.parse::<Type>()?)
+            }
+            ("zero", None) => niche_zero = true,
+            ("drop", None) => niche_drop = true,
+            ("repr", None) => niche_repr = true,
+            ("mirror", None) => niche_mirror = Some(Vec::new()),
+            ("mirror", Some(value)) => {
+                let parser = Punctuated::<Path, Token![,]>::parse_terminated;
+                let derives = str_lit(value, "#[niche(mirror)]")?.parse_with(parser)?;
+                niche_mirror = Some(derives.into_iter().collect());
+            }
+            ("bound", Some(value)) => niche_bound_override = Some(parse_bound(value)?),
+            ("none_value", Some(value)) => niche_none_value = Some(value.clone()),
+            ("bitwise", None) => niche_bitwise = true,
+            ("alias", Some(_)) => {}
+            _ => {
+                let known = [
+                    ("alias", true),
+                    ("bound", true),
+                    ("none_value", true),
+                    ("path", true),
+                    ("output", true),
+                    ("zero", false),
+                    ("drop", false),
+                    ("repr", false),
+                    ("mirror", false),
+                    ("bitwise", false),
+                ];
+                return Err(niche_arg_error(&arg, kind, &known));
+            }
+        }
+    }
+
+    if niche_drop && niche_output.is_some() {
+        let msg = "#[niche(drop)] cannot be used with #[niche(output)]";
+        return Err(syn::parse::Error::new_spanned(item, msg));
+    }
+    if niche_repr && (niche_path.is_some() || niche_output.is_some() || niche_drop) {
+        let msg = "#[niche(repr)] cannot be used with #[niche(path)], #[niche(output)], \
+                   or #[niche(drop)]";
+        return Err(syn::parse::Error::new_spanned(item, msg));
+    }
+    if niche_mirror.is_some()
+        && (niche_path.is_some()
+            || niche_output.is_some()
+            || niche_drop
+            || niche_zero
+            || niche_repr)
+    {
+        let msg = "#[niche(mirror)] cannot be used with #[niche(path)], #[niche(output)], \
+                   #[niche(drop)], #[niche(zero)], or #[niche(repr)]";
+        return Err(syn::parse::Error::new_spanned(item, msg));
+    }
+    if niche_bitwise && (niche_repr || niche_mirror.is_some() || niche_none_value.is_some()) {
+        let msg = "#[niche(bitwise)] cannot be used with #[niche(repr)], #[niche(mirror)], \
+                   or #[niche(none_value)]";
+        return Err(syn::parse::Error::new_spanned(item, msg));
+    }
+    if let Some(none_value) = niche_none_value {
+        if niche_path.is_some() || niche_drop || niche_zero || niche_repr || niche_mirror.is_some()
+        {
+            let msg = "#[niche(none_value)] cannot be used with #[niche(path)], \
+                       #[niche(drop)], #[niche(zero)], #[niche(repr)], or #[niche(mirror)]";
+            return Err(syn::parse::Error::new_spanned(item, msg));
+        }
+        if let Some(field) = item.fields.iter().find(field_is_niche) {
+            let msg = "#[niche] field cannot be used with #[niche(none_value)]";
+            return Err(syn::parse::Error::new_spanned(field, msg));
+        }
+        let bound = niche_bound_override.as_ref();
+        return Ok(derive_struct_with_none_value(
+            item,
+            &none_value,
+            niche_output.as_ref(),
+            bound,
+        ));
+    }
+
+    // A #[repr(transparent)] newtype can use the niche of its only field directly, including its
+    // `Output` type.
+    if niche_path.is_none()
+        && niche_output.is_none()
+        && !niche_zero
+        && !niche_drop
+        && !niche_repr
+        && niche_mirror.is_none()
+        && !niche_bitwise
+        && is_repr_transparent(item)?
+    {
+        if let Some(output) = derive_transparent_struct(item, niche_bound_override.as_ref())? {
+            return Ok(output);
+        }
+    }
+
+    // Find the field that is marked #[niche] (or the only field, if there's just one).  In a
+    // regular struct, extract its name; in a tuple struct, extract its index.  In both cases,
+    // that can be converted into a `Member`, which is the type needed down below in the field
+    // access expression.  If the struct has a #[niche(path = "...")] attribute instead, the
+    // niche is a nested field, and we need a sequence of members to access it.
+    let niche_by_path = niche_path.is_some();
+    let niche_field_path: Vec<Member>;
+    let niche_ops: NicheFieldOps;
+    let mut strategies = Vec::new();
+    if let Some(path) = niche_path {
+        if let Some(field) = item.fields.iter().find(field_is_niche) {
+            let msg = "#[niche] field cannot be used with #[niche(path)]";
+            return Err(syn::parse::Error::new_spanned(field, msg));
+        }
+        // We don't know the type of the nested field, so we can't add a `Niche` bound for it.
+        niche_field_path = path;
+        niche_ops = NicheFieldOps::from_niche_impl(None);
+    } else {
+        let (niche_field_name, niche_field) = match find_niche_field(&item.fields)? {
+            Some(found) => found,
+            None if matches!(item.fields, Fields::Unit) => {
+                let msg = "#[derive(Niche)] cannot be used on an empty tuple struct";
+                return Err(syn::parse::Error::new_spanned(item, msg));
+            }
+            None => {
+                let msg = "#[derive(Niche)] requires a field marked #[niche]";
+                return Err(syn::parse::Error::new_spanned(item, msg));
+            }
+        };
+        niche_field_path = vec![niche_field_name];
+        niche_ops = niche_field_ops(niche_field)?;
+
+        // Any candidate field (including the one we chose) can ask for a separate strategy type
+        // that uses its niche.
+        for (idx, field) in item.fields.iter().enumerate() {
+            if !field_is_niche(&field) {
+                continue;
+            }
+            let ops = niche_field_ops(field)?;
+            if ops.strategy.is_some() {
+                let member = field_member(idx, field);
+                let bound = niche_bound_override.as_ref();
+                let strategy_name = ops.strategy.clone().unwrap();
+                let doc = format!(
+                    "A `NicheStrategy` that stores a [`{}`] using the niche of its `{}` field.",
+                    ty_name,
+                    quote! { #member },
+                );
+                let target = quote! { #ty_name #ty_generics };
+                strategies.push(niche_strategy_type(&item.vis, &strategy_name, &doc));
+                strategies.push(niche_strategy_impl(
+                    item, &target, &member, ops, niche_zero, bound,
+                ));
+            }
+        }
+
+        if niche_repr || niche_mirror.is_some() {
+            if niche_ops.niche_type.is_none() {
+                let msg = "#[niche(repr)] and #[niche(mirror)] cannot be used with `with` or \
+                           `sentinel`";
+                return Err(syn::parse::Error::new_spanned(niche_field, msg));
+            }
+            // `as_some` reinterprets the repr struct as the original struct, which is only sound
+            // if the compiler lays out both of them the same way.
+            if !has_repr(item, "C")? && !is_repr_transparent(item)? {
+                let msg = "#[niche(repr)] and #[niche(mirror)] require the struct to be \
+                           #[repr(C)] or #[repr(transparent)]";
+                return Err(syn::parse::Error::new_spanned(item, msg));
+            }
+            let bound = niche_bound_override.as_ref();
+            let output = derive_struct_with_repr(
+                item,
+                &niche_field_path[0],
+                niche_field,
+                niche_zero,
+                niche_mirror.as_deref(),
+                bound,
+            )?;
+            let zero_niche = if niche_zero {
+                derive_zero_niche(item, &niche_field.ty, bound)
+            } else {
+                TokenStream2::new()
+            };
+            return Ok(quote! { #output #zero_niche #(#strategies)* });
+        }
+    }
+    let NicheFieldOps {
+        fill_with_none,
+        field_is_none,
+        niche_type: niche_field_type,
+        ..
+    } = niche_ops;
+
+    // With #[niche(bitwise)], we compare the niche field's bytes against the bytes of its `None`
+    // value, instead of calling its `is_none` method.  That's a comparison of a constant offset
+    // against a constant, which optimizes better when scanning many values.
+    // That's only sound if the field's `Output` type has no padding or uninitialized bytes, which
+    // the field type vouches for by implementing the unsafe `BitwiseNiche` trait.  We can't add
+    // that bound when we don't know the field's type, so we don't allow nested niche paths.
+    let mut bitwise_bound = None;
+    let field_is_none = if niche_bitwise {
+        if niche_by_path {
+            let msg = "#[niche(bitwise)] cannot be used with #[niche(path)]";
+            return Err(syn::parse::Error::new_spanned(item, msg));
+        }
+        let niche_field_type = match niche_field_type {
+            Some(niche_field_type) => niche_field_type,
+            None => {
+                let msg = "#[niche(bitwise)] cannot be used with `with` or `sentinel`";
+                return Err(syn::parse::Error::new_spanned(item, msg));
+            }
+        };
+        bitwise_bound = Some(parse_quote_spanned! {niche_field_type.span()=>
+            where #niche_field_type: ::controlled_option::BitwiseNiche
+        });
+        quote! { unsafe { ::controlled_option::struct_field_is_none_bitwise(field) } }
+    } else {
+        field_is_none
+    };
+
+    let mut where_clause = impl_where_clause(
+        ty_where_clause.as_ref(),
+        niche_field_type,
+        niche_bound_override.as_ref(),
+    );
+    if let Some(bitwise_bound) = bitwise_bound {
+        where_clause = Some(merge_where_clauses(where_clause, bitwise_bound));
+    }
+
+    // By default, we store the struct as a `MaybeUninit<Self>`, leaving everything but the niche
+    // field uninitialized in the `None` value.  With #[niche(output = "Type")], we store it as a
+    // type that you provide, which must have the same layout as the struct.  Since we have to
+    // return a fully initialized value of that type, we zero everything but the niche field.
+    // (#[niche(zero)] does the same in the default mode, so that `None` has a consistent bit
+    // pattern.)  A `MaybeUninit` never drops its contents, so with #[niche(drop)], we wrap it in
+    // a `DropRepr`, which does.  Otherwise, we assert that the struct doesn't need to be dropped,
+    // so that a `Some` value can't leak anything.
+    let new_output = if niche_zero {
+        quote! { zeroed }
+    } else {
+        quote! { uninit }
+    };
+    let zeroed_output = niche_output.is_some();
+    let output = match niche_output {
+        None if niche_drop => quote! {
+            impl #impl_generics ::controlled_option::DropNiche for #ty_name #ty_generics
+            #where_clause
+            {
+                #[inline]
+                fn repr_is_none(value: &::core::mem::MaybeUninit<Self>) -> bool {
+                    let ptr = value.as_ptr();
+                    let field = unsafe { ::core::ptr::addr_of!((*ptr)#(.#niche_field_path)*) };
+                    #field_is_none
+                }
+            }
+
+            impl #impl_generics ::controlled_option::Niche for #ty_name #ty_generics
+            #where_clause
+            {
+                type Output = ::controlled_option::DropRepr<Self>;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    let mut value = ::core::mem::MaybeUninit::<Self>::#new_output();
+                    let ptr = value.as_mut_ptr();
+                    let field = unsafe { ::core::ptr::addr_of_mut!((*ptr)#(.#niche_field_path)*) };
+                    #fill_with_none;
+                    ::controlled_option::DropRepr::from_repr(value)
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    <Self as ::controlled_option::DropNiche>::repr_is_none(value.as_repr())
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    ::controlled_option::DropRepr::new(value)
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    unsafe { value.into_inner() }
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { value.as_inner() }
+                }
+
+                #[inline]
+                unsafe fn write_some(value: Self, dest: *mut Self::Output) {
+                    ::controlled_option::write_struct_repr(value, dest)
+                }
+
+                #[inline]
+                unsafe fn read_some(src: *const Self::Output) -> Self {
+                    ::controlled_option::read_struct_repr(src)
+                }
+            }
+        },
+        None => quote! {
+            impl #impl_generics ::controlled_option::Niche for #ty_name #ty_generics
+            #where_clause
+            {
+                type Output = ::core::mem::MaybeUninit<Self>;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    let mut value = Self::Output::#new_output();
+                    let ptr = value.as_mut_ptr();
+                    let field = unsafe { ::core::ptr::addr_of_mut!((*ptr)#(.#niche_field_path)*) };
+                    #fill_with_none;
+                    value
                 }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    let ptr = value.as_ptr();
+                    let field = unsafe { ::core::ptr::addr_of!((*ptr)#(.#niche_field_path)*) };
+                    #field_is_none
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    ::controlled_option::assert_no_drop::<Self>();
+                    ::core::mem::MaybeUninit::new(value)
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    unsafe { value.assume_init() }
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { &*value.as_ptr() }
+                }
+
+                #[inline]
+                unsafe fn write_some(value: Self, dest: *mut Self::Output) {
+                    ::controlled_option::assert_no_drop::<Self>();
+                    ::controlled_option::write_struct_repr(value, dest)
+                }
+
+                #[inline]
+                unsafe fn read_some(src: *const Self::Output) -> Self {
+                    ::controlled_option::read_struct_repr(src)
+                }
+            }
+        },
+        Some(niche_output) => {
+            let where_clause =
+                merge_where_clauses(where_clause, niche_output_bound(item, &niche_output));
+            quote! {
+            impl #impl_generics ::controlled_option::Niche for #ty_name #ty_generics
+            #where_clause
+            {
+                type Output = #niche_output;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    let mut value = ::core::mem::MaybeUninit::<Self::Output>::zeroed();
+                    let ptr = value.as_mut_ptr() as *mut Self;
+                    let field = unsafe { ::core::ptr::addr_of_mut!((*ptr)#(.#niche_field_path)*) };
+                    #fill_with_none;
+                    unsafe { value.assume_init() }
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    let ptr = value as *const Self::Output as *const Self;
+                    let field = unsafe { ::core::ptr::addr_of!((*ptr)#(.#niche_field_path)*) };
+                    #field_is_none
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    ::controlled_option::assert_no_drop::<Self>();
+                    unsafe { ::controlled_option::convert_struct_repr(value) }
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    unsafe { ::controlled_option::convert_struct_repr(value) }
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { &*(value as *const Self::Output as *const Self) }
+                }
+
+                #[inline]
+                unsafe fn write_some(value: Self, dest: *mut Self::Output) {
+                    ::controlled_option::assert_no_drop::<Self>();
+                    ::controlled_option::write_struct_repr(value, dest)
+                }
+
+                #[inline]
+                unsafe fn read_some(src: *const Self::Output) -> Self {
+                    ::controlled_option::read_struct_repr(src)
+                }
+            }
+            }
+        }
+    };
+    // When everything but the niche field is zeroed, the `None` value is all-zero bytes if the
+    // niche field's niche is.
+    let zero_niche = match niche_field_type {
+        Some(niche_field_type) if niche_zero || zeroed_output => {
+            derive_zero_niche(item, niche_field_type, niche_bound_override.as_ref())
+        }
+        _ => TokenStream2::new(),
+    };
+    Ok(quote! { #output #zero_niche #(#strategies)* })
+}
+
+/// Generates the `Niche` implementation for a struct whose `None` value is given by a
+/// #[niche(none_value = expr)] attribute.
+fn derive_struct_with_none_value(
+    item: &ItemStruct,
+    none_value: &Expr,
+    niche_output: Option<&Type>,
+    bound: Option<&WhereClause>,
+) -> TokenStream2 {
+    let ty_name = &item.ident;
+    let (impl_generics, ty_generics, ty_where_clause) = item.generics.split_for_impl();
+
+    // The `None` value can span several fields, so we can't check for it by looking at any one
+    // field.  Instead, it must be a valid value of the `Output` type, which we compare against
+    // with `PartialEq`.  That type is the struct itself, unless you've chosen a different one
+    // with #[niche(output = "Type")], in which case we convert between the two by reinterpreting
+    // their bytes.  The expression must be constant, so we can also implement `ConstNiche`.
+    let (output_type, into_some, from_some, as_some) = match niche_output {
+        Some(output) => (
+            quote! { #output },
+            quote! {
+                ::controlled_option::assert_no_drop::<Self>();
+                unsafe { ::controlled_option::convert_struct_repr(value) }
+            },
+            quote! { unsafe { ::controlled_option::convert_struct_repr(value) } },
+            quote! { unsafe { &*(value as *const Self::Output as *const Self) } },
+        ),
+        None => (
+            quote! { Self },
+            quote! { value },
+            quote! { value },
+            quote! { value },
+        ),
+    };
+    let eq_bound = match niche_output {
+        Some(output) => quote_spanned! {output.span()=> #output: ::core::cmp::PartialEq },
+        None => quote! { #ty_name #ty_generics: ::core::cmp::PartialEq },
+    };
+    let mut where_clause = merge_where_clauses(
+        impl_where_clause(ty_where_clause, None, bound),
+        parse_quote! { where #eq_bound },
+    );
+    if let Some(output) = niche_output {
+        where_clause = merge_where_clauses(Some(where_clause), niche_output_bound(item, output));
+    }
+
+    quote! {
+        impl #impl_generics ::controlled_option::Niche for #ty_name #ty_generics
+        #where_clause
+        {
+            type Output = #output_type;
+
+            #[inline]
+            fn none() -> Self::Output {
+                <Self as ::controlled_option::ConstNiche>::NONE
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                *value == <Self as ::controlled_option::ConstNiche>::NONE
+            }
+
+            #[inline]
+            fn into_some(value: Self) -> Self::Output {
+                #into_some
+            }
+
+            #[inline]
+            fn from_some(value: Self::Output) -> Self {
+                #from_some
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &Self {
+                #as_some
+            }
+        }
+
+        unsafe impl #impl_generics ::controlled_option::ConstNiche for #ty_name #ty_generics
+        #where_clause
+        {
+            const NONE: Self::Output = #none_value;
+        }
+    }
+}
+
+/// Generates a `ZeroNiche` impl for a struct whose `None` value is all-zero bytes whenever the
+/// niche field's type has an all-zero niche.  That's the case when everything other than the
+/// niche field is zeroed, or when the struct delegates to the niche field's `Output` type.
+fn derive_zero_niche(
+    item: &ItemStruct,
+    niche_type: &Type,
+    bound: Option<&WhereClause>,
+) -> TokenStream2 {
+    let ty_name = &item.ident;
+    let (impl_generics, ty_generics, ty_where_clause) = item.generics.split_for_impl();
+    // The field type might not be `ZeroNiche`, and if it's not generic, the compiler would reject
+    // a `FieldType: ZeroNiche` bound outright.  Quantifying over an unused lifetime defers the
+    // check until someone actually needs the struct to be `ZeroNiche`.
+    let zero_bound = quote_spanned! {niche_type.span()=>
+        for<'__niche> #niche_type: ::controlled_option::ZeroNiche
+    };
+    let where_clause = merge_where_clauses(
+        impl_where_clause(ty_where_clause, None, bound),
+        parse_quote! { where #zero_bound },
+    );
+    quote! {
+        unsafe impl #impl_generics ::controlled_option::ZeroNiche for #ty_name #ty_generics
+        #where_clause
+        {
+        }
+    }
+}
+
+/// Generates a unit struct to use as a `NicheStrategy`.
+fn niche_strategy_type(vis: &Visibility, name: &Ident, doc: &str) -> TokenStream2 {
+    quote! {
+        #[doc = #doc]
+        #[derive(
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::fmt::Debug,
+            ::core::default::Default,
+        )]
+        #vis struct #name;
+    }
+}
+
+/// Generates a `NicheStrategy` impl that uses one of the candidate niche fields of a struct.
+/// This always stores the struct as a `MaybeUninit<Self>`, just like the default mode of the
+/// `Niche` derive.  The strategy is for `target`, which is either `item` itself, or the remote
+/// type that `item` describes.
+fn niche_strategy_impl(
+    item: &ItemStruct,
+    target: &TokenStream2,
+    member: &Member,
+    ops: NicheFieldOps,
+    niche_zero: bool,
+    bound: Option<&WhereClause>,
+) -> TokenStream2 {
+    let (impl_generics, _, ty_where_clause) = item.generics.split_for_impl();
+    let NicheFieldOps {
+        fill_with_none,
+        field_is_none,
+        niche_type,
+        strategy,
+    } = ops;
+    let where_clause = impl_where_clause(ty_where_clause, niche_type, bound);
+    let new_output = if niche_zero {
+        quote! { zeroed }
+    } else {
+        quote! { uninit }
+    };
+
+    quote! {
+        impl #impl_generics ::controlled_option::NicheStrategy<#target> for #strategy
+        #where_clause
+        {
+            type Output = ::core::mem::MaybeUninit<#target>;
+
+            #[inline]
+            fn none() -> Self::Output {
+                let mut value = Self::Output::#new_output();
+                let ptr = value.as_mut_ptr();
+                let field = unsafe { ::core::ptr::addr_of_mut!((*ptr).#member) };
+                #fill_with_none;
+                value
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                let ptr = value.as_ptr();
+                let field = unsafe { ::core::ptr::addr_of!((*ptr).#member) };
+                #field_is_none
+            }
+
+            #[inline]
+            fn into_some(value: #target) -> Self::Output {
+                ::controlled_option::assert_no_drop::<#target>();
+                ::core::mem::MaybeUninit::new(value)
+            }
+
+            #[inline]
+            fn from_some(value: Self::Output) -> #target {
+                unsafe { value.assume_init() }
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &#target {
+                unsafe { &*value.as_ptr() }
+            }
+
+            #[inline]
+            unsafe fn write_some(value: #target, dest: *mut Self::Output) {
+                ::controlled_option::assert_no_drop::<#target>();
+                ::controlled_option::write_struct_repr(value, dest)
+            }
+
+            #[inline]
+            unsafe fn read_some(src: *const Self::Output) -> #target {
+                ::controlled_option::read_struct_repr(src)
+            }
+        }
+    }
+}
+
+fn derive_struct_with_repr(
+    item: &ItemStruct,
+    niche_field_name: &Member,
+    niche_field: &Field,
+    niche_zero: bool,
+    mirror: Option<&[Path]>,
+    bound: Option<&WhereClause>,
+) -> Result<TokenStream2, syn::parse::Error> {
+    let ty_name = &item.ident;
+    let ty_vis = &item.vis;
+    let ty_params = &item.generics;
+    let (impl_generics, ty_generics, ty_where_clause) = item.generics.split_for_impl();
+    let niche_field_type = &niche_field.ty;
+    let where_clause = impl_where_clause(ty_where_clause, Some(niche_field_type), bound);
+
+    // With #[niche(repr)], we generate a parallel `FooNicheRepr` struct, which is the same as the
+    // original struct, except that the niche field is stored as its `Output` type.  That lets us
+    // use the field's `Niche` implementation to convert it, instead of reinterpreting its bytes.
+    // The struct is stored as a `MaybeUninit` of the repr struct, and all of the other fields are
+    // moved over as-is.  (`as_some` still needs to reinterpret the repr struct as the original,
+    // so their layouts must match.)
+    //
+    // With #[niche(mirror)], the repr struct is the `Output` type itself, without a `MaybeUninit`
+    // wrapper.  The `None` value fills in every other field with its default value, so that it's
+    // fully initialized, and the repr struct can derive whatever traits you ask for.
+    let repr_name = format_ident!("{}NicheRepr", ty_name);
+    let repr_attrs = item.attrs.iter().filter(|attr| attr.path.is_ident("repr"));
+    let repr_doc = match mirror {
+        Some(derives) => {
+            let doc = format!("The representation of an optional [`{}`].", ty_name);
+            let derives = if derives.is_empty() {
+                TokenStream2::new()
+            } else {
+                quote! { #[derive(#(#derives),*)] }
             };
-            output.into()
+            quote! { #[doc = #doc] #derives }
+        }
+        None => quote! { #[doc(hidden)] },
+    };
+    let members = item
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| field_member(idx, field))
+        .collect::<Vec<_>>();
+    let bindings = (0..members.len())
+        .map(|idx| format_ident!("__field{}", idx))
+        .collect::<Vec<_>>();
+    let repr_fields = item.fields.iter().zip(&members).map(|(field, member)| {
+        let vis = &field.vis;
+        let ty = if member == niche_field_name {
+            quote! { <#niche_field_type as ::controlled_option::Niche>::Output }
+        } else {
+            let ty = &field.ty;
+            quote! { #ty }
+        };
+        match &field.ident {
+            Some(ident) => quote! { #vis #ident: #ty },
+            None => quote! { #vis #ty },
+        }
+    });
+    let repr_def = match &item.fields {
+        Fields::Named(_) => quote! {
+            #repr_doc
+            #(#repr_attrs)*
+            #ty_vis struct #repr_name #ty_params #where_clause {
+                #(#repr_fields),*
+            }
+        },
+        _ => quote! {
+            #repr_doc
+            #(#repr_attrs)*
+            #ty_vis struct #repr_name #ty_params ( #(#repr_fields),* ) #where_clause;
+        },
+    };
+    let into_repr = members.iter().zip(&bindings).map(|(member, binding)| {
+        if member == niche_field_name {
+            quote! {
+                #member: <#niche_field_type as ::controlled_option::Niche>::into_some(#binding)
+            }
+        } else {
+            quote! { #member: #binding }
+        }
+    });
+    let from_repr = members.iter().zip(&bindings).map(|(member, binding)| {
+        if member == niche_field_name {
+            quote! {
+                #member: <#niche_field_type as ::controlled_option::Niche>::from_some(#binding)
+            }
+        } else {
+            quote! { #member: #binding }
+        }
+    });
+
+    if mirror.is_some() {
+        let none_fields = item.fields.iter().zip(&members).map(|(field, member)| {
+            if member == niche_field_name {
+                quote! { #member: <#niche_field_type as ::controlled_option::Niche>::none() }
+            } else {
+                let ty = &field.ty;
+                quote! { #member: <#ty as ::core::default::Default>::default() }
+            }
+        });
+        // Every other field needs a default value.  We give each bound the field type's span, so
+        // that the compiler's error points at any field that doesn't have one.
+        let default_bounds = item
+            .fields
+            .iter()
+            .zip(&members)
+            .filter(|(_, member)| *member != niche_field_name)
+            .map(|(field, _)| {
+                let ty = &field.ty;
+                quote_spanned! {ty.span()=> #ty: ::core::default::Default }
+            });
+        let impl_where_clause = merge_where_clauses(
+            where_clause.clone(),
+            parse_quote! { where #(#default_bounds),* },
+        );
+        let output = quote! {
+            #repr_def
+
+            impl #impl_generics ::controlled_option::Niche for #ty_name #ty_generics
+            #impl_where_clause
+            {
+                type Output = #repr_name #ty_generics;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    #repr_name { #(#none_fields),* }
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    <#niche_field_type as ::controlled_option::Niche>::is_none(
+                        &value.#niche_field_name,
+                    )
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    let Self { #(#members: #bindings),* } = value;
+                    #repr_name { #(#into_repr),* }
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    let #repr_name { #(#members: #bindings),* } = value;
+                    Self { #(#from_repr),* }
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { ::controlled_option::struct_mirror_as_some(value) }
+                }
+            }
+        };
+        return Ok(output);
+    }
+
+    let new_output = if niche_zero {
+        quote! { zeroed }
+    } else {
+        quote! { uninit }
+    };
+
+    let output = quote! {
+        #repr_def
+
+        impl #impl_generics ::controlled_option::Niche for #ty_name #ty_generics
+        #where_clause
+        {
+            type Output = ::core::mem::MaybeUninit<#repr_name #ty_generics>;
+
+            #[inline]
+            fn none() -> Self::Output {
+                let mut value = Self::Output::#new_output();
+                let ptr = value.as_mut_ptr();
+                unsafe {
+                    ::core::ptr::addr_of_mut!((*ptr).#niche_field_name)
+                        .write(<#niche_field_type as ::controlled_option::Niche>::none())
+                };
+                value
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                let ptr = value.as_ptr();
+                <#niche_field_type as ::controlled_option::Niche>::is_none(unsafe {
+                    &*::core::ptr::addr_of!((*ptr).#niche_field_name)
+                })
+            }
+
+            #[inline]
+            fn into_some(value: Self) -> Self::Output {
+                ::controlled_option::assert_no_drop::<Self>();
+                let Self { #(#members: #bindings),* } = value;
+                ::core::mem::MaybeUninit::new(#repr_name { #(#into_repr),* })
+            }
+
+            #[inline]
+            fn from_some(value: Self::Output) -> Self {
+                let #repr_name { #(#members: #bindings),* } = unsafe { value.assume_init() };
+                Self { #(#from_repr),* }
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &Self {
+                unsafe { ::controlled_option::struct_repr_as_some(value) }
+            }
         }
+    };
+    Ok(output)
+}
+
+fn derive_union(item: &ItemUnion) -> Result<TokenStream2, syn::parse::Error> {
+    // A union's niche member works just like a struct's niche field: `none` fills in that member
+    // with its niche value, and `is_none` checks that member.  The other members overlap it, so
+    // you must ensure that a `Some` value never has the niche value in the niche member's bytes.
+    // Everything else about the derive is the same as for a struct with named fields, except
+    // that we can't generate a repr struct, since that requires moving every field.
+    if let Some(arg) = niche_args(&item.attrs)?
+        .into_iter()
+        .find(|arg| arg.name == "repr" || arg.name == "mirror")
+    {
+        let msg = format!("#[niche({})] cannot be used with a union", arg.name);
+        return Err(syn::parse::Error::new_spanned(&arg.name, msg));
+    }
+
+    // Reading the niche member is only sound if it's initialized no matter which member is
+    // active, which we can't check, so the union must also implement the unsafe `NicheUnion`
+    // trait.  We add that as a bound on the union's own where clause, so that it can't be
+    // replaced by a #[niche(bound)] attribute.
+    let ty_name = &item.ident;
+    let (_, ty_generics, _) = item.generics.split_for_impl();
+    let union_bound: WhereClause = parse_quote_spanned! {ty_name.span()=>
+        where #ty_name #ty_generics: ::controlled_option::NicheUnion
+    };
+    let mut generics = item.generics.clone();
+    generics.where_clause = Some(merge_where_clauses(generics.where_clause, union_bound));
+    let item = ItemStruct {
+        attrs: item.attrs.clone(),
+        vis: item.vis.clone(),
+        struct_token: Default::default(),
+        ident: item.ident.clone(),
+        generics,
+        fields: Fields::Named(item.fields.clone()),
+        semi_token: None,
+    };
+    derive_struct(&item, "a union")
+}
+
+fn derive_transparent_struct(
+    item: &ItemStruct,
+    bound: Option<&WhereClause>,
+) -> Result<Option<TokenStream2>, syn::parse::Error> {
+    let ty_name = &item.ident;
+    let (impl_generics, ty_generics, ty_where_clause) = item.generics.split_for_impl();
+
+    // We can only delegate if there's exactly one field that isn't a `PhantomData`, and if that
+    // field doesn't need any special #[niche] handling.
+    let mut inner = None;
+    for (idx, field) in item.fields.iter().enumerate() {
+        if field_is_phantom(field) {
+            continue;
+        }
+        if inner.is_some() || !niche_args(&field.attrs)?.is_empty() {
+            return Ok(None);
+        }
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => idx.into(),
+        };
+        inner = Some((member, field));
+    }
+    let (inner_name, inner_field) = match inner {
+        Some(inner) => inner,
+        None => return Ok(None),
+    };
+    let inner_type = &inner_field.ty;
+
+    // When we rebuild the struct in `from_some`, every other field is a `PhantomData`.
+    let field_values = item.fields.iter().enumerate().map(|(idx, field)| {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => idx.into(),
+        };
+        if member == inner_name {
+            quote! { #member: <#inner_type as ::controlled_option::Niche>::from_some(value) }
+        } else {
+            quote! { #member: ::core::marker::PhantomData }
+        }
+    });
+
+    let where_clause = impl_where_clause(ty_where_clause, Some(inner_type), bound);
+    let zero_niche = derive_zero_niche(item, inner_type, bound);
+
+    let output = quote! {
+        impl #impl_generics ::controlled_option::Niche for #ty_name #ty_generics
+        #where_clause
+        {
+            type Output = <#inner_type as ::controlled_option::Niche>::Output;
+
+            #[inline]
+            fn none() -> Self::Output {
+                <#inner_type as ::controlled_option::Niche>::none()
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                <#inner_type as ::controlled_option::Niche>::is_none(value)
+            }
+
+            #[inline]
+            fn into_some(value: Self) -> Self::Output {
+                <#inner_type as ::controlled_option::Niche>::into_some(value.#inner_name)
+            }
+
+            #[inline]
+            fn from_some(value: Self::Output) -> Self {
+                Self { #(#field_values),* }
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &Self {
+                let inner = <#inner_type as ::controlled_option::Niche>::as_some(value);
+                unsafe { &*(inner as *const #inner_type as *const Self) }
+            }
+        }
+
+        #zero_niche
+    };
+    Ok(Some(output))
+}
+
+fn derive_enum(item: &ItemEnum) -> Result<TokenStream2, syn::parse::Error> {
+    let mut discriminant = None;
+    for arg in niche_args(&item.attrs)? {
+        match (arg.name.to_string().as_str(), &arg.value) {
+            ("discriminant", Some(value)) => discriminant = Some(value.clone()),
+            ("alias", Some(_)) => {}
+            _ => {
+                let known = [("alias", true), ("discriminant", true)];
+                return Err(niche_arg_error(&arg, "an enum", &known));
+            }
+        }
+    }
+
+    let mut niche_variants = item.variants.iter().filter(variant_is_niche);
+    let niche_variant = niche_variants.next();
+    if let Some(duplicate) = niche_variants.next() {
+        let msg = "#[derive(Niche)] only allows one variant to be marked #[niche]";
+        return Err(syn::parse::Error::new_spanned(duplicate, msg));
+    }
+    if let Some(variant) = niche_variant {
+        if let Some(arg) = niche_args(&variant.attrs)?.first() {
+            let msg = "#[niche] on a variant does not take any arguments";
+            return Err(syn::parse::Error::new_spanned(&arg.name, msg));
+        }
+    }
+
+    match niche_variant {
+        Some(niche_variant) if discriminant.is_some() => {
+            let msg = "#[niche(discriminant)] cannot be used with a #[niche] variant";
+            Err(syn::parse::Error::new_spanned(niche_variant, msg))
+        }
+        Some(niche_variant) => derive_enum_with_niche_variant(item, niche_variant),
+        None => derive_enum_with_unused_discriminant(item, discriminant.as_ref()),
+    }
+}
+
+fn derive_enum_with_niche_variant(
+    item: &ItemEnum,
+    niche_variant: &Variant,
+) -> Result<TokenStream2, syn::parse::Error> {
+    let ty_name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    // The variant marked #[niche] must be a unit variant, since we need to be able to create it
+    // out of thin air in `none`.  That variant is then a valid instance of the enum type, so we
+    // can use the enum type itself as the `Output` type.
+    if !matches!(niche_variant.fields, Fields::Unit) {
+        let msg = "#[derive(Niche)] requires the #[niche] variant to be a unit variant";
+        return Err(syn::parse::Error::new_spanned(niche_variant, msg));
+    }
+    let niche_variant_name = &niche_variant.ident;
+
+    let output = quote! {
+        impl #impl_generics ::controlled_option::Niche for #ty_name #ty_generics
+        #where_clause
+        {
+            type Output = Self;
+
+            #[inline]
+            fn none() -> Self::Output {
+                Self::#niche_variant_name
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                ::core::matches!(value, Self::#niche_variant_name)
+            }
+
+            #[inline]
+            fn into_some(value: Self) -> Self::Output {
+                debug_assert!(
+                    !::core::matches!(value, Self::#niche_variant_name),
+                    "cannot use the #[niche] variant as a Some value",
+                );
+                value
+            }
+
+            #[inline]
+            fn from_some(value: Self::Output) -> Self {
+                value
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &Self {
+                value
+            }
+        }
+
+        unsafe impl #impl_generics ::controlled_option::ConstNiche for #ty_name #ty_generics
+        #where_clause
+        {
+            const NONE: Self::Output = Self::#niche_variant_name;
+        }
+    };
+    Ok(output)
+}
+
+/// Returns the range of values of a primitive integer type that we can use as an enum's `repr`.
+/// We only allow the portion of the `usize` and `isize` ranges that is valid on every platform.
+fn repr_range(repr: &Ident) -> Option<(i128, i128)> {
+    let range = match repr.to_string().as_str() {
+        "u8" => (0, u8::MAX as i128),
+        "u16" => (0, u16::MAX as i128),
+        "u32" | "usize" => (0, u32::MAX as i128),
+        "u64" => (0, u64::MAX as i128),
+        "i8" => (i8::MIN as i128, i8::MAX as i128),
+        "i16" => (i16::MIN as i128, i16::MAX as i128),
+        "i32" | "isize" => (i32::MIN as i128, i32::MAX as i128),
+        "i64" => (i64::MIN as i128, i64::MAX as i128),
+        _ => return None,
+    };
+    Some(range)
+}
+
+/// Returns the primitive integer type from an enum's `#[repr]` attribute, if it has one.
+fn enum_repr(item: &ItemEnum) -> Result<Option<Ident>, syn::parse::Error> {
+    for attr in &item.attrs {
+        if !attr.path.is_ident("repr") {
+            continue;
+        }
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in &list.nested {
+                if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                    if let Some(ident) = path.get_ident() {
+                        if repr_range(ident).is_some() {
+                            return Ok(Some(ident.clone()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Evaluates an explicit enum discriminant.  We can only handle integer literals, possibly
+/// negated, since we can't evaluate arbitrary constant expressions in a proc macro.
+fn eval_discriminant(expr: &Expr) -> Result<i128, syn::parse::Error> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse::<i128>(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => Ok(-eval_discriminant(expr)?),
+        Expr::Group(group) => eval_discriminant(&group.expr),
+        Expr::Paren(paren) => eval_discriminant(&paren.expr),
         _ => {
-            let msg = "#[derive(Niche)] is only supported on struct types";
-            syn::parse::Error::new_spanned(item, msg)
-                .to_compile_error()
-                .into()
+            let msg = "#[derive(Niche)] requires enum discriminants to be integer literals";
+            Err(syn::parse::Error::new_spanned(expr, msg))
         }
     }
 }
+
+/// Returns the discriminant of each variant of a field-less enum.
+fn enum_discriminants(item: &ItemEnum) -> Result<Vec<i128>, syn::parse::Error> {
+    let mut discriminants = Vec::with_capacity(item.variants.len());
+    let mut next = 0;
+    for variant in &item.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            let msg = "#[derive(Niche)] requires a unit variant marked #[niche], \
+                       or an enum whose variants are all unit variants";
+            return Err(syn::parse::Error::new_spanned(variant, msg));
+        }
+        let discriminant = match &variant.discriminant {
+            Some((_, expr)) => eval_discriminant(expr)?,
+            None => next,
+        };
+        discriminants.push(discriminant);
+        next = discriminant + 1;
+    }
+    Ok(discriminants)
+}
+
+/// Returns a literal token for an integer, which might be negative.
+fn int_literal(value: i128) -> TokenStream2 {
+    let literal = Literal::u128_unsuffixed(value.unsigned_abs());
+    if value < 0 {
+        quote! { -#literal }
+    } else {
+        quote! { #literal }
+    }
+}
+
+fn derive_enum_with_unused_discriminant(
+    item: &ItemEnum,
+    discriminant: Option<&Expr>,
+) -> Result<TokenStream2, syn::parse::Error> {
+    let ty_name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    // A field-less enum with an explicit integer representation is stored as that integer, and
+    // any discriminant value that isn't used by one of the variants can be the niche.  Unless
+    // you've chosen one with #[niche(discriminant = N)], we choose the largest unused value.
+    let discriminants = enum_discriminants(item)?;
+    let repr = match enum_repr(item)? {
+        Some(repr) => repr,
+        None => {
+            let msg = "#[derive(Niche)] requires a unit variant marked #[niche], \
+                       or a #[repr] attribute with a primitive integer type";
+            return Err(syn::parse::Error::new_spanned(item, msg));
+        }
+    };
+    let (min, max) = repr_range(&repr).unwrap();
+    let niche = match discriminant {
+        Some(expr) => {
+            let niche = eval_discriminant(expr)?;
+            if niche < min || niche > max {
+                let msg = format!("#[niche(discriminant)] is out of range for {}", repr);
+                return Err(syn::parse::Error::new_spanned(expr, msg));
+            }
+            if discriminants.contains(&niche) {
+                let msg = "#[niche(discriminant)] is already used by one of the variants";
+                return Err(syn::parse::Error::new_spanned(expr, msg));
+            }
+            niche
+        }
+        None => match (min..=max)
+            .rev()
+            .find(|value| !discriminants.contains(value))
+        {
+            Some(niche) => niche,
+            None => {
+                let msg = "#[derive(Niche)] requires at least one unused discriminant value";
+                return Err(syn::parse::Error::new_spanned(item, msg));
+            }
+        },
+    };
+    // If we chose zero as the niche, the `None` value is all-zero bytes.
+    let zero_niche = if niche == 0 {
+        quote! {
+            unsafe impl #impl_generics ::controlled_option::ZeroNiche for #ty_name #ty_generics
+            #where_clause
+            {
+            }
+        }
+    } else {
+        TokenStream2::new()
+    };
+    let niche = int_literal(niche);
+
+    let output = quote! {
+        impl #impl_generics ::controlled_option::Niche for #ty_name #ty_generics
+        #where_clause
+        {
+            type Output = #repr;
+
+            #[inline]
+            fn none() -> Self::Output {
+                #niche
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                *value == #niche
+            }
+
+            #[inline]
+            fn into_some(value: Self) -> Self::Output {
+                value as #repr
+            }
+
+            #[inline]
+            fn from_some(value: Self::Output) -> Self {
+                unsafe { ::core::mem::transmute::<#repr, Self>(value) }
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &Self {
+                unsafe { &*(value as *const #repr as *const Self) }
+            }
+        }
+
+        unsafe impl #impl_generics ::controlled_option::ConstNiche for #ty_name #ty_generics
+        #where_clause
+        {
+            const NONE: Self::Output = #niche;
+        }
+
+        #zero_niche
+    };
+    Ok(output)
+}
+
+/// Returns the `T` in an `Option<T>` type, if that's what `ty` is.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[proc_macro_attribute]
+pub fn controlled(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = TokenStream2::from(args);
+    let mut item = parse_macro_input!(input as ItemStruct);
+    let result = if args.is_empty() {
+        controlled_struct(&mut item)
+    } else {
+        let msg = "#[controlled] does not take any arguments";
+        Err(syn::parse::Error::new_spanned(args, msg))
+    };
+    match result {
+        Ok(accessors) => quote! { #item #accessors }.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn controlled_struct(item: &mut ItemStruct) -> Result<TokenStream2, syn::parse::Error> {
+    if !matches!(item.fields, Fields::Named(_)) {
+        let msg = "#[controlled] is only supported on structs with named fields";
+        return Err(syn::parse::Error::new_spanned(&item, msg));
+    }
+
+    // Each field marked #[controlled] must be an `Option<T>`, which we replace with a
+    // `ControlledOption<T>`.  Since that changes the field's type, we also generate accessors
+    // that use a plain `Option`, so that code using the struct doesn't need to know about the
+    // different representation.
+    let mut accessors = Vec::new();
+    for field in item.fields.iter_mut() {
+        let mut marked = false;
+        for attr in &field.attrs {
+            if attr.path.is_ident("controlled") {
+                if !attr.tokens.is_empty() {
+                    let msg = "#[controlled] on a field does not take any arguments";
+                    return Err(syn::parse::Error::new_spanned(attr, msg));
+                }
+                marked = true;
+            }
+        }
+        if !marked {
+            continue;
+        }
+        field.attrs.retain(|attr| !attr.path.is_ident("controlled"));
+
+        let inner = match option_inner_type(&field.ty) {
+            Some(inner) => inner.clone(),
+            None => {
+                let msg = "#[controlled] field must have an `Option<T>` type";
+                return Err(syn::parse::Error::new_spanned(&field.ty, msg));
+            }
+        };
+        field.ty = parse_quote! { ::controlled_option::ControlledOption<#inner> };
+
+        // An attribute macro sees the fields before any `#[cfg]` attributes are evaluated, so the
+        // accessors must be conditional on the same things as the field.
+        let cfgs = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("cfg"))
+            .collect::<Vec<_>>();
+        let vis = &field.vis;
+        let name = field.ident.as_ref().unwrap();
+        let set_name = format_ident!("set_{}", name);
+        let take_name = format_ident!("take_{}", name);
+        let get_doc = format!(
+            "Returns the value of the `{}` field, if there is one.",
+            name
+        );
+        let set_doc = format!("Sets the value of the `{}` field.", name);
+        let take_doc = format!(
+            "Takes the value of the `{}` field, leaving `None` in its place.",
+            name
+        );
+        accessors.push(quote! {
+            #(#cfgs)*
+            #[doc = #get_doc]
+            #[inline]
+            #vis fn #name(&self) -> ::core::option::Option<&#inner> {
+                self.#name.as_option()
+            }
+
+            #(#cfgs)*
+            #[doc = #set_doc]
+            #[inline]
+            #vis fn #set_name(&mut self, value: ::core::option::Option<#inner>) {
+                self.#name = ::controlled_option::ControlledOption::from_option(value);
+            }
+
+            #(#cfgs)*
+            #[doc = #take_doc]
+            #[inline]
+            #vis fn #take_name(&mut self) -> ::core::option::Option<#inner> {
+                ::core::mem::take(&mut self.#name).into_option()
+            }
+        });
+    }
+    if accessors.is_empty() {
+        let msg = "#[controlled] requires at least one field marked #[controlled]";
+        return Err(syn::parse::Error::new_spanned(&item, msg));
+    }
+
+    let ty_name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #ty_name #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    })
+}
+
+#[proc_macro]
+pub fn derive_niche_for(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemStruct);
+    match derive_remote(&item) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Returns how to fill in and check the niche of a candidate field in a derive_niche_for!
+/// description.
+fn remote_field_ops<'a>(
+    item: &ItemStruct,
+    field: &'a Field,
+) -> Result<NicheFieldOps<'a>, syn::parse::Error> {
+    let mut ops = niche_field_ops(field)?;
+    if ops.strategy.is_some() {
+        let msg = "#[niche(strategy)] cannot be used in derive_niche_for!; \
+                   the strategy is the type being described";
+        return Err(syn::parse::Error::new_spanned(field, msg));
+    }
+    ops.strategy = Some(item.ident.clone());
+    Ok(ops)
+}
+
+fn derive_remote(item: &ItemStruct) -> Result<TokenStream2, syn::parse::Error> {
+    let mut remote = None;
+    let mut niche_zero = false;
+    let mut niche_bound_override = None;
+    for arg in niche_args(&item.attrs)? {
+        match (arg.name.to_string().as_str(), &arg.value) {
+            ("remote", Some(value)) => {
+                remote = Some(str_lit(value, "#[niche(remote)]")?.parse::<Type>()?)
+            }
+            ("zero", None) => niche_zero = true,
+            ("bound", Some(value)) => niche_bound_override = Some(parse_bound(value)?),
+            _ => {
+                let known = [("remote", true), ("zero", false), ("bound", true)];
+                return Err(niche_arg_error(&arg, "derive_niche_for!", &known));
+            }
+        }
+    }
+    let remote = match remote {
+        Some(remote) => remote,
+        None => {
+            let msg = "derive_niche_for! requires a #[niche(remote = \"Type\")] attribute";
+            return Err(syn::parse::Error::new_spanned(item, msg));
+        }
+    };
+
+    // We can't implement `Niche` for a type from another crate, but we can implement a
+    // `NicheStrategy` for it, since the strategy type is local.
+    let target = quote! { #remote };
+    let doc = format!(
+        "A `NicheStrategy` for `{}`.",
+        target.to_string().replace(' ', "")
+    );
+    let mut output = niche_strategy_type(&item.vis, &item.ident, &doc);
+    let bound = niche_bound_override.as_ref();
+
+    // Unlike a derive macro, a function-like macro sees the fields before any `#[cfg]`
+    // attributes are evaluated.  If any of the candidate niche fields are conditional, we can't
+    // choose one here; instead, we generate an impl for each candidate, and use `#[cfg]`
+    // attributes to enable the one that the derive would have chosen in each configuration.
+    let candidates = item
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| item.fields.len() == 1 || field_is_niche(field))
+        .map(|(idx, field)| {
+            let cfg = field_cfg(field)?;
+            Ok((field_member(idx, field), field, niche_priority(field)?, cfg))
+        })
+        .collect::<Result<Vec<_>, syn::parse::Error>>()?;
+    if candidates.iter().all(|(_, _, _, cfg)| cfg.is_none()) {
+        let (niche_field_name, niche_field) = match find_niche_field(&item.fields)? {
+            Some(found) => found,
+            None => {
+                let msg = "derive_niche_for! requires a field marked #[niche]";
+                return Err(syn::parse::Error::new_spanned(item, msg));
+            }
+        };
+        let ops = remote_field_ops(item, niche_field)?;
+        output.extend(niche_strategy_impl(
+            item,
+            &target,
+            &niche_field_name,
+            ops,
+            niche_zero,
+            bound,
+        ));
+    } else {
+        let always = quote! { all() };
+        let cfg_of = |cfg: &Option<TokenStream2>| cfg.clone().unwrap_or_else(|| always.clone());
+        for (idx, (member, field, priority, cfg)) in candidates.iter().enumerate() {
+            let cfg = cfg_of(cfg);
+            let higher = candidates
+                .iter()
+                .filter(|(_, _, other, _)| other > priority)
+                .map(|(_, _, _, cfg)| cfg_of(cfg))
+                .collect::<Vec<_>>();
+            let tied = candidates
+                .iter()
+                .enumerate()
+                .filter(|(other_idx, (_, _, other, _))| other_idx != &idx && other == priority)
+                .map(|(_, (_, _, _, cfg))| cfg_of(cfg))
+                .collect::<Vec<_>>();
+            let ops = remote_field_ops(item, field)?;
+            let strategy_impl = niche_strategy_impl(item, &target, member, ops, niche_zero, bound);
+            output.extend(quote! {
+                #[cfg(all(#cfg, not(any(#(#higher),*)), not(any(#(#tied),*))))]
+                #strategy_impl
+            });
+            // Two candidates with the same priority can't both be enabled at the same time.
+            for (_, later, _, later_cfg) in candidates[idx + 1..]
+                .iter()
+                .filter(|(_, _, other, _)| other == priority)
+            {
+                let later_cfg = cfg_of(later_cfg);
+                let msg = "derive_niche_for! found more than one #[niche] field with the \
+                           highest priority; use #[niche(priority = N)] to choose between them";
+                output.extend(quote_spanned! {later.span()=>
+                    #[cfg(all(#cfg, #later_cfg, not(any(#(#higher),*))))]
+                    ::core::compile_error!(#msg);
+                });
+            }
+        }
+        let all_cfgs = candidates.iter().map(|(_, _, _, cfg)| cfg_of(cfg));
+        let msg = "derive_niche_for! requires a field marked #[niche]";
+        output.extend(quote_spanned! {item.ident.span()=>
+            #[cfg(not(any(#(#all_cfgs),*)))]
+            ::core::compile_error!(#msg);
+        });
+    }
+
+    // Make sure that the description matches the remote type, by checking that each of the
+    // described fields exists, with the described type.  (This requires the fields to be
+    // visible from here, which is also required for the niche field, since the strategy needs
+    // to access it.)
+    let (impl_generics, _, ty_where_clause) = item.generics.split_for_impl();
+    let field_checks = item.fields.iter().enumerate().map(|(idx, field)| {
+        let member = field_member(idx, field);
+        let ty = &field.ty;
+        let cfgs = field.attrs.iter().filter(|attr| attr.path.is_ident("cfg"));
+        quote_spanned! {ty.span()=> #(#cfgs)* let _: &#ty = &value.#member; }
+    });
+    Ok(quote! {
+        #output
+
+        const _: () = {
+            #[allow(dead_code)]
+            fn check_remote_fields #impl_generics (value: &#remote) #ty_where_clause {
+                #(#field_checks)*
+            }
+        };
+    })
+}
@@ -0,0 +1,76 @@
+# -*- coding: utf-8 -*-
+# ------------------------------------------------------------------------------------------------
+# Copyright © 2021, Douglas Creager.
+# Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+# Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+# ------------------------------------------------------------------------------------------------
+
+# GDB pretty-printer for controlled_option::ControlledOption.  This is embedded into the
+# .debug_gdb_scripts section of any binary that links against controlled-option, and is loaded
+# automatically if GDB's auto-load safe path allows it.
+#
+# The output type alone doesn't tell us which value is the niche, so we only recognize the niche
+# for the types whose niche we know is zero: references, NonNull, Box, and the NonZero integers.
+# For references, NonNull, and Box, that's a null data pointer; for the NonZero integers, it's an
+# all-zero value.  For any other type, we display the raw output value.
+
+import re
+
+import gdb
+
+CONTROLLED_OPTION_TYPE = re.compile(r"^controlled_option::ControlledOption<.+>$")
+DEFAULT_NICHE_TYPE = re.compile(
+    r"^controlled_option::ControlledOption<(.+), controlled_option::DefaultNiche>$"
+)
+ZERO_POINTER_TYPES = re.compile(r"^(&|core::ptr::non_null::NonNull<|alloc::boxed::Box<)")
+ZERO_INTEGER_TYPES = re.compile(r"^core::num::nonzero::NonZero(<|[IU](8|16|32|64|128|size)\b)")
+
+
+# Returns the number of leading bytes of the output value that are all zero exactly when the
+# option is None, or 0 if we don't know where the option's niche is.
+def zero_niche_size(type_name, value):
+    match = DEFAULT_NICHE_TYPE.match(type_name)
+    if match is None:
+        return 0
+    if ZERO_POINTER_TYPES.match(match.group(1)):
+        return gdb.lookup_type("void").pointer().sizeof
+    if ZERO_INTEGER_TYPES.match(match.group(1)):
+        return value.type.sizeof
+    return 0
+
+
+class ControlledOptionPrinter:
+    def __init__(self, val):
+        self.value = val["value"]
+        self.niche_size = zero_niche_size(val.type.strip_typedefs().name, self.value)
+
+    def is_known_niche(self):
+        return self.niche_size > 0
+
+    def is_none(self):
+        if not self.is_known_niche():
+            return False
+        memory = gdb.selected_inferior().read_memory(self.value.address, self.niche_size)
+        return not any(bytes(memory))
+
+    def to_string(self):
+        if self.is_none():
+            return "None"
+        if self.is_known_niche():
+            return "Some"
+        return "ControlledOption"
+
+    def children(self):
+        if self.is_none():
+            return
+        yield ("0", self.value)
+
+
+def lookup(val):
+    type_name = val.type.strip_typedefs().name
+    if type_name is not None and CONTROLLED_OPTION_TYPE.match(type_name):
+        return ControlledOptionPrinter(val)
+    return None
+
+
+gdb.current_objfile().pretty_printers.append(lookup)
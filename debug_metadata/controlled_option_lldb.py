@@ -0,0 +1,60 @@
+# -*- coding: utf-8 -*-
+# ------------------------------------------------------------------------------------------------
+# Copyright © 2021, Douglas Creager.
+# Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+# Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+# ------------------------------------------------------------------------------------------------
+
+# LLDB summary provider for controlled_option::ControlledOption.  Unlike GDB and Natvis, rustc
+# cannot embed LLDB scripts into a binary, so you must load this one yourself:
+#
+#     (lldb) command script import /path/to/controlled_option_lldb.py
+#
+# The output type alone doesn't tell us which value is the niche, so we only recognize the niche
+# for the types whose niche we know is zero: references, NonNull, Box, and the NonZero integers.
+# For references, NonNull, and Box, that's a null data pointer; for the NonZero integers, it's an
+# all-zero value.  For any other type, we display the raw output value.
+
+import re
+
+import lldb
+
+DEFAULT_NICHE_TYPE = re.compile(
+    r"^controlled_option::ControlledOption<(.+), controlled_option::DefaultNiche>$"
+)
+ZERO_POINTER_TYPES = re.compile(r"^(&|core::ptr::non_null::NonNull<|alloc::boxed::Box<)")
+ZERO_INTEGER_TYPES = re.compile(r"^core::num::nonzero::NonZero(<|[IU](8|16|32|64|128|size)\b)")
+
+
+# Returns the number of leading bytes of the output value that are all zero exactly when the
+# option is None, or 0 if we don't know where the option's niche is.
+def zero_niche_size(valobj, value):
+    type_name = valobj.GetType().GetCanonicalType().GetName()
+    match = DEFAULT_NICHE_TYPE.match(type_name)
+    if match is None:
+        return 0
+    if ZERO_POINTER_TYPES.match(match.group(1)):
+        return valobj.GetTarget().GetAddressByteSize()
+    if ZERO_INTEGER_TYPES.match(match.group(1)):
+        return value.GetByteSize()
+    return 0
+
+
+def summary(valobj, internal_dict):
+    valobj = valobj.GetNonSyntheticValue()
+    value = valobj.GetChildMemberWithName("value")
+    niche_size = zero_niche_size(valobj, value)
+    if niche_size == 0:
+        return "ControlledOption(%s)" % (value.GetSummary() or value.GetValue())
+    data = value.GetData()
+    error = lldb.SBError()
+    if not any(data.GetUnsignedInt8(error, offset) for offset in range(niche_size)):
+        return "None"
+    return "Some(%s)" % (value.GetSummary() or value.GetValue())
+
+
+def __lldb_init_module(debugger, internal_dict):
+    debugger.HandleCommand(
+        'type summary add -F controlled_option_lldb.summary '
+        '-x "^controlled_option::ControlledOption<.+>$" --category Rust'
+    )
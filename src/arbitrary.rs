@@ -0,0 +1,32 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! `arbitrary` support for `ControlledOption`.
+//!
+//! We generate options in the same way as the standard `Option` type.  Because `Some` values are
+//! always generated from an arbitrary instance of the underlying type, we will never accidentally
+//! produce the niche value for a `Some`.
+
+use ::arbitrary::Arbitrary;
+use ::arbitrary::Result;
+use ::arbitrary::Unstructured;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+impl<'a, T> Arbitrary<'a> for ControlledOption<T>
+where
+    T: Niche + Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Option::<T>::arbitrary(u).map(ControlledOption::from)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        Option::<T>::size_hint(depth)
+    }
+}
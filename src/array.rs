@@ -0,0 +1,208 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::ops::Deref;
+use core::ops::DerefMut;
+
+use crate::ControlledOption;
+use crate::IterSome;
+use crate::Niche;
+use crate::OptionSliceExt;
+
+/// A fixed-size array of [`ControlledOption`][]s.
+///
+/// Each element is stored as its niche's output type, with no additional discriminant.  The scan
+/// methods look at every element without any early exits or data-dependent branches, which lets
+/// the compiler vectorize them.  The array dereferences to a standard array of options, so you
+/// can use all of the usual array and slice methods to access its elements.
+#[repr(transparent)]
+pub struct OptionArray<T, const N: usize>
+where
+    T: Niche,
+{
+    values: [ControlledOption<T>; N],
+}
+
+impl<T, const N: usize> OptionArray<T, N>
+where
+    T: Niche,
+{
+    /// Creates a new array where every element is `None`.
+    #[inline]
+    pub fn none() -> OptionArray<T, N> {
+        OptionArray {
+            values: core::array::from_fn(|_| ControlledOption::none()),
+        }
+    }
+
+    /// Creates a new array from a standard array of options.
+    #[inline]
+    pub fn from_array(values: [ControlledOption<T>; N]) -> OptionArray<T, N> {
+        OptionArray { values }
+    }
+
+    /// Consumes the array, returning a standard array of options.
+    #[inline]
+    pub fn into_array(self) -> [ControlledOption<T>; N] {
+        self.values
+    }
+
+    /// Returns the number of `Some` elements in the array.
+    #[inline]
+    pub fn count_some(&self) -> usize {
+        self.values
+            .iter()
+            .map(|value| value.is_some() as usize)
+            .sum()
+    }
+
+    /// Returns whether any element of the array is `None`.
+    #[inline]
+    pub fn any_none(&self) -> bool {
+        self.values
+            .iter()
+            .fold(false, |any, value| any | value.is_none())
+    }
+
+    /// Returns the index of the first `None` element of the array.
+    #[inline]
+    pub fn first_none(&self) -> Option<usize> {
+        let index = self
+            .values
+            .iter()
+            .enumerate()
+            .fold(N, |first, (index, value)| {
+                if value.is_none() {
+                    first.min(index)
+                } else {
+                    first
+                }
+            });
+        if index < N {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the first `Some` element of the array, along with its index.
+    #[inline]
+    pub fn first_some(&self) -> Option<(usize, &T)> {
+        let index = self
+            .values
+            .iter()
+            .enumerate()
+            .fold(N, |first, (index, value)| {
+                if value.is_some() {
+                    first.min(index)
+                } else {
+                    first
+                }
+            });
+        let value = self.values.get(index)?;
+        Some((index, value.as_option()?))
+    }
+
+    /// Returns an iterator over the `Some` elements of the array, along with their indices.
+    #[inline]
+    pub fn iter_some(&self) -> IterSome<'_, T> {
+        self.values.iter_some()
+    }
+}
+
+impl<T, const N: usize> Deref for OptionArray<T, N>
+where
+    T: Niche,
+{
+    type Target = [ControlledOption<T>; N];
+
+    #[inline]
+    fn deref(&self) -> &[ControlledOption<T>; N] {
+        &self.values
+    }
+}
+
+impl<T, const N: usize> DerefMut for OptionArray<T, N>
+where
+    T: Niche,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [ControlledOption<T>; N] {
+        &mut self.values
+    }
+}
+
+impl<T, const N: usize> Default for OptionArray<T, N>
+where
+    T: Niche,
+{
+    #[inline]
+    fn default() -> OptionArray<T, N> {
+        OptionArray::none()
+    }
+}
+
+impl<T, const N: usize> From<[ControlledOption<T>; N]> for OptionArray<T, N>
+where
+    T: Niche,
+{
+    #[inline]
+    fn from(values: [ControlledOption<T>; N]) -> OptionArray<T, N> {
+        OptionArray::from_array(values)
+    }
+}
+
+impl<T, const N: usize> Clone for OptionArray<T, N>
+where
+    T: Niche,
+    T::Output: Clone,
+{
+    fn clone(&self) -> Self {
+        OptionArray {
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<T, const N: usize> Copy for OptionArray<T, N>
+where
+    T: Niche,
+    T::Output: Copy,
+{
+}
+
+impl<T, const N: usize> core::fmt::Debug for OptionArray<T, N>
+where
+    T: core::fmt::Debug + Niche,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_list().entries(self.values.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> PartialEq for OptionArray<T, N>
+where
+    T: Niche + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<T, const N: usize> Eq for OptionArray<T, N> where T: Niche + Eq {}
+
+impl<T, const N: usize> core::hash::Hash for OptionArray<T, N>
+where
+    T: Niche + core::hash::Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: core::hash::Hasher,
+    {
+        self.values.hash(state)
+    }
+}
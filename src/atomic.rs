@@ -0,0 +1,332 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::marker::PhantomData;
+use core::sync::atomic::Ordering;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+/// An output type that has a corresponding atomic type in `std::sync::atomic`.  Options whose
+/// output type implements this trait can be wrapped in an [`AtomicControlledOption`][].
+///
+/// # Safety
+///
+/// The atomic type must store values of this type exactly: every value returned by one of these
+/// methods must be a value that was previously passed into `new_atomic`, `store`, `swap`, or
+/// `compare_exchange` (or a copy of one), and `compare_exchange` must only succeed when the stored
+/// value is bitwise equal to `current`.  [`AtomicControlledOption`][] relies on this to hand out
+/// `Some` values without checking them.
+pub unsafe trait AtomicOutput: Copy {
+    /// The atomic type that can store values of this type.
+    type Atomic;
+
+    /// Creates a new atomic containing the given value.
+    fn new_atomic(value: Self) -> Self::Atomic;
+
+    /// Consumes an atomic, returning the value that it contains.
+    fn into_inner(atomic: Self::Atomic) -> Self;
+
+    /// Loads a value from an atomic.
+    fn load(atomic: &Self::Atomic, order: Ordering) -> Self;
+
+    /// Stores a value into an atomic.
+    fn store(atomic: &Self::Atomic, value: Self, order: Ordering);
+
+    /// Stores a value into an atomic, returning the previous value.
+    fn swap(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self;
+
+    /// Stores a value into an atomic if its current value is the same as `current`.  Returns the
+    /// previous value, wrapped in `Ok` if the store succeeded, and `Err` if it didn't.
+    fn compare_exchange(
+        atomic: &Self::Atomic,
+        current: Self,
+        new: Self,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self, Self>;
+}
+
+macro_rules! impl_atomic_output {
+    ($($ty:ty => $atomic:ty),* $(,)?) => {
+        $(
+            unsafe impl AtomicOutput for $ty {
+                type Atomic = $atomic;
+
+                #[inline]
+                fn new_atomic(value: Self) -> Self::Atomic {
+                    <$atomic>::new(value)
+                }
+
+                #[inline]
+                fn into_inner(atomic: Self::Atomic) -> Self {
+                    atomic.into_inner()
+                }
+
+                #[inline]
+                fn load(atomic: &Self::Atomic, order: Ordering) -> Self {
+                    atomic.load(order)
+                }
+
+                #[inline]
+                fn store(atomic: &Self::Atomic, value: Self, order: Ordering) {
+                    atomic.store(value, order)
+                }
+
+                #[inline]
+                fn swap(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self {
+                    atomic.swap(value, order)
+                }
+
+                #[inline]
+                fn compare_exchange(
+                    atomic: &Self::Atomic,
+                    current: Self,
+                    new: Self,
+                    success: Ordering,
+                    failure: Ordering,
+                ) -> Result<Self, Self> {
+                    atomic.compare_exchange(current, new, success, failure)
+                }
+            }
+        )*
+    };
+}
+
+impl_atomic_output! {
+    u8 => core::sync::atomic::AtomicU8,
+    u16 => core::sync::atomic::AtomicU16,
+    u32 => core::sync::atomic::AtomicU32,
+    u64 => core::sync::atomic::AtomicU64,
+    usize => core::sync::atomic::AtomicUsize,
+    i8 => core::sync::atomic::AtomicI8,
+    i16 => core::sync::atomic::AtomicI16,
+    i32 => core::sync::atomic::AtomicI32,
+    i64 => core::sync::atomic::AtomicI64,
+    isize => core::sync::atomic::AtomicIsize,
+}
+
+unsafe impl<T> AtomicOutput for *mut T {
+    type Atomic = core::sync::atomic::AtomicPtr<T>;
+
+    #[inline]
+    fn new_atomic(value: Self) -> Self::Atomic {
+        core::sync::atomic::AtomicPtr::new(value)
+    }
+
+    #[inline]
+    fn into_inner(atomic: Self::Atomic) -> Self {
+        atomic.into_inner()
+    }
+
+    #[inline]
+    fn load(atomic: &Self::Atomic, order: Ordering) -> Self {
+        atomic.load(order)
+    }
+
+    #[inline]
+    fn store(atomic: &Self::Atomic, value: Self, order: Ordering) {
+        atomic.store(value, order)
+    }
+
+    #[inline]
+    fn swap(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self {
+        atomic.swap(value, order)
+    }
+
+    #[inline]
+    fn compare_exchange(
+        atomic: &Self::Atomic,
+        current: Self,
+        new: Self,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self, Self> {
+        atomic.compare_exchange(current, new, success, failure)
+    }
+}
+
+unsafe impl<T> AtomicOutput for *const T {
+    type Atomic = core::sync::atomic::AtomicPtr<T>;
+
+    #[inline]
+    fn new_atomic(value: Self) -> Self::Atomic {
+        core::sync::atomic::AtomicPtr::new(value as *mut T)
+    }
+
+    #[inline]
+    fn into_inner(atomic: Self::Atomic) -> Self {
+        atomic.into_inner()
+    }
+
+    #[inline]
+    fn load(atomic: &Self::Atomic, order: Ordering) -> Self {
+        atomic.load(order)
+    }
+
+    #[inline]
+    fn store(atomic: &Self::Atomic, value: Self, order: Ordering) {
+        atomic.store(value as *mut T, order)
+    }
+
+    #[inline]
+    fn swap(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self {
+        atomic.swap(value as *mut T, order)
+    }
+
+    #[inline]
+    fn compare_exchange(
+        atomic: &Self::Atomic,
+        current: Self,
+        new: Self,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self, Self> {
+        atomic
+            .compare_exchange(current as *mut T, new as *mut T, success, failure)
+            .map(|value| value as *const T)
+            .map_err(|value| value as *const T)
+    }
+}
+
+/// A [`ControlledOption`][] that can be safely shared between threads.  The option is stored in
+/// the atomic type that corresponds to the niche's output type, so this is only available for
+/// niches whose output is an integer or a (thin) pointer.
+pub struct AtomicControlledOption<T>
+where
+    T: Niche,
+    T::Output: AtomicOutput,
+{
+    value: <T::Output as AtomicOutput>::Atomic,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> AtomicControlledOption<T>
+where
+    T: Niche,
+    T::Output: AtomicOutput,
+{
+    /// Creates a new atomic option containing the given value.
+    #[inline]
+    pub fn new(value: ControlledOption<T>) -> AtomicControlledOption<T> {
+        AtomicControlledOption {
+            value: T::Output::new_atomic(value.value),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new atomic option containing `None`.
+    #[inline]
+    pub fn none() -> AtomicControlledOption<T> {
+        AtomicControlledOption::new(ControlledOption::none())
+    }
+
+    /// Consumes the atomic option, returning the option that it contains.
+    #[inline]
+    pub fn into_inner(self) -> ControlledOption<T> {
+        ControlledOption::from_repr(T::Output::into_inner(self.value))
+    }
+
+    /// Stores a new value into the option.
+    #[inline]
+    pub fn store(&self, value: ControlledOption<T>, order: Ordering) {
+        T::Output::store(&self.value, value.value, order)
+    }
+
+    /// Stores a new value into the option, returning the previous value.
+    #[inline]
+    pub fn swap(&self, value: ControlledOption<T>, order: Ordering) -> ControlledOption<T> {
+        ControlledOption::from_repr(T::Output::swap(&self.value, value.value, order))
+    }
+
+    /// Takes the value out of the option, leaving `None` in its place.
+    #[inline]
+    pub fn take(&self, order: Ordering) -> ControlledOption<T> {
+        self.swap(ControlledOption::none(), order)
+    }
+}
+
+// Loading the option, or comparing it against an expected value, leaves a copy of the stored
+// value behind, and so these are only available when `T` itself can be copied.
+
+impl<T> AtomicControlledOption<T>
+where
+    T: Copy + Niche,
+    T::Output: AtomicOutput,
+{
+    /// Loads the current value of the option.
+    #[inline]
+    pub fn load(&self, order: Ordering) -> ControlledOption<T> {
+        ControlledOption::from_repr(T::Output::load(&self.value, order))
+    }
+
+    /// Stores a new value into the option if its current value is the same as `current`.  Returns
+    /// the previous value, wrapped in `Ok` if the store succeeded, and `Err` if it didn't.
+    ///
+    /// The comparison is performed on the options' output representations.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: ControlledOption<T>,
+        new: ControlledOption<T>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<ControlledOption<T>, ControlledOption<T>> {
+        T::Output::compare_exchange(&self.value, current.value, new.value, success, failure)
+            .map(ControlledOption::from_repr)
+            .map_err(ControlledOption::from_repr)
+    }
+}
+
+impl<T> Default for AtomicControlledOption<T>
+where
+    T: Niche,
+    T::Output: AtomicOutput,
+{
+    #[inline]
+    fn default() -> AtomicControlledOption<T> {
+        AtomicControlledOption::none()
+    }
+}
+
+impl<T> From<ControlledOption<T>> for AtomicControlledOption<T>
+where
+    T: Niche,
+    T::Output: AtomicOutput,
+{
+    #[inline]
+    fn from(value: ControlledOption<T>) -> AtomicControlledOption<T> {
+        AtomicControlledOption::new(value)
+    }
+}
+
+impl<T> core::fmt::Debug for AtomicControlledOption<T>
+where
+    T: Copy + core::fmt::Debug + Niche,
+    T::Output: AtomicOutput,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.load(Ordering::SeqCst).fmt(f)
+    }
+}
+
+// Any thread that has a shared reference to the atomic option can move a `T` into or out of it,
+// so the atomic option can be shared between threads as long as `T` can be sent between them.
+
+unsafe impl<T> Send for AtomicControlledOption<T>
+where
+    T: Niche + Send,
+    T::Output: AtomicOutput,
+{
+}
+
+unsafe impl<T> Sync for AtomicControlledOption<T>
+where
+    T: Niche + Send,
+    T::Output: AtomicOutput,
+{
+}
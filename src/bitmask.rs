@@ -0,0 +1,389 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::mem::MaybeUninit;
+
+use crate::OptionVec;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A growable vector of optional values, for element types that don't have a niche.
+///
+/// The elements are stored as raw `T`s, and a separate bitmask (one bit per element) records
+/// which of them are present.  This has the same API as
+/// [`ControlledOptionVec`][crate::ControlledOptionVec], and both types implement [`OptionVec`][],
+/// so generic code can work with either one.
+pub struct BitmaskOptionVec<T> {
+    values: Vec<MaybeUninit<T>>,
+    mask: Vec<u64>,
+}
+
+impl<T> BitmaskOptionVec<T> {
+    /// Creates a new empty vector.
+    #[inline]
+    pub fn new() -> BitmaskOptionVec<T> {
+        BitmaskOptionVec {
+            values: Vec::new(),
+            mask: Vec::new(),
+        }
+    }
+
+    /// Creates a new empty vector with space for at least `capacity` elements.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> BitmaskOptionVec<T> {
+        BitmaskOptionVec {
+            values: Vec::with_capacity(capacity),
+            mask: Vec::with_capacity(capacity.div_ceil(BITS)),
+        }
+    }
+
+    /// Creates a new vector containing `len` `None` elements.
+    pub fn from_none(len: usize) -> BitmaskOptionVec<T> {
+        let mut result = BitmaskOptionVec::with_capacity(len);
+        result.resize_none(len);
+        result
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether the vector is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the number of elements that the vector can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Returns whether the element at `index` is `Some`.  Panics if `index` is out of bounds.
+    #[inline]
+    pub fn is_some(&self, index: usize) -> bool {
+        assert!(index < self.len(), "index out of bounds");
+        self.bit(index)
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if that element is `None` or
+    /// `index` is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len() && self.bit(index) {
+            Some(unsafe { self.values[index].assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if that element is
+    /// `None` or `index` is out of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len() && self.bit(index) {
+            Some(unsafe { self.values[index].assume_init_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Appends an option to the end of the vector.
+    #[inline]
+    pub fn push(&mut self, value: Option<T>) {
+        match value {
+            Some(value) => self.push_some(value),
+            None => self.push_none(),
+        }
+    }
+
+    /// Appends a `Some` element to the end of the vector.
+    #[inline]
+    pub fn push_some(&mut self, value: T) {
+        let index = self.values.len();
+        self.values.push(MaybeUninit::new(value));
+        self.grow_mask();
+        self.set_bit(index, true);
+    }
+
+    /// Appends a `None` element to the end of the vector.
+    #[inline]
+    pub fn push_none(&mut self) {
+        self.values.push(MaybeUninit::uninit());
+        self.grow_mask();
+    }
+
+    /// Removes the last element of the vector, returning `None` if the vector is empty.
+    pub fn pop(&mut self) -> Option<Option<T>> {
+        let index = self.values.len().checked_sub(1)?;
+        let value = self.take(index);
+        self.values.pop();
+        self.shrink_mask();
+        Some(value)
+    }
+
+    /// Replaces the element at `index`, returning its previous value.  Panics if `index` is out
+    /// of bounds.
+    pub fn set(&mut self, index: usize, value: Option<T>) -> Option<T> {
+        let previous = self.take(index);
+        if let Some(value) = value {
+            self.values[index] = MaybeUninit::new(value);
+            self.set_bit(index, true);
+        }
+        previous
+    }
+
+    /// Takes the element at `index`, leaving `None` in its place.  Panics if `index` is out of
+    /// bounds.
+    pub fn take(&mut self, index: usize) -> Option<T> {
+        if !self.is_some(index) {
+            return None;
+        }
+        self.set_bit(index, false);
+        Some(unsafe { self.values[index].assume_init_read() })
+    }
+
+    /// Returns an iterator over the `Some` elements of the vector, along with their indices.
+    #[inline]
+    pub fn iter_some(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        self.values
+            .iter()
+            .enumerate()
+            .filter(move |(index, _)| self.bit(*index))
+            .map(|(index, value)| (index, unsafe { value.assume_init_ref() }))
+    }
+
+    /// Returns the number of `Some` elements in the vector.
+    #[inline]
+    pub fn count_some(&self) -> usize {
+        self.mask
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Sets every element of the vector to `None`, without changing its length.
+    pub fn fill_none(&mut self) {
+        self.drop_values(0);
+    }
+
+    /// Shortens the vector to `len` elements.  Does nothing if the vector is already shorter
+    /// than that.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len() {
+            return;
+        }
+        self.drop_values(len);
+        self.values.truncate(len);
+        self.shrink_mask();
+    }
+
+    /// Resizes the vector to `len` elements, filling any new elements with `None`.
+    pub fn resize_none(&mut self, len: usize) {
+        if len <= self.len() {
+            self.truncate(len);
+            return;
+        }
+        self.values.resize_with(len, MaybeUninit::uninit);
+        self.mask.resize(len.div_ceil(BITS), 0);
+    }
+
+    /// Removes all elements from the vector.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    #[inline]
+    fn bit(&self, index: usize) -> bool {
+        self.mask[index / BITS] & (1 << (index % BITS)) != 0
+    }
+
+    #[inline]
+    fn set_bit(&mut self, index: usize, value: bool) {
+        let word = &mut self.mask[index / BITS];
+        if value {
+            *word |= 1 << (index % BITS);
+        } else {
+            *word &= !(1 << (index % BITS));
+        }
+    }
+
+    #[inline]
+    fn grow_mask(&mut self) {
+        if self.mask.len() * BITS < self.values.len() {
+            self.mask.push(0);
+        }
+    }
+
+    // Removes any mask words (and bits within the last word) that are past the end of the vector.
+    // The corresponding elements must already have been dropped.
+    fn shrink_mask(&mut self) {
+        let len = self.values.len();
+        self.mask.truncate(len.div_ceil(BITS));
+        if !len.is_multiple_of(BITS) {
+            if let Some(last) = self.mask.last_mut() {
+                *last &= (1 << (len % BITS)) - 1;
+            }
+        }
+    }
+
+    // Drops every `Some` element at or after `start`, clearing its bit first, so that if one of
+    // the destructors panics, we won't try to drop that element again.
+    fn drop_values(&mut self, start: usize) {
+        for index in start..self.values.len() {
+            if self.bit(index) {
+                self.set_bit(index, false);
+                unsafe { self.values[index].assume_init_drop() };
+            }
+        }
+    }
+}
+
+impl<T> OptionVec<T> for BitmaskOptionVec<T> {
+    #[inline]
+    fn from_none(len: usize) -> Self {
+        BitmaskOptionVec::from_none(len)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        BitmaskOptionVec::len(self)
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<&T> {
+        BitmaskOptionVec::get(self, index)
+    }
+
+    #[inline]
+    fn push_some(&mut self, value: T) {
+        BitmaskOptionVec::push_some(self, value)
+    }
+
+    #[inline]
+    fn push_none(&mut self) {
+        BitmaskOptionVec::push_none(self)
+    }
+
+    #[inline]
+    fn set(&mut self, index: usize, value: Option<T>) -> Option<T> {
+        BitmaskOptionVec::set(self, index, value)
+    }
+
+    #[inline]
+    fn take(&mut self, index: usize) -> Option<T> {
+        BitmaskOptionVec::take(self, index)
+    }
+
+    #[inline]
+    fn count_some(&self) -> usize {
+        BitmaskOptionVec::count_some(self)
+    }
+
+    #[inline]
+    fn iter_some<'a>(&'a self) -> impl Iterator<Item = (usize, &'a T)>
+    where
+        T: 'a,
+    {
+        BitmaskOptionVec::iter_some(self)
+    }
+
+    #[inline]
+    fn fill_none(&mut self) {
+        BitmaskOptionVec::fill_none(self)
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        BitmaskOptionVec::truncate(self, len)
+    }
+
+    #[inline]
+    fn resize_none(&mut self, len: usize) {
+        BitmaskOptionVec::resize_none(self, len)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        BitmaskOptionVec::clear(self)
+    }
+}
+
+impl<T> Drop for BitmaskOptionVec<T> {
+    fn drop(&mut self) {
+        self.drop_values(0);
+    }
+}
+
+impl<T> Default for BitmaskOptionVec<T> {
+    #[inline]
+    fn default() -> BitmaskOptionVec<T> {
+        BitmaskOptionVec::new()
+    }
+}
+
+impl<T> Clone for BitmaskOptionVec<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        (0..self.len())
+            .map(|index| self.get(index).cloned())
+            .collect()
+    }
+}
+
+impl<T> core::fmt::Debug for BitmaskOptionVec<T>
+where
+    T: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_list()
+            .entries((0..self.len()).map(|index| self.get(index)))
+            .finish()
+    }
+}
+
+impl<T> PartialEq for BitmaskOptionVec<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && (0..self.len()).all(|index| self.get(index) == other.get(index))
+    }
+}
+
+impl<T> Eq for BitmaskOptionVec<T> where T: Eq {}
+
+impl<T> FromIterator<Option<T>> for BitmaskOptionVec<T> {
+    fn from_iter<I>(iter: I) -> BitmaskOptionVec<T>
+    where
+        I: IntoIterator<Item = Option<T>>,
+    {
+        let mut result = BitmaskOptionVec::new();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<T> Extend<Option<T>> for BitmaskOptionVec<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Option<T>>,
+    {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! borsh support for `ControlledOption`.
+//!
+//! We use the same encoding as `Option`: a one-byte tag, followed by the value if the tag is 1.
+//! The niche is only used for the in-memory representation; it never appears on the wire.
+
+use ::borsh::io::Read;
+use ::borsh::io::Result;
+use ::borsh::io::Write;
+
+use ::borsh::BorshDeserialize;
+use ::borsh::BorshSerialize;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+impl<T> BorshSerialize for ControlledOption<T>
+where
+    T: Niche + BorshSerialize,
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.as_option().serialize(writer)
+    }
+}
+
+impl<T> BorshDeserialize for ControlledOption<T>
+where
+    T: Niche + BorshDeserialize,
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Option::<T>::deserialize_reader(reader).map(ControlledOption::from)
+    }
+}
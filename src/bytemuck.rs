@@ -0,0 +1,37 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! bytemuck support for `ControlledOption`.
+//!
+//! Which of bytemuck's traits we can implement depends on what we know about the niche.  An
+//! option can be created from all-zero bytes (`Zeroable`) if the niche is all zeroes.  It can be
+//! created from arbitrary bytes (`Pod`) or from arbitrary output values (`TransparentWrapper`)
+//! only if every output value is either the niche or a valid `Some` value.  (bytemuck provides
+//! `NoUninit` for every `Pod` type, so we cannot implement it separately for the options that can
+//! only be viewed as bytes.)
+
+use ::bytemuck::Pod;
+use ::bytemuck::TransparentWrapper;
+use ::bytemuck::Zeroable;
+
+use crate::ControlledOption;
+use crate::DenseNiche;
+use crate::ZeroNiche;
+
+// SAFETY: All-zero bytes are the niche, which is a valid `None` value.
+unsafe impl<T> Zeroable for ControlledOption<T> where T: ZeroNiche {}
+
+// SAFETY: Every bit pattern is a valid output value, and every output value is a valid option.
+unsafe impl<T> Pod for ControlledOption<T>
+where
+    T: DenseNiche + ZeroNiche + 'static,
+    T::Output: Pod,
+{
+}
+
+// SAFETY: ControlledOption is #[repr(transparent)], and every output value is a valid option.
+unsafe impl<T> TransparentWrapper<T::Output> for ControlledOption<T> where T: DenseNiche {}
@@ -0,0 +1,162 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::cell::Cell;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+/// A mutable memory location containing a [`ControlledOption`][].  This is the counterpart of a
+/// standard `Cell<Option<T>>`, and lets you get and set the option through a shared reference.
+#[repr(transparent)]
+pub struct ControlledCell<T>
+where
+    T: Niche,
+{
+    value: Cell<ControlledOption<T>>,
+}
+
+impl<T> ControlledCell<T>
+where
+    T: Niche,
+{
+    /// Creates a new cell containing the given option.
+    #[inline]
+    pub fn new(value: ControlledOption<T>) -> ControlledCell<T> {
+        ControlledCell {
+            value: Cell::new(value),
+        }
+    }
+
+    /// Creates a new cell containing `None`.
+    #[inline]
+    pub fn none() -> ControlledCell<T> {
+        ControlledCell::new(ControlledOption::none())
+    }
+
+    /// Creates a new cell containing `Some(value)`.
+    #[inline]
+    pub fn some(value: T) -> ControlledCell<T> {
+        ControlledCell::new(ControlledOption::some(value))
+    }
+
+    /// Returns a copy of the cell's option.
+    #[inline]
+    pub fn get(&self) -> ControlledOption<T>
+    where
+        T::Output: Copy,
+    {
+        self.value.get()
+    }
+
+    /// Returns `true` if the cell contains `None`.
+    #[inline]
+    pub fn is_none(&self) -> bool
+    where
+        T::Output: Copy,
+    {
+        // We check a copy of the option, since `is_none` runs arbitrary code, which could set the
+        // cell while we're looking at it.
+        self.get().is_none()
+    }
+
+    /// Returns `true` if the cell contains `Some` value.
+    #[inline]
+    pub fn is_some(&self) -> bool
+    where
+        T::Output: Copy,
+    {
+        !self.is_none()
+    }
+
+    /// Sets the cell's option.
+    #[inline]
+    pub fn set(&self, value: ControlledOption<T>) {
+        self.value.set(value)
+    }
+
+    /// Replaces the cell's option, returning the previous option.
+    #[inline]
+    pub fn replace(&self, value: ControlledOption<T>) -> ControlledOption<T> {
+        self.value.replace(value)
+    }
+
+    /// Takes the cell's option, leaving `None` in its place.
+    #[inline]
+    pub fn take(&self) -> ControlledOption<T> {
+        self.value.take()
+    }
+
+    /// Returns a mutable reference to the cell's option.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut ControlledOption<T> {
+        self.value.get_mut()
+    }
+
+    /// Consumes the cell, returning its option.
+    #[inline]
+    pub fn into_inner(self) -> ControlledOption<T> {
+        self.value.into_inner()
+    }
+}
+
+impl<T> Clone for ControlledCell<T>
+where
+    T: Niche,
+    T::Output: Copy,
+{
+    fn clone(&self) -> Self {
+        ControlledCell::new(self.get())
+    }
+}
+
+impl<T> Default for ControlledCell<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn default() -> ControlledCell<T> {
+        ControlledCell::none()
+    }
+}
+
+impl<T> From<ControlledOption<T>> for ControlledCell<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn from(value: ControlledOption<T>) -> ControlledCell<T> {
+        ControlledCell::new(value)
+    }
+}
+
+impl<T> core::fmt::Debug for ControlledCell<T>
+where
+    T: core::fmt::Debug + Niche,
+    T::Output: Copy,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_tuple("ControlledCell").field(&self.get()).finish()
+    }
+}
+
+impl<T> PartialEq for ControlledCell<T>
+where
+    T: Niche + PartialEq,
+    T::Output: Copy,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T> Eq for ControlledCell<T>
+where
+    T: Niche + Eq,
+    T::Output: Copy,
+{
+}
@@ -0,0 +1,26 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! defmt support for `ControlledOption`.
+
+use ::defmt::Format;
+use ::defmt::Formatter;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+impl<T> Format for ControlledOption<T>
+where
+    T: Niche + Format,
+{
+    fn format(&self, f: Formatter) {
+        match self.as_option() {
+            Some(value) => ::defmt::write!(f, "Some({})", value),
+            None => ::defmt::write!(f, "None"),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use crate::ControlledResult;
+use crate::ResultNiche;
+
+/// An `Either` type where you have control over how the `Left` and `Right` variants are
+/// represented in memory.
+///
+/// This has the same representation as a [`ControlledResult<L, R>`][ControlledResult]: the left
+/// type must implement [`ResultNiche<R>`][ResultNiche], which determines how both variants are
+/// packed into a single value.  In particular, if `L` implements [`Niche`][crate::Niche], then
+/// `ControlledEither<L, ()>` uses `L`'s niche to represent the right variant.
+#[repr(transparent)]
+pub struct ControlledEither<L, R>
+where
+    L: ResultNiche<R>,
+{
+    inner: ControlledResult<L, R>,
+}
+
+impl<L, R> ControlledEither<L, R>
+where
+    L: ResultNiche<R>,
+{
+    /// Creates a new `Left` instance.
+    #[inline]
+    pub fn from_left(value: L) -> ControlledEither<L, R> {
+        ControlledEither {
+            inner: ControlledResult::ok(value),
+        }
+    }
+
+    /// Creates a new `Right` instance.
+    #[inline]
+    pub fn from_right(value: R) -> ControlledEither<L, R> {
+        ControlledEither {
+            inner: ControlledResult::err(value),
+        }
+    }
+
+    /// Returns `true` if this is a `Left` value.
+    #[inline]
+    pub fn is_left(&self) -> bool {
+        self.inner.is_ok()
+    }
+
+    /// Returns `true` if this is a `Right` value.
+    #[inline]
+    pub fn is_right(&self) -> bool {
+        self.inner.is_err()
+    }
+
+    /// Returns the left value, or `None` if this is a `Right` value.
+    #[inline]
+    pub fn left(self) -> Option<L> {
+        self.inner.into_result().ok()
+    }
+
+    /// Returns the right value, or `None` if this is a `Left` value.
+    #[inline]
+    pub fn right(self) -> Option<R> {
+        self.inner.into_result().err()
+    }
+
+    /// Returns a reference to the left value, or `None` if this is a `Right` value.
+    #[inline]
+    pub fn as_left(&self) -> Option<&L> {
+        self.inner.as_result().ok()
+    }
+
+    /// Returns a reference to the right value, or `None` if this is a `Left` value.
+    #[inline]
+    pub fn as_right(&self) -> Option<&R> {
+        self.inner.as_result().err()
+    }
+
+    /// Applies a function to the left value, leaving a right value untouched.
+    #[inline]
+    pub fn map_left<F, M>(self, f: F) -> ControlledEither<M, R>
+    where
+        F: FnOnce(L) -> M,
+        M: ResultNiche<R>,
+    {
+        match self.inner.into_result() {
+            Ok(value) => ControlledEither::from_left(f(value)),
+            Err(value) => ControlledEither::from_right(value),
+        }
+    }
+
+    /// Applies a function to the right value, leaving a left value untouched.
+    #[inline]
+    pub fn map_right<F, S>(self, f: F) -> ControlledEither<L, S>
+    where
+        F: FnOnce(R) -> S,
+        L: ResultNiche<S>,
+    {
+        match self.inner.into_result() {
+            Ok(value) => ControlledEither::from_left(value),
+            Err(value) => ControlledEither::from_right(f(value)),
+        }
+    }
+}
+
+impl<L, R> Clone for ControlledEither<L, R>
+where
+    L: ResultNiche<R>,
+    L::Output: Clone,
+{
+    fn clone(&self) -> Self {
+        ControlledEither {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<L, R> Copy for ControlledEither<L, R>
+where
+    L: ResultNiche<R>,
+    L::Output: Copy,
+{
+}
+
+impl<L, R> core::fmt::Debug for ControlledEither<L, R>
+where
+    L: core::fmt::Debug + ResultNiche<R>,
+    R: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.inner.as_result() {
+            Ok(value) => f
+                .debug_tuple("ControlledEither::Left")
+                .field(value)
+                .finish(),
+            Err(value) => f
+                .debug_tuple("ControlledEither::Right")
+                .field(value)
+                .finish(),
+        }
+    }
+}
+
+impl<L, R> PartialEq for ControlledEither<L, R>
+where
+    L: ResultNiche<R>,
+    L::Output: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.eq(&other.inner)
+    }
+}
+
+impl<L, R> Eq for ControlledEither<L, R>
+where
+    L: ResultNiche<R>,
+    L::Output: Eq,
+{
+}
+
+impl<L, R> core::hash::Hash for ControlledEither<L, R>
+where
+    L: ResultNiche<R>,
+    L::Output: core::hash::Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: core::hash::Hasher,
+    {
+        self.inner.hash(state)
+    }
+}
+
+#[cfg(feature = "either")]
+impl<L, R> From<::either::Either<L, R>> for ControlledEither<L, R>
+where
+    L: ResultNiche<R>,
+{
+    #[inline]
+    fn from(value: ::either::Either<L, R>) -> ControlledEither<L, R> {
+        match value {
+            ::either::Either::Left(value) => ControlledEither::from_left(value),
+            ::either::Either::Right(value) => ControlledEither::from_right(value),
+        }
+    }
+}
+
+#[cfg(feature = "either")]
+impl<L, R> From<ControlledEither<L, R>> for ::either::Either<L, R>
+where
+    L: ResultNiche<R>,
+{
+    #[inline]
+    fn from(value: ControlledEither<L, R>) -> ::either::Either<L, R> {
+        match value.inner.into_result() {
+            Ok(value) => ::either::Either::Left(value),
+            Err(value) => ::either::Either::Right(value),
+        }
+    }
+}
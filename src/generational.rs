@@ -0,0 +1,124 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::mem::MaybeUninit;
+use core::num::NonZeroU32;
+
+use crate::ConstNiche;
+use crate::Niche;
+use crate::ZeroNiche;
+
+const FIRST_GENERATION: NonZeroU32 = match NonZeroU32::new(1) {
+    Some(generation) => generation,
+    None => unreachable!(),
+};
+
+/// A key into an arena, consisting of a slot index and a generation count.
+///
+/// Arenas typically bump a slot's generation each time it is reused, so that stale keys for
+/// earlier occupants of the slot can be detected.  Generations start at 1, so the zero generation
+/// is available as a niche, and an optional key is the same size as the key itself.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct GenerationalId {
+    index: u32,
+    generation: NonZeroU32,
+}
+
+impl GenerationalId {
+    /// Creates a new key for the first generation of a slot.
+    #[inline]
+    pub const fn new(index: u32) -> GenerationalId {
+        GenerationalId {
+            index,
+            generation: FIRST_GENERATION,
+        }
+    }
+
+    /// Creates a new key from its parts.
+    #[inline]
+    pub const fn from_parts(index: u32, generation: NonZeroU32) -> GenerationalId {
+        GenerationalId { index, generation }
+    }
+
+    /// Returns the slot index of this key.
+    #[inline]
+    pub const fn index(self) -> u32 {
+        self.index
+    }
+
+    /// Returns the generation of this key.
+    #[inline]
+    pub const fn generation(self) -> NonZeroU32 {
+        self.generation
+    }
+
+    /// Returns a key for the next generation of the same slot.  The generation wraps back around
+    /// to 1 after `u32::MAX`.
+    #[inline]
+    pub const fn next_generation(self) -> GenerationalId {
+        let generation = match self.generation.checked_add(1) {
+            Some(generation) => generation,
+            None => FIRST_GENERATION,
+        };
+        GenerationalId {
+            index: self.index,
+            generation,
+        }
+    }
+
+    /// Returns whether this key is still live, given the current generation of its slot.
+    #[inline]
+    pub const fn is_live(self, current_generation: NonZeroU32) -> bool {
+        self.generation.get() == current_generation.get()
+    }
+
+    /// Returns whether this key refers to the same slot as `other`, but a later generation.
+    #[inline]
+    pub const fn supersedes(self, other: GenerationalId) -> bool {
+        self.index == other.index && self.generation.get() > other.generation.get()
+    }
+}
+
+// We store the key in a `MaybeUninit` so that we can place a zero in the generation field, just
+// like the output of `#[derive(Niche)]` would.
+
+impl Niche for GenerationalId {
+    type Output = MaybeUninit<Self>;
+
+    #[inline]
+    fn none() -> Self::Output {
+        MaybeUninit::zeroed()
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        let ptr = value.as_ptr();
+        unsafe { crate::struct_field_is_none(core::ptr::addr_of!((*ptr).generation)) }
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        MaybeUninit::new(value)
+    }
+
+    #[inline]
+    fn from_some(value: Self::Output) -> Self {
+        unsafe { value.assume_init() }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*value.as_ptr() }
+    }
+}
+
+unsafe impl ZeroNiche for GenerationalId {}
+
+unsafe impl ConstNiche for GenerationalId {
+    const NONE: Self::Output = MaybeUninit::zeroed();
+}
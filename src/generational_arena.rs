@@ -0,0 +1,45 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! `Niche` implementation for generational-arena indices.
+//!
+//! An arena's slots live in a `Vec`, which can never hold `usize::MAX` elements, so no arena will
+//! ever hand out an index for that slot.  We use that slot (with the largest possible generation)
+//! as the niche, so the output type is the index type itself.
+
+use ::generational_arena::Index;
+
+use crate::Niche;
+
+impl Niche for Index {
+    type Output = Index;
+
+    #[inline]
+    fn none() -> Self::Output {
+        Index::from_raw_parts(usize::MAX, u64::MAX)
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.into_raw_parts().0 == usize::MAX
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value
+    }
+
+    #[inline]
+    fn from_some(value: Self::Output) -> Self {
+        value
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        value
+    }
+}
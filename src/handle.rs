@@ -0,0 +1,167 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! Opaque handles that pack an index and some metadata into a single integer.
+//!
+//! The index lives in the low `INDEX_BITS` bits, and the metadata (typically a generation count
+//! or a resource type tag) lives in the remaining high bits.  The all-ones bit pattern is
+//! reserved as the niche, so an optional handle is the same size as the handle itself.
+
+use core::convert::TryFrom;
+
+use crate::ConstNiche;
+use crate::DenseNiche;
+use crate::Niche;
+
+/// The error that is returned when converting a raw integer into a handle fails, because the
+/// integer is the reserved invalid bit pattern.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct InvalidHandle;
+
+impl core::fmt::Display for InvalidHandle {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("invalid handle")
+    }
+}
+
+impl core::error::Error for InvalidHandle {}
+
+macro_rules! packed_handle {
+    ($($(#[$attr:meta])* $name:ident => $primitive:ty, $default_bits:expr);* $(;)?) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+            #[repr(transparent)]
+            pub struct $name<const INDEX_BITS: u32 = $default_bits>($primitive);
+
+            impl<const INDEX_BITS: u32> $name<INDEX_BITS> {
+                const CHECK: () = assert!(
+                    INDEX_BITS > 0 && INDEX_BITS < <$primitive>::BITS,
+                    "a handle must have at least one index bit and one metadata bit"
+                );
+
+                /// The number of bits used for the index.
+                pub const INDEX_BITS: u32 = INDEX_BITS;
+
+                /// The number of bits used for the metadata.
+                pub const METADATA_BITS: u32 = <$primitive>::BITS - INDEX_BITS;
+
+                /// The reserved bit pattern that represents `None` in a `ControlledOption`.
+                pub const INVALID: $primitive = <$primitive>::MAX;
+
+                const INDEX_MASK: $primitive = (1 << INDEX_BITS) - 1;
+
+                /// Creates a new handle, returning `None` if `index` or `metadata` don't fit in
+                /// their bit ranges, or if they would produce the reserved invalid bit pattern.
+                #[inline]
+                pub const fn new(index: $primitive, metadata: $primitive) -> Option<Self> {
+                    #[allow(clippy::let_unit_value)]
+                    let () = Self::CHECK;
+                    if index > Self::INDEX_MASK || metadata >> Self::METADATA_BITS != 0 {
+                        return None;
+                    }
+                    let raw = (metadata << INDEX_BITS) | index;
+                    if raw == Self::INVALID {
+                        return None;
+                    }
+                    Some($name(raw))
+                }
+
+                /// Returns the index stored in this handle.
+                #[inline]
+                pub const fn index(self) -> $primitive {
+                    self.0 & Self::INDEX_MASK
+                }
+
+                /// Returns the metadata stored in this handle.
+                #[inline]
+                pub const fn metadata(self) -> $primitive {
+                    self.0 >> INDEX_BITS
+                }
+
+                /// Returns the raw bit pattern of this handle.
+                #[inline]
+                pub const fn to_raw(self) -> $primitive {
+                    self.0
+                }
+            }
+
+            impl<const INDEX_BITS: u32> From<$name<INDEX_BITS>> for $primitive {
+                #[inline]
+                fn from(value: $name<INDEX_BITS>) -> $primitive {
+                    value.to_raw()
+                }
+            }
+
+            impl<const INDEX_BITS: u32> TryFrom<$primitive> for $name<INDEX_BITS> {
+                type Error = InvalidHandle;
+
+                #[inline]
+                fn try_from(value: $primitive) -> Result<Self, InvalidHandle> {
+                    #[allow(clippy::let_unit_value)]
+                    let () = Self::CHECK;
+                    if value == Self::INVALID {
+                        Err(InvalidHandle)
+                    } else {
+                        Ok($name(value))
+                    }
+                }
+            }
+
+            impl<const INDEX_BITS: u32> core::fmt::Debug for $name<INDEX_BITS> {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    f.debug_struct(stringify!($name))
+                        .field("index", &self.index())
+                        .field("metadata", &self.metadata())
+                        .finish()
+                }
+            }
+
+            impl<const INDEX_BITS: u32> Niche for $name<INDEX_BITS> {
+                type Output = $primitive;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    Self::INVALID
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    *value == Self::INVALID
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    value.0
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    $name(value)
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { &*(value as *const Self::Output as *const Self) }
+                }
+            }
+
+            unsafe impl<const INDEX_BITS: u32> DenseNiche for $name<INDEX_BITS> {}
+
+            unsafe impl<const INDEX_BITS: u32> ConstNiche for $name<INDEX_BITS> {
+                const NONE: Self::Output = Self::INVALID;
+            }
+        )*
+    };
+}
+
+packed_handle! {
+    /// A 32-bit handle.  By default, it uses 24 bits for the index and 8 bits for the metadata.
+    Handle32 => u32, 24;
+    /// A 64-bit handle.  By default, it uses 32 bits for the index and 32 bits for the metadata.
+    Handle64 => u64, 32;
+}
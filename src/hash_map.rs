@@ -0,0 +1,397 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::borrow::Borrow;
+use core::hash::BuildHasher;
+use core::hash::Hash;
+use core::iter::FromIterator;
+use core::mem::MaybeUninit;
+use std::collections::hash_map::RandomState;
+use std::vec::Vec;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+/// The smallest number of slots that we allocate once the map is not empty.
+const MIN_SLOTS: usize = 8;
+
+/// An open-addressing hash map whose empty slots are marked using the key's niche.
+///
+/// The keys are stored in an array of [`ControlledOption`][]s, and the values in a parallel
+/// array, so there is no separate array of control bytes: a slot is empty exactly when its key
+/// is `None`.  The map uses linear probing.  Removing an entry shifts any later entries in the
+/// same probe sequence backwards, so the map never needs tombstones, and a single niche value is
+/// enough.
+pub struct ControlledHashMap<K, V, S = RandomState>
+where
+    K: Niche,
+{
+    keys: Vec<ControlledOption<K>>,
+    values: Vec<MaybeUninit<V>>,
+    len: usize,
+    hash_builder: S,
+}
+
+impl<K, V> ControlledHashMap<K, V>
+where
+    K: Niche,
+{
+    /// Creates a new empty map.
+    #[inline]
+    pub fn new() -> ControlledHashMap<K, V> {
+        ControlledHashMap::with_hasher(RandomState::new())
+    }
+
+    /// Creates a new empty map with space for at least `capacity` entries.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> ControlledHashMap<K, V> {
+        ControlledHashMap::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<K, V, S> ControlledHashMap<K, V, S>
+where
+    K: Niche,
+{
+    /// Creates a new empty map that uses `hash_builder` to hash its keys.
+    #[inline]
+    pub fn with_hasher(hash_builder: S) -> ControlledHashMap<K, V, S> {
+        ControlledHashMap {
+            keys: Vec::new(),
+            values: Vec::new(),
+            len: 0,
+            hash_builder,
+        }
+    }
+
+    /// Creates a new empty map with space for at least `capacity` entries, which uses
+    /// `hash_builder` to hash its keys.
+    pub fn with_capacity_and_hasher(
+        capacity: usize,
+        hash_builder: S,
+    ) -> ControlledHashMap<K, V, S> {
+        let mut map = ControlledHashMap::with_hasher(hash_builder);
+        if capacity > 0 {
+            map.allocate(slots_for_capacity(capacity));
+        }
+        map
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of entries that the map can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        max_load(self.keys.len())
+    }
+
+    /// Returns an iterator over the entries in the map, in arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> HashMapIter<'_, K, V> {
+        HashMapIter {
+            keys: self.keys.iter(),
+            values: self.values.iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys in the map, in arbitrary order.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values in the map, in arbitrary order.
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Removes all entries from the map, keeping its allocated memory.
+    pub fn clear(&mut self) {
+        for slot in 0..self.keys.len() {
+            if core::mem::take(&mut self.keys[slot]).is_some() {
+                unsafe { self.values[slot].assume_init_drop() };
+            }
+        }
+        self.len = 0;
+    }
+
+    fn allocate(&mut self, slots: usize) {
+        debug_assert!(slots.is_power_of_two());
+        self.keys = core::iter::repeat_with(ControlledOption::none)
+            .take(slots)
+            .collect();
+        self.values = core::iter::repeat_with(MaybeUninit::uninit)
+            .take(slots)
+            .collect();
+    }
+}
+
+impl<K, V, S> ControlledHashMap<K, V, S>
+where
+    K: Eq + Hash + Niche,
+    S: BuildHasher,
+{
+    /// Inserts an entry into the map, returning the previous value for `key`, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(slot) = self.find(&key) {
+            let old = core::mem::replace(&mut self.values[slot], MaybeUninit::new(value));
+            return Some(unsafe { old.assume_init() });
+        }
+        if self.len >= self.capacity() {
+            self.grow();
+        }
+        self.insert_new(key, value);
+        None
+    }
+
+    /// Returns a reference to the value for `key`, if any.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let slot = self.find(key)?;
+        Some(unsafe { self.values[slot].assume_init_ref() })
+    }
+
+    /// Returns a mutable reference to the value for `key`, if any.
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let slot = self.find(key)?;
+        Some(unsafe { self.values[slot].assume_init_mut() })
+    }
+
+    /// Returns whether the map contains an entry for `key`.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Removes the entry for `key` from the map, returning its value, if any.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let mut hole = self.find(key)?;
+        self.keys[hole] = ControlledOption::none();
+        let value = unsafe { self.values[hole].assume_init_read() };
+        self.len -= 1;
+
+        // Shift any later entries in the same probe sequence back into the hole, so that lookups
+        // don't stop early at the now-empty slot.
+        let mask = self.keys.len() - 1;
+        let mut slot = hole;
+        loop {
+            slot = (slot + 1) & mask;
+            let ideal = match self.keys[slot].as_option() {
+                Some(key) => self.ideal_slot(key),
+                None => break,
+            };
+            // The entry can move into the hole unless its ideal slot lies cyclically in
+            // (hole, slot].
+            let stays = if hole <= slot {
+                hole < ideal && ideal <= slot
+            } else {
+                hole < ideal || ideal <= slot
+            };
+            if !stays {
+                self.keys.swap(hole, slot);
+                self.values.swap(hole, slot);
+                hole = slot;
+            }
+        }
+        Some(value)
+    }
+
+    fn ideal_slot<Q>(&self, key: &Q) -> usize
+    where
+        Q: Hash + ?Sized,
+    {
+        self.hash_builder.hash_one(key) as usize & (self.keys.len() - 1)
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let mask = self.keys.len() - 1;
+        let mut slot = self.ideal_slot(key);
+        loop {
+            match self.keys[slot].as_option() {
+                Some(candidate) if candidate.borrow() == key => return Some(slot),
+                Some(_) => slot = (slot + 1) & mask,
+                None => return None,
+            }
+        }
+    }
+
+    // Inserts an entry that is known not to be in the map, and for which there is room.
+    fn insert_new(&mut self, key: K, value: V) {
+        let mask = self.keys.len() - 1;
+        let mut slot = self.ideal_slot(&key);
+        while self.keys[slot].is_some() {
+            slot = (slot + 1) & mask;
+        }
+        self.keys[slot] = ControlledOption::some(key);
+        self.values[slot] = MaybeUninit::new(value);
+        self.len += 1;
+    }
+
+    fn grow(&mut self) {
+        let slots = (self.keys.len() * 2).max(MIN_SLOTS);
+        let keys = core::mem::take(&mut self.keys);
+        let values = core::mem::take(&mut self.values);
+        self.allocate(slots);
+        self.len = 0;
+        for (key, value) in keys.into_iter().zip(values) {
+            if let Some(key) = key.into_option() {
+                self.insert_new(key, unsafe { value.assume_init() });
+            }
+        }
+    }
+}
+
+// We keep the load factor at or below 7/8.
+#[inline]
+fn max_load(slots: usize) -> usize {
+    slots - slots / 8
+}
+
+#[inline]
+fn slots_for_capacity(capacity: usize) -> usize {
+    let mut slots = MIN_SLOTS;
+    while max_load(slots) < capacity {
+        slots *= 2;
+    }
+    slots
+}
+
+impl<K, V, S> Drop for ControlledHashMap<K, V, S>
+where
+    K: Niche,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K, V, S> Default for ControlledHashMap<K, V, S>
+where
+    K: Niche,
+    S: Default,
+{
+    #[inline]
+    fn default() -> ControlledHashMap<K, V, S> {
+        ControlledHashMap::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> core::fmt::Debug for ControlledHashMap<K, V, S>
+where
+    K: core::fmt::Debug + Niche,
+    V: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for ControlledHashMap<K, V, S>
+where
+    K: Eq + Hash + Niche,
+    S: BuildHasher,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for ControlledHashMap<K, V, S>
+where
+    K: Eq + Hash + Niche,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I>(iter: I) -> ControlledHashMap<K, V, S>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = ControlledHashMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a ControlledHashMap<K, V, S>
+where
+    K: Niche,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = HashMapIter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of a [`ControlledHashMap`][].
+pub struct HashMapIter<'a, K, V>
+where
+    K: Niche,
+{
+    keys: core::slice::Iter<'a, ControlledOption<K>>,
+    values: core::slice::Iter<'a, MaybeUninit<V>>,
+}
+
+impl<'a, K, V> Iterator for HashMapIter<'a, K, V>
+where
+    K: Niche,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let key = self.keys.next()?;
+            let value = self.values.next()?;
+            if let Some(key) = key.as_option() {
+                return Some((key, unsafe { value.assume_init_ref() }));
+            }
+        }
+    }
+}
+
+impl<K, V> core::iter::FusedIterator for HashMapIter<'_, K, V> where K: Niche {}
@@ -0,0 +1,49 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! `Niche` implementation for id-arena identifiers.
+//!
+//! An arena's items live in a `Vec`, and an arena can only hand out an id for an item that it has
+//! allocated, so no arena will ever hand out an id for index `usize::MAX`.  We use that index as
+//! the niche, so the output type is the id type itself.  (This only covers the `Id` type used by
+//! `DefaultArenaBehavior`; if you define your own arena behavior, its id type is yours, and you
+//! can implement `Niche` for it directly.)
+
+use ::id_arena::ArenaBehavior;
+use ::id_arena::DefaultArenaBehavior;
+use ::id_arena::Id;
+
+use crate::Niche;
+
+impl<T> Niche for Id<T> {
+    type Output = Id<T>;
+
+    #[inline]
+    fn none() -> Self::Output {
+        DefaultArenaBehavior::<T>::new_id(u32::MAX, usize::MAX)
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.index() == usize::MAX
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value
+    }
+
+    #[inline]
+    fn from_some(value: Self::Output) -> Self {
+        value
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        value
+    }
+}
@@ -0,0 +1,200 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! Niches for operating system I/O resources.
+//!
+//! On Unix, file descriptors are never `-1`, which is the sentinel that C APIs use to indicate a
+//! missing or invalid descriptor.  On Windows, sockets are never `INVALID_SOCKET`.  (Windows
+//! handles do not have a niche, since an `OwnedHandle` can hold both `NULL` and
+//! `INVALID_HANDLE_VALUE`, depending on how it was created.)
+//!
+//! For the owned variants, the option's output type closes the resource when it is dropped.
+
+#[cfg(unix)]
+pub use self::unix::OwnedFdRepr;
+#[cfg(windows)]
+pub use self::windows::OwnedSocketRepr;
+
+#[cfg(unix)]
+mod unix {
+    use core::mem::ManuallyDrop;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::io::BorrowedFd;
+    use std::os::unix::io::FromRawFd;
+    use std::os::unix::io::IntoRawFd;
+    use std::os::unix::io::OwnedFd;
+    use std::os::unix::io::RawFd;
+
+    use crate::Niche;
+
+    const FD_NICHE: RawFd = -1;
+
+    /// The output representation of an optional [`OwnedFd`][].  The file descriptor is closed
+    /// when the representation is dropped.
+    ///
+    /// [`OwnedFd`]: https://doc.rust-lang.org/std/os/fd/struct.OwnedFd.html
+    #[repr(transparent)]
+    pub struct OwnedFdRepr(RawFd);
+
+    impl Drop for OwnedFdRepr {
+        fn drop(&mut self) {
+            if self.0 != FD_NICHE {
+                drop(unsafe { OwnedFd::from_raw_fd(self.0) });
+            }
+        }
+    }
+
+    impl Niche for OwnedFd {
+        type Output = OwnedFdRepr;
+
+        #[inline]
+        fn none() -> Self::Output {
+            OwnedFdRepr(FD_NICHE)
+        }
+
+        #[inline]
+        fn is_none(value: &Self::Output) -> bool {
+            value.0 == FD_NICHE
+        }
+
+        #[inline]
+        fn into_some(value: Self) -> Self::Output {
+            OwnedFdRepr(value.into_raw_fd())
+        }
+
+        #[inline]
+        fn from_some(value: Self::Output) -> Self {
+            let value = ManuallyDrop::new(value);
+            unsafe { OwnedFd::from_raw_fd(value.0) }
+        }
+
+        #[inline]
+        fn as_some(value: &Self::Output) -> &Self {
+            unsafe { &*(value as *const Self::Output as *const Self) }
+        }
+    }
+
+    impl Niche for BorrowedFd<'_> {
+        type Output = RawFd;
+
+        #[inline]
+        fn none() -> Self::Output {
+            FD_NICHE
+        }
+
+        #[inline]
+        fn is_none(value: &Self::Output) -> bool {
+            *value == FD_NICHE
+        }
+
+        #[inline]
+        fn into_some(value: Self) -> Self::Output {
+            value.as_raw_fd()
+        }
+
+        #[inline]
+        fn from_some(value: Self::Output) -> Self {
+            unsafe { BorrowedFd::borrow_raw(value) }
+        }
+
+        #[inline]
+        fn as_some(value: &Self::Output) -> &Self {
+            unsafe { &*(value as *const Self::Output as *const Self) }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use core::mem::ManuallyDrop;
+    use std::os::windows::io::AsRawSocket;
+    use std::os::windows::io::BorrowedSocket;
+    use std::os::windows::io::FromRawSocket;
+    use std::os::windows::io::IntoRawSocket;
+    use std::os::windows::io::OwnedSocket;
+    use std::os::windows::io::RawSocket;
+
+    use crate::Niche;
+
+    // The standard library stores sockets as a pointer-sized `SOCKET`, even though `RawSocket` is
+    // always 64 bits, so we do the same to ensure that our output types have the same layout.
+    const SOCKET_NICHE: usize = usize::MAX;
+
+    /// The output representation of an optional [`OwnedSocket`][].  The socket is closed when the
+    /// representation is dropped.
+    ///
+    /// [`OwnedSocket`]: https://doc.rust-lang.org/std/os/windows/io/struct.OwnedSocket.html
+    #[repr(transparent)]
+    pub struct OwnedSocketRepr(usize);
+
+    impl Drop for OwnedSocketRepr {
+        fn drop(&mut self) {
+            if self.0 != SOCKET_NICHE {
+                drop(unsafe { OwnedSocket::from_raw_socket(self.0 as RawSocket) });
+            }
+        }
+    }
+
+    impl Niche for OwnedSocket {
+        type Output = OwnedSocketRepr;
+
+        #[inline]
+        fn none() -> Self::Output {
+            OwnedSocketRepr(SOCKET_NICHE)
+        }
+
+        #[inline]
+        fn is_none(value: &Self::Output) -> bool {
+            value.0 == SOCKET_NICHE
+        }
+
+        #[inline]
+        fn into_some(value: Self) -> Self::Output {
+            OwnedSocketRepr(value.into_raw_socket() as usize)
+        }
+
+        #[inline]
+        fn from_some(value: Self::Output) -> Self {
+            let value = ManuallyDrop::new(value);
+            unsafe { OwnedSocket::from_raw_socket(value.0 as RawSocket) }
+        }
+
+        #[inline]
+        fn as_some(value: &Self::Output) -> &Self {
+            unsafe { &*(value as *const Self::Output as *const Self) }
+        }
+    }
+
+    impl Niche for BorrowedSocket<'_> {
+        type Output = usize;
+
+        #[inline]
+        fn none() -> Self::Output {
+            SOCKET_NICHE
+        }
+
+        #[inline]
+        fn is_none(value: &Self::Output) -> bool {
+            *value == SOCKET_NICHE
+        }
+
+        #[inline]
+        fn into_some(value: Self) -> Self::Output {
+            value.as_raw_socket() as usize
+        }
+
+        #[inline]
+        fn from_some(value: Self::Output) -> Self {
+            unsafe { BorrowedSocket::borrow_raw(value as RawSocket) }
+        }
+
+        #[inline]
+        fn as_some(value: &Self::Output) -> &Self {
+            unsafe { &*(value as *const Self::Output as *const Self) }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::cell::Cell;
+use core::ops::Deref;
+
+use crate::ControlledOnceCell;
+use crate::ControlledOption;
+use crate::Niche;
+
+/// A value that is initialized on first access, which uses the niche of `T` to represent the
+/// uninitialized state.  This is the counterpart of the standard `LazyCell` type.
+///
+/// The value is stored in a [`ControlledOnceCell`][], so there is no separate discriminant or
+/// `Once` state.  The only other storage is the initialization function itself, which is
+/// discarded once it has been called.  (With the default function type, `fn() -> T`, that's a
+/// single pointer, whose own niche represents the "already called" state.)
+pub struct ControlledLazy<T, F = fn() -> T>
+where
+    T: Niche,
+{
+    cell: ControlledOnceCell<T>,
+    init: Cell<Option<F>>,
+}
+
+impl<T, F> ControlledLazy<T, F>
+where
+    T: Niche,
+    F: FnOnce() -> T,
+{
+    /// Creates a new lazy value, which will be initialized by calling `f`.
+    #[inline]
+    pub fn new(f: F) -> ControlledLazy<T, F> {
+        ControlledLazy {
+            cell: ControlledOnceCell::new(),
+            init: Cell::new(Some(f)),
+        }
+    }
+
+    /// Forces the evaluation of the lazy value, and returns a reference to the result.  Panics if
+    /// a previous initialization attempt panicked.
+    #[inline]
+    pub fn force(this: &ControlledLazy<T, F>) -> &T {
+        this.cell.get_or_init(|| match this.init.take() {
+            Some(f) => f(),
+            None => panic!("ControlledLazy instance has previously been poisoned"),
+        })
+    }
+
+    /// Returns a reference to the value if it has already been initialized, without forcing it.
+    #[inline]
+    pub fn get(this: &ControlledLazy<T, F>) -> Option<&T> {
+        this.cell.get()
+    }
+
+    /// Consumes the lazy value, returning the initialized value, or the initialization function
+    /// if it hasn't been called yet.
+    pub fn into_inner(this: ControlledLazy<T, F>) -> Result<T, F> {
+        let ControlledLazy { cell, init } = this;
+        match cell.into_inner().into_option() {
+            Some(value) => Ok(value),
+            None => Err(init
+                .into_inner()
+                .expect("ControlledLazy instance has previously been poisoned")),
+        }
+    }
+}
+
+impl<T, F> Deref for ControlledLazy<T, F>
+where
+    T: Niche,
+    F: FnOnce() -> T,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        ControlledLazy::force(self)
+    }
+}
+
+impl<T> Default for ControlledLazy<T>
+where
+    T: Default + Niche,
+{
+    #[inline]
+    fn default() -> ControlledLazy<T> {
+        ControlledLazy::new(T::default)
+    }
+}
+
+impl<T, F> core::fmt::Debug for ControlledLazy<T, F>
+where
+    T: core::fmt::Debug + Niche,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.cell.get() {
+            Some(value) => f.debug_tuple("ControlledLazy").field(value).finish(),
+            None => write!(f, "ControlledLazy(<uninit>)"),
+        }
+    }
+}
+
+impl<T, F> From<ControlledLazy<T, F>> for ControlledOption<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn from(value: ControlledLazy<T, F>) -> ControlledOption<T> {
+        value.cell.into_inner()
+    }
+}
@@ -24,8 +24,231 @@
 //!
 //! [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
 //! [_niches_]: https://rust-lang.github.io/unsafe-code-guidelines/glossary.html#niche
-
-use std::alloc::Layout;
+//!
+//! # `no_std` support
+//!
+//! This crate is `no_std`.  The `std` feature (enabled by default) adds support for types that are
+//! only available in the standard library, such as file descriptors.  The `alloc` feature (implied
+//! by `std`) adds support for the types in the `alloc` crate, such as `Box`, `Rc`, `Vec`, and
+//! `String`, along with [`ControlledOptionVec`][].  Everything else, including the `Niche` derive
+//! macro, works with just `core`.
+//!
+//! # Optional features
+//!
+//! - `abi_stable`: implements `StableAbi` for `ControlledOption`, so that options can be passed
+//!   across `abi_stable` plugin boundaries.  The option's layout is certified as a transparent
+//!   wrapper around the niche's output type.
+//! - `arbitrary`: implements `Arbitrary` for `ControlledOption`, so that you can use options in
+//!   fuzz targets.
+//! - `borsh`: implements `BorshSerialize` and `BorshDeserialize` for `ControlledOption`, using the
+//!   same encoding as the standard `Option` type (a one-byte tag followed by the value).
+//! - `bytemuck`: implements bytemuck's `Zeroable`, `Pod`, and `TransparentWrapper` traits for
+//!   `ControlledOption`, whenever the niche's representation makes that sound.
+//! - `defmt`: implements `defmt::Format` for `ControlledOption`, printing options as `None` or
+//!   `Some(value)`.
+//! - `either`: provides conversions between [`ControlledEither`][] and `either::Either`.
+//! - `generational-arena`: implements [`Niche`][] for `generational_arena::Index`.
+//! - `id-arena`: implements [`Niche`][] for `id_arena::Id`.
+//! - `nonmax`: implements [`Niche`][] for the `nonmax` crate's types, using the same all-zero
+//!   niche as the standard `Option`, so they also implement [`StdNiche`][].
+//! - `petgraph`: implements [`Niche`][] for petgraph's `NodeIndex` and `EdgeIndex` types, using
+//!   the maximum index (which petgraph reserves as an invalid index) as the niche.
+//! - `proptest`: implements proptest's `Arbitrary` for `ControlledOption`.  The
+//!   [`proptest`][proptest] module also provides strategy constructors that mirror those in
+//!   `proptest::option`.
+//! - `quickcheck`: implements quickcheck's `Arbitrary` for `ControlledOption`.
+//! - `rkyv`: implements rkyv's `Archive`, `Serialize`, and `Deserialize` traits for
+//!   `ControlledOption`.  The archived form of a `ControlledOption<T>` is a
+//!   `ControlledOption<T::Archived>`, so archived options are just as compact as the live ones.
+//!   The archived type must implement `ArchivedNiche`, which we implement for the archived
+//!   forms of the standard non-zero integers.
+//! - `schemars`: implements `JsonSchema` for `ControlledOption`, producing the same schema as the
+//!   corresponding `Option`.  Struct fields are only treated as optional if you mark them with
+//!   `#[serde(default)]`.
+//! - `serde`: implements `Serialize` and `Deserialize` for `ControlledOption`, using the same data
+//!   model as the standard `Option` type.  Also enables `serde-with`.
+//! - `serde-with`: provides the [`serde`][serde] module, whose functions you can use with serde's
+//!   `with` field attribute, without also implementing `Serialize` and `Deserialize` for
+//!   `ControlledOption` itself.
+//! - `slotmap`: implements [`Niche`][] for slotmap's `DefaultKey` and `KeyData` types, using
+//!   slotmap's null key as the niche.  The `impl_niche_for_slotmap_key!` macro does the same for
+//!   the key types that you define with `new_key_type!`.
+//! - `zerocopy`: implements zerocopy's `IntoBytes`, `Immutable`, `KnownLayout`, and `Unaligned`
+//!   traits for `ControlledOption`, whenever the output type implements them.  We cannot provide
+//!   `FromBytes` or `TryFromBytes`: zerocopy only supports derived implementations of those traits,
+//!   and a derived implementation would only check that the bytes are a valid output value, not
+//!   that they are a valid option.  (A `char` option, for instance, stores a `u32`, and most `u32`
+//!   values are neither the niche nor a valid `char`.)  zerocopy can only run that extra check
+//!   behind its unstable `zerocopy_unstable_ptr` cfg.  For [`DenseNiche`][] types, where every
+//!   output value is valid, you can instead read the output type from bytes and wrap it with
+//!   [`ControlledOption::from_output`][].
+//!
+//! # Debugger support
+//!
+//! We embed a Natvis visualizer and a GDB pretty-printer into any binary that links against this
+//! crate, so that debuggers display options as `None` or `Some(value)`.  For LLDB, you must load
+//! the `debug_metadata/controlled_option_lldb.py` script yourself, via `command script import`.
+//! The visualizers can't tell from the output type alone which value is the niche, so they only
+//! recognize the niche of references, `NonNull`, `Box`, and the non-zero integer types (using the
+//! default niche strategy), which is always zero.  For any other type, they display the raw output
+//! value.
+//!
+//! [proptest]: proptest/index.html
+//! [serde]: serde/index.html
+
+#![debugger_visualizer(natvis_file = "../debug_metadata/controlled_option.natvis")]
+#![debugger_visualizer(gdb_script_file = "../debug_metadata/controlled_option_gdb.py")]
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::alloc::Layout;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::mem::MaybeUninit;
+
+mod array;
+mod atomic;
+#[cfg(feature = "alloc")]
+mod bitmask;
+mod cell;
+mod either;
+mod generational;
+mod handle;
+#[cfg(feature = "std")]
+mod hash_map;
+#[cfg(feature = "std")]
+mod io;
+mod lazy;
+mod link;
+mod maybe_niche;
+mod nonnan;
+mod once;
+mod option_ext;
+#[cfg(feature = "alloc")]
+mod owned;
+pub mod prelude;
+mod reserved;
+mod result;
+mod sentinel;
+#[cfg(feature = "alloc")]
+mod slab;
+mod slice;
+mod std_option;
+mod symbol;
+mod tagged;
+#[cfg(feature = "alloc")]
+mod vec;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "borsh")]
+mod borsh;
+#[cfg(feature = "bytemuck")]
+mod bytemuck;
+#[cfg(feature = "defmt")]
+mod defmt;
+#[cfg(feature = "generational-arena")]
+mod generational_arena;
+#[cfg(feature = "id-arena")]
+mod id_arena;
+#[cfg(feature = "nonmax")]
+mod nonmax;
+#[cfg(feature = "petgraph")]
+mod petgraph;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "quickcheck")]
+mod quickcheck;
+#[cfg(feature = "rkyv")]
+mod rkyv;
+#[cfg(feature = "schemars")]
+mod schemars;
+#[cfg(feature = "serde-with")]
+pub mod serde;
+#[cfg(feature = "slotmap")]
+mod slotmap;
+
+pub use array::OptionArray;
+pub use atomic::AtomicControlledOption;
+pub use atomic::AtomicOutput;
+#[cfg(feature = "alloc")]
+pub use bitmask::BitmaskOptionVec;
+pub use cell::ControlledCell;
+pub use either::ControlledEither;
+pub use generational::GenerationalId;
+pub use handle::Handle32;
+pub use handle::Handle64;
+pub use handle::InvalidHandle;
+#[cfg(feature = "std")]
+pub use hash_map::ControlledHashMap;
+#[cfg(feature = "std")]
+pub use hash_map::HashMapIter;
+#[cfg(all(feature = "std", unix))]
+pub use io::OwnedFdRepr;
+#[cfg(all(feature = "std", windows))]
+pub use io::OwnedSocketRepr;
+pub use lazy::ControlledLazy;
+pub use link::LinkIndex;
+pub use link::NicheLink;
+pub use link::NicheLinkIter;
+pub use maybe_niche::FallbackNiche;
+pub use maybe_niche::MaybeNiche;
+pub use nonnan::NonNanF32;
+pub use nonnan::NonNanF64;
+pub use once::ControlledOnceCell;
+pub use once::ControlledOnceLock;
+pub use option_ext::OptionExt;
+#[cfg(feature = "alloc")]
+pub use owned::OwnedRepr;
+pub use reserved::PackedOption;
+pub use reserved::ReservedValue;
+pub use result::ControlledResult;
+pub use result::ResultNiche;
+#[cfg(feature = "rkyv")]
+pub use rkyv::ArchivedNiche;
+pub use sentinel::NonMaxU16;
+pub use sentinel::NonMaxU32;
+pub use sentinel::NonMaxU64;
+pub use sentinel::NonMaxU8;
+pub use sentinel::NonMaxUsize;
+pub use sentinel::NonMinI16;
+pub use sentinel::NonMinI32;
+pub use sentinel::NonMinI64;
+pub use sentinel::NonMinI8;
+pub use sentinel::NonMinIsize;
+pub use sentinel::SentinelI16;
+pub use sentinel::SentinelI32;
+pub use sentinel::SentinelI64;
+pub use sentinel::SentinelI8;
+pub use sentinel::SentinelIsize;
+pub use sentinel::SentinelU16;
+pub use sentinel::SentinelU32;
+pub use sentinel::SentinelU64;
+pub use sentinel::SentinelU8;
+pub use sentinel::SentinelUsize;
+#[cfg(feature = "alloc")]
+pub use slab::ControlledSlab;
+pub use slice::IterSome;
+pub use slice::OptionSliceExt;
+#[cfg(feature = "slotmap")]
+#[doc(hidden)]
+pub use slotmap::slotmap_key_is_null;
+#[cfg(feature = "slotmap")]
+#[doc(hidden)]
+pub use slotmap::slotmap_null_key;
+pub use std_option::StdNiche;
+#[cfg(feature = "std")]
+pub use symbol::Interner;
+pub use symbol::Symbol;
+pub use tagged::TaggedPtr;
+#[cfg(feature = "alloc")]
+pub use vec::ControlledOptionVec;
+#[cfg(feature = "alloc")]
+pub use vec::OptionVec;
 
 /// A type should implement `Niche` if its memory representation has any bit patterns that do not
 /// represent valid values.  If so, one of those can be used to represent the `None` case of an
@@ -60,18 +283,302 @@ pub trait Niche: Sized {
     /// Transforms a non-niche value of this type from its `Output` type.  When `Output` is `Self`,
     /// this will be the identity function.
     fn from_some(value: Self::Output) -> Self;
+
+    /// Returns a reference to a non-niche value of this type, given a reference to its `Output`
+    /// representation.  This lets you access the contents of a `ControlledOption` without
+    /// consuming it.  When `Output` is `Self`, this will be the identity function.  Otherwise,
+    /// `Output` will typically have the same layout as `Self`, and you can cast the reference.
+    fn as_some(value: &Self::Output) -> &Self;
+
+    /// Writes the `Output` representation of a non-niche value of this type directly into
+    /// `dest`.  `ControlledOption` uses this to construct `Some` values in place.  The default
+    /// implementation writes the result of [`into_some`][Self::into_some]; if `Output` has the
+    /// same layout as `Self`, you can override this to write `value` straight into `dest`, so
+    /// that large values aren't copied through a temporary.
+    ///
+    /// # Safety
+    ///
+    /// `dest` must be valid for writes and properly aligned.  Any existing value at `dest` is
+    /// overwritten without being dropped.
+    #[inline]
+    unsafe fn write_some(value: Self, dest: *mut Self::Output) {
+        dest.write(Self::into_some(value));
+    }
+
+    /// Reads a non-niche value of this type directly out of the `Output` value at `src`.
+    /// `ControlledOption` uses this to unwrap `Some` values in place.  The default implementation
+    /// reads the `Output` value and passes it to [`from_some`][Self::from_some]; as with
+    /// [`write_some`][Self::write_some], you can override this to avoid the extra copy.
+    ///
+    /// # Safety
+    ///
+    /// `src` must be valid for reads, properly aligned, and point at a non-niche value.  Like
+    /// [`ptr::read`][core::ptr::read], this moves the value out of `src`, which must not be used
+    /// or dropped afterwards.
+    #[inline]
+    unsafe fn read_some(src: *const Self::Output) -> Self {
+        Self::from_some(src.read())
+    }
+}
+
+/// A marker trait for [`Niche`][] types whose niche value is represented by all-zero bytes.
+///
+/// # Safety
+///
+/// You must ensure that a `Self::Output` consisting entirely of zero bytes is a valid value, and
+/// that [`is_none`][Niche::is_none] returns `true` for it.
+pub unsafe trait ZeroNiche: Niche {}
+
+/// A marker trait for types that a struct deriving [`Niche`][] can use as its `Output` type, via a
+/// `#[niche(output = "Type")]` attribute.  The derive reinterprets the struct's bytes as this
+/// type (and vice versa), so you must vouch for that by implementing this trait.
+///
+/// # Safety
+///
+/// `Self` must have the same size and alignment as `T`, and each of `T`'s fields must be at the
+/// same offset as a field of `Self` that can hold any valid value of it.  A value consisting
+/// entirely of zero bytes must be a valid value of `Self`.  `T` must not have any padding bytes
+/// that `Self` would treat as data.
+pub unsafe trait NicheOutput<T> {}
+
+/// A marker trait for unions that derive [`Niche`][].  The derive reads the union's `#[niche]`
+/// member to decide whether a value is `None`, regardless of which member is active, so you must
+/// vouch for that by implementing this trait.
+///
+/// # Safety
+///
+/// For every `Some` value of the union, whichever member is active, the bytes of the `#[niche]`
+/// member must be initialized, must be a valid value of that member's type, and must not be that
+/// type's niche value.
+pub unsafe trait NicheUnion {}
+
+/// A marker trait for [`Niche`][] types where _every_ valid `Self::Output` value is meaningful:
+/// it's either the niche, or a value that [`from_some`][Niche::from_some] can safely turn back
+/// into a valid instance of `Self`.  This means that it's safe to create a `ControlledOption`
+/// from an arbitrary `Output` value.
+///
+/// # Safety
+///
+/// You must ensure that `from_some` returns a valid value for every `Self::Output` value for which
+/// [`is_none`][Niche::is_none] returns `false`.
+pub unsafe trait DenseNiche: Niche {}
+
+/// A marker trait for [`Niche`][] types whose niche can be checked by comparing bytes.  A struct
+/// deriving [`Niche`][] can only use a `#[niche(bitwise)]` attribute if its niche field's type
+/// implements this trait.
+///
+/// # Safety
+///
+/// Every valid `Self::Output` value, including the niche, must be fully initialized, with no
+/// padding bytes.  [`is_none`][Niche::is_none] must return `true` for a value exactly when its
+/// bytes are the same as the bytes of the value returned by [`none`][Niche::none].
+pub unsafe trait BitwiseNiche: Niche {}
+
+/// A [`Niche`][] type whose niche value is available at compile time.  This lets you create
+/// options in `const` and `static` contexts, using [`ControlledOption::NONE`][],
+/// [`ControlledOption::const_none`][], and [`ControlledOption::const_some`][].  (Trait methods
+/// cannot be `const fn`s on stable Rust, which is why this is a separate trait.)
+///
+/// Note that there is no `const` version of `is_none`, since that would require calling the
+/// (non-`const`) [`Niche::is_none`][] method.
+///
+/// # Safety
+///
+/// [`is_none`][Niche::is_none] must return `true` for `NONE`.  In addition, `Self` and
+/// `Self::Output` must have the same layout, and [`into_some`][Niche::into_some] must not change
+/// the bit pattern of its argument, since `const_some` performs that conversion with a transmute.
+pub unsafe trait ConstNiche: Niche {
+    /// The niche value for this type.  This must be the same value that [`none`][Niche::none]
+    /// returns.
+    const NONE: Self::Output;
+}
+
+/// A [`Niche`][] type that has more than one niche value available.  The additional niche values
+/// let you nest options without making them any larger: a `ControlledOption<T>` is itself a
+/// `Niche` type whenever `T` is a `MultiNiche` type, and it uses `T`'s first extra niche value to
+/// represent its own `None`.  (This is the same trick that the compiler uses to make
+/// `Option<Option<bool>>` a single byte.)  You can also use the extra niche values when
+/// implementing `Niche` for your own small enums that wrap a `MultiNiche` type.
+///
+/// # Safety
+///
+/// Each extra niche value must be a valid `Self::Output` that is distinct from
+/// [`none`][Niche::none], from every other extra niche value, and from every value that
+/// [`into_some`][Niche::into_some] can return.  [`extra_niche_index`][Self::extra_niche_index]
+/// must return `Some(index)` exactly when its argument is equal to `extra_niche(index)`.
+pub unsafe trait MultiNiche: Niche {
+    /// The number of extra niche values, not counting the one returned by [`none`][Niche::none].
+    const EXTRA_NICHES: usize;
+
+    /// Returns one of the extra niche values.  `index` must be less than
+    /// [`EXTRA_NICHES`][Self::EXTRA_NICHES].
+    fn extra_niche(index: usize) -> Self::Output;
+
+    /// Returns the index of `value` if it is one of the extra niche values.
+    fn extra_niche_index(value: &Self::Output) -> Option<usize>;
+}
+
+/// A strategy for storing values of `T` inside of a `ControlledOption`.  Most of the time you
+/// don't need to think about this: the [`DefaultNiche`][] strategy uses the [`Niche`][]
+/// implementation of `T` itself.  But some types have more than one candidate niche (for
+/// instance, a struct with two `NonZero` fields, or an integer where either `0` or `MAX` could be
+/// reserved).  You can implement this trait on a separate (typically zero-sized) type to select a
+/// different niche for a particular `ControlledOption`, without having to define a wrapper type
+/// around `T`.
+///
+/// The methods have the same meaning (and the `Output` type has the same layout caveats) as
+/// their counterparts in [`Niche`][].
+pub trait NicheStrategy<T> {
+    /// The type that is used to store values of `T` inside of a `ControlledOption`.
+    type Output;
+
+    /// Returns the niche value that should be used to represent `None`.
+    fn none() -> Self::Output;
+
+    /// Returns whether value is the niche value for this strategy.
+    fn is_none(value: &Self::Output) -> bool;
+
+    /// Transforms a non-niche value of `T` into its `Output` type.
+    fn into_some(value: T) -> Self::Output;
+
+    /// Transforms a non-niche value of `T` from its `Output` type.
+    fn from_some(value: Self::Output) -> T;
+
+    /// Returns a reference to a non-niche value of `T`, given a reference to its `Output`
+    /// representation.
+    fn as_some(value: &Self::Output) -> &T;
+
+    /// Writes the `Output` representation of a non-niche value of `T` directly into `dest`.  See
+    /// [`Niche::write_some`][] for details.
+    ///
+    /// # Safety
+    ///
+    /// `dest` must be valid for writes and properly aligned.  Any existing value at `dest` is
+    /// overwritten without being dropped.
+    #[inline]
+    unsafe fn write_some(value: T, dest: *mut Self::Output) {
+        dest.write(Self::into_some(value));
+    }
+
+    /// Reads a non-niche value of `T` directly out of the `Output` value at `src`.  See
+    /// [`Niche::read_some`][] for details.
+    ///
+    /// # Safety
+    ///
+    /// `src` must be valid for reads, properly aligned, and point at a non-niche value.  The
+    /// value is moved out of `src`, which must not be used or dropped afterwards.
+    #[inline]
+    unsafe fn read_some(src: *const Self::Output) -> T {
+        Self::from_some(src.read())
+    }
+}
+
+/// The default [`NicheStrategy`][], which uses the [`Niche`][] implementation of the wrapped type.
+#[cfg_attr(feature = "abi_stable", derive(abi_stable::StableAbi))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct DefaultNiche;
+
+impl<T> NicheStrategy<T> for DefaultNiche
+where
+    T: Niche,
+{
+    type Output = T::Output;
+
+    #[inline]
+    fn none() -> Self::Output {
+        T::none()
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        T::is_none(value)
+    }
+
+    #[inline]
+    fn into_some(value: T) -> Self::Output {
+        T::into_some(value)
+    }
+
+    #[inline]
+    fn from_some(value: Self::Output) -> T {
+        T::from_some(value)
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &T {
+        T::as_some(value)
+    }
+
+    #[inline]
+    unsafe fn write_some(value: T, dest: *mut Self::Output) {
+        T::write_some(value, dest)
+    }
+
+    #[inline]
+    unsafe fn read_some(src: *const Self::Output) -> T {
+        T::read_some(src)
+    }
 }
 
 /// An `Option` type where you have control over the in-memory representation of the `None` and
 /// `Some` variants.  See the [module-level documentation][parent] for more information.
 ///
+/// Even though only a `T::Output` is stored, a `ControlledOption<T>` is treated by the type
+/// system as owning a `T`, just like an `Option<T>` would.  In particular, it is `Unpin` exactly
+/// when `T` is, and it is `Send` or `Sync` only when both `T` and its `Output` type are.  (So an
+/// option of a reference, which is stored as a raw pointer, is neither.)  Note that the wrapper is
+/// always _invariant_ in `T`, even when `Option<T>` would be covariant, since the compiler cannot
+/// see through the `T::Output` projection to determine a more precise variance.
+///
+/// The optional `S` parameter selects the [`NicheStrategy`][] that determines how `T` is stored.
+/// It defaults to [`DefaultNiche`][], which uses `T`'s own [`Niche`][] implementation; you can
+/// provide a different strategy if `T` has more than one usable niche.
+///
+/// Since an option is only `Send` if its `Output` type is, a strategy that stores values in a
+/// non-`Send` type makes the option non-`Send` too, even if `T` is `Send`:
+///
+/// ```compile_fail,E0277
+/// # use std::rc::Rc;
+/// # use controlled_option::ControlledOption;
+/// # use controlled_option::NicheStrategy;
+/// struct Shared;
+///
+/// impl NicheStrategy<u32> for Shared {
+///     type Output = Option<Rc<u32>>;
+///     fn none() -> Self::Output { None }
+///     fn is_none(value: &Self::Output) -> bool { value.is_none() }
+///     fn into_some(value: u32) -> Self::Output { Some(Rc::new(value)) }
+///     fn from_some(value: Self::Output) -> u32 { *value.unwrap() }
+///     fn as_some(value: &Self::Output) -> &u32 { value.as_deref().unwrap() }
+/// }
+///
+/// fn require_send<T: Send>(_: T) {}
+/// require_send(ControlledOption::<u32, Shared>::some_with_strategy(75));
+/// ```
+///
 /// [parent]: index.html
+#[cfg_attr(feature = "abi_stable", derive(abi_stable::StableAbi))]
+#[cfg_attr(
+    feature = "abi_stable",
+    sabi(bound(S::Output: abi_stable::StableAbi))
+)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::Immutable,
+        zerocopy::IntoBytes,
+        zerocopy::KnownLayout,
+        zerocopy::Unaligned
+    )
+)]
 #[repr(transparent)]
-pub struct ControlledOption<T>
+pub struct ControlledOption<T, S = DefaultNiche>
 where
-    T: Niche,
+    S: NicheStrategy<T>,
 {
-    value: T::Output,
+    value: S::Output,
+    _phantom: PhantomData<T>,
 }
 
 impl<T> ControlledOption<T>
@@ -81,59 +588,154 @@ where
     /// Creates a new `None` instance for this option.
     #[inline]
     pub fn none() -> ControlledOption<T> {
-        let value = T::none();
-        debug_assert!(T::is_none(&value));
-        ControlledOption { value }
+        ControlledOption::none_with_strategy()
     }
 
     /// Creates a new `Some` instance for this option.
     #[inline]
     pub fn some(value: T) -> ControlledOption<T> {
-        let value = T::into_some(value);
-        debug_assert!(!T::is_none(&value));
-        ControlledOption { value }
+        ControlledOption::some_with_strategy(value)
+    }
+
+    /// Transforms an [`Option`][] into a `ControlledOption`.
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn from_option(value: Option<T>) -> ControlledOption<T> {
+        ControlledOption::from_option_with_strategy(value)
+    }
+}
+
+impl<T> ControlledOption<T>
+where
+    T: DenseNiche,
+{
+    /// Creates an option from its raw output representation.  This is safe because every output
+    /// value of a [`DenseNiche`][] type is either the niche or a valid `Some` value.
+    #[inline]
+    pub fn from_output(output: T::Output) -> ControlledOption<T> {
+        ControlledOption::from_repr(output)
+    }
+}
+
+impl<T> ControlledOption<T>
+where
+    T: ConstNiche,
+{
+    /// A `None` instance for this option, which can be used in `const` and `static` items.
+    pub const NONE: ControlledOption<T> = ControlledOption::from_repr(T::NONE);
+
+    /// Creates a new `None` instance for this option in a `const` context.
+    #[inline]
+    pub const fn const_none() -> ControlledOption<T> {
+        ControlledOption::from_repr(T::NONE)
+    }
+
+    /// Creates a new `Some` instance for this option in a `const` context.
+    #[inline]
+    pub const fn const_some(value: T) -> ControlledOption<T> {
+        union Transmute<T, U> {
+            from: ManuallyDrop<T>,
+            to: ManuallyDrop<U>,
+        }
+        assert!(core::mem::size_of::<T>() == core::mem::size_of::<T::Output>());
+        let value = Transmute::<T, T::Output> {
+            from: ManuallyDrop::new(value),
+        };
+        ControlledOption::from_repr(ManuallyDrop::into_inner(unsafe { value.to }))
+    }
+}
+
+impl<T, S> ControlledOption<T, S>
+where
+    S: NicheStrategy<T>,
+{
+    /// Creates a new `None` instance for this option, using a non-default [`NicheStrategy`][].
+    /// (The [`none`][ControlledOption::none] constructor always uses [`DefaultNiche`][], so that
+    /// the compiler can infer the strategy when you don't provide one.)
+    #[inline]
+    pub fn none_with_strategy() -> ControlledOption<T, S> {
+        let value = S::none();
+        debug_assert!(S::is_none(&value));
+        ControlledOption::from_repr(value)
+    }
+
+    /// Creates a new `Some` instance for this option, using a non-default [`NicheStrategy`][].
+    #[inline]
+    pub fn some_with_strategy(value: T) -> ControlledOption<T, S> {
+        let mut result = MaybeUninit::<ControlledOption<T, S>>::uninit();
+        let result = unsafe {
+            S::write_some(value, core::ptr::addr_of_mut!((*result.as_mut_ptr()).value));
+            result.assume_init()
+        };
+        debug_assert!(!S::is_none(&result.value));
+        result
+    }
+
+    /// Transforms an [`Option`][] into a `ControlledOption`, using a non-default
+    /// [`NicheStrategy`][].
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn from_option_with_strategy(value: Option<T>) -> ControlledOption<T, S> {
+        match value {
+            Some(value) => ControlledOption::some_with_strategy(value),
+            None => ControlledOption::none_with_strategy(),
+        }
+    }
+
+    #[inline]
+    const fn from_repr(value: S::Output) -> ControlledOption<T, S> {
+        ControlledOption {
+            value,
+            _phantom: PhantomData,
+        }
     }
 
     /// Returns `true` is the option is a `None` value.
     #[inline]
     pub fn is_none(&self) -> bool {
-        T::is_none(&self.value)
+        S::is_none(&self.value)
     }
 
     /// Returns `true` is the option is a `Some` value.
     #[inline]
     pub fn is_some(&self) -> bool {
-        !T::is_none(&self.value)
+        !S::is_none(&self.value)
     }
 
-    /// Transforms an [`Option`][] into a `ControlledOption`.
+    /// Transforms a `ControlledOption` into an [`Option`][].  This gives you access to all of the
+    /// usual assortment of useful methods that you expect from an `Option`.
     ///
     /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
     #[inline]
-    pub fn from_option(value: Option<T>) -> ControlledOption<T> {
-        value.into()
+    pub fn into_option(self) -> Option<T> {
+        self.into()
     }
 
-    /// Transforms a `ControlledOption` into an [`Option`][].  This gives you access to all of the
-    /// usual assortment of useful methods that you expect from an `Option`.
+    /// Converts from `&ControlledOption<T>` to an [`Option<&T>`][`Option`], without consuming the
+    /// original option.
     ///
     /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
     #[inline]
-    pub fn into_option(self) -> Option<T> {
+    pub fn as_option(&self) -> Option<&T> {
         self.into()
     }
 }
 
-impl<T> Default for ControlledOption<T>
+impl<T, S> Default for ControlledOption<T, S>
 where
-    T: Niche,
+    S: NicheStrategy<T>,
 {
     #[inline]
-    fn default() -> ControlledOption<T> {
-        ControlledOption::none()
+    fn default() -> ControlledOption<T, S> {
+        ControlledOption::none_with_strategy()
     }
 }
 
+// These conversions only use the default strategy; otherwise a call like
+// `ControlledOption::from(value)` would be ambiguous.
+
 impl<T> From<T> for ControlledOption<T>
 where
     T: Niche,
@@ -150,480 +752,1905 @@ where
 {
     #[inline]
     fn from(value: Option<T>) -> ControlledOption<T> {
-        match value {
-            Some(value) => ControlledOption::some(value),
-            None => ControlledOption::none(),
+        ControlledOption::from_option(value)
+    }
+}
+
+impl<T, S> From<ControlledOption<T, S>> for Option<T>
+where
+    S: NicheStrategy<T>,
+{
+    #[inline]
+    fn from(value: ControlledOption<T, S>) -> Option<T> {
+        if S::is_none(&value.value) {
+            None
+        } else {
+            let value = ManuallyDrop::new(value);
+            Some(unsafe { S::read_some(&value.value) })
         }
     }
 }
 
-impl<T> Into<Option<T>> for ControlledOption<T>
+impl<'a, T, S> From<&'a ControlledOption<T, S>> for Option<&'a T>
 where
-    T: Niche,
+    S: NicheStrategy<T>,
 {
     #[inline]
-    fn into(self) -> Option<T> {
-        if T::is_none(&self.value) {
+    fn from(value: &'a ControlledOption<T, S>) -> Option<&'a T> {
+        if S::is_none(&value.value) {
             None
         } else {
-            Some(T::from_some(self.value))
+            Some(S::as_some(&value.value))
         }
     }
 }
 
 // Normally we would #[derive] all of these traits, but the auto-derived implementations all
-// require that T implement the trait as well.  In our case, we (usually) need T::Output to
+// require that T implement the trait as well.  In our case, we (usually) need S::Output to
 // implement the traits, not T itself.
 
-impl<T> Clone for ControlledOption<T>
+impl<T, S> Clone for ControlledOption<T, S>
 where
-    T: Niche,
-    T::Output: Clone,
+    S: NicheStrategy<T>,
+    S::Output: Clone,
 {
     fn clone(&self) -> Self {
-        ControlledOption {
-            value: self.value.clone(),
-        }
+        ControlledOption::from_repr(self.value.clone())
     }
 }
 
-impl<T> Copy for ControlledOption<T>
+impl<T, S> Copy for ControlledOption<T, S>
 where
-    T: Niche,
-    T::Output: Copy,
+    S: NicheStrategy<T>,
+    S::Output: Copy,
 {
 }
 
-impl<T> std::fmt::Debug for ControlledOption<T>
+impl<T, S> core::fmt::Debug for ControlledOption<T, S>
 where
-    T: std::fmt::Debug + Niche,
-    T::Output: Clone,
+    T: core::fmt::Debug,
+    S: NicheStrategy<T>,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        if self.is_none() {
-            write!(f, "ControlledOption::None")
-        } else {
-            f.debug_tuple("ControlledOption::Some")
-                .field(&T::from_some(self.value.clone()))
-                .finish()
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.as_option() {
+            Some(value) => f
+                .debug_tuple("ControlledOption::Some")
+                .field(value)
+                .finish(),
+            None => write!(f, "ControlledOption::None"),
         }
     }
 }
 
-impl<T> PartialEq for ControlledOption<T>
+// Equality, ordering, and hashing all use the semantics of `Option<T>`, comparing the underlying
+// values (and not their outputs), with `None` sorting before any `Some`.  The output
+// representation needn't preserve the order of the values (it might be encoded, for instance), and
+// the niche can be any value.  Using the values for all three keeps them consistent with each
+// other, as `Ord` and `Hash` require.
+
+impl<T, S> PartialEq for ControlledOption<T, S>
 where
-    T: Niche,
-    T::Output: PartialEq,
+    T: PartialEq,
+    S: NicheStrategy<T>,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.value.eq(&other.value)
+        self.as_option() == other.as_option()
+    }
+}
+
+impl<T, S> Eq for ControlledOption<T, S>
+where
+    T: Eq,
+    S: NicheStrategy<T>,
+{
+}
+
+impl<T, S> PartialOrd for ControlledOption<T, S>
+where
+    T: PartialOrd,
+    S: NicheStrategy<T>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.as_option().partial_cmp(&other.as_option())
+    }
+}
+
+impl<T, S> Ord for ControlledOption<T, S>
+where
+    T: Ord,
+    S: NicheStrategy<T>,
+{
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.as_option().cmp(&other.as_option())
     }
+}
 
-    fn ne(&self, other: &Self) -> bool {
-        self.value.ne(&other.value)
+// You can also compare a `ControlledOption<T>` directly with an `Option<T>` or a `T`.  These
+// comparisons use the same `Option<T>` semantics.
+
+impl<T, S> PartialEq<Option<T>> for ControlledOption<T, S>
+where
+    T: PartialEq,
+    S: NicheStrategy<T>,
+{
+    fn eq(&self, other: &Option<T>) -> bool {
+        self.as_option() == other.as_ref()
     }
 }
 
-impl<T> Eq for ControlledOption<T>
+impl<T, S> PartialEq<ControlledOption<T, S>> for Option<T>
 where
-    T: Niche,
-    T::Output: Eq,
+    T: PartialEq,
+    S: NicheStrategy<T>,
 {
+    fn eq(&self, other: &ControlledOption<T, S>) -> bool {
+        self.as_ref() == other.as_option()
+    }
 }
 
-impl<T> PartialOrd for ControlledOption<T>
+impl<T, S> PartialEq<T> for ControlledOption<T, S>
 where
-    T: Niche,
-    T::Output: PartialOrd,
+    T: PartialEq,
+    S: NicheStrategy<T>,
 {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.value.partial_cmp(&other.value)
+    fn eq(&self, other: &T) -> bool {
+        self.as_option() == Some(other)
     }
+}
+
+impl<T, S> PartialOrd<Option<T>> for ControlledOption<T, S>
+where
+    T: PartialOrd,
+    S: NicheStrategy<T>,
+{
+    fn partial_cmp(&self, other: &Option<T>) -> Option<core::cmp::Ordering> {
+        self.as_option().partial_cmp(&other.as_ref())
+    }
+}
+
+impl<T, S> PartialOrd<ControlledOption<T, S>> for Option<T>
+where
+    T: PartialOrd,
+    S: NicheStrategy<T>,
+{
+    fn partial_cmp(&self, other: &ControlledOption<T, S>) -> Option<core::cmp::Ordering> {
+        self.as_ref().partial_cmp(&other.as_option())
+    }
+}
+
+impl<T, S> PartialOrd<T> for ControlledOption<T, S>
+where
+    T: PartialOrd,
+    S: NicheStrategy<T>,
+{
+    fn partial_cmp(&self, other: &T) -> Option<core::cmp::Ordering> {
+        self.as_option().partial_cmp(&Some(other))
+    }
+}
+
+// A `ControlledOption<T>` is semantically an optional `T`, but the value that it actually stores
+// is a `T::Output`.  `Niche` is a safe trait that can choose any `Output` type (such as an `Rc`
+// that is shared with other clones), and so these auto traits must depend on both.
+
+unsafe impl<T, S> Send for ControlledOption<T, S>
+where
+    S: NicheStrategy<T>,
+    S::Output: Send,
+    T: Send,
+{
+}
+
+unsafe impl<T, S> Sync for ControlledOption<T, S>
+where
+    S: NicheStrategy<T>,
+    S::Output: Sync,
+    T: Sync,
+{
+}
+
+impl<T, S> Unpin for ControlledOption<T, S>
+where
+    S: NicheStrategy<T>,
+    T: Unpin,
+{
+}
+
+impl<T, S> core::hash::Hash for ControlledOption<T, S>
+where
+    T: core::hash::Hash,
+    S: NicheStrategy<T>,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: core::hash::Hasher,
+    {
+        self.as_option().hash(state)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Structs and enums
+//
+// The ‘controlled-option-macros’ crate provides a derive macro for the ‘Niche’ trait.  The derived
+// implementation for structs depends on the following functions to get access to the field that
+// you want to use as the struct's niche.  (Enums don't need any helpers, since their niche is a
+// regular unit variant.)
 
-    fn lt(&self, other: &Self) -> bool {
-        self.value.lt(&other.value)
+/// Automatically derives a [`Niche`][] implementation for a struct, enum, or union type.
+///
+/// For a struct, you must mark one of the fields with a `#[niche]` attribute.  This field's type
+/// must already implement [`Niche`][].  The `None` value for the struct will be uninitialized
+/// memory, except for the chosen field, which will be filled in with its `None` niche value.
+/// (This requires that the [`Niche`][] implementation for the field's type must have the same
+/// layout for its `Self` and `Output` types.)
+///
+/// You can mark several candidate fields, giving each one a `#[niche(priority = N)]` argument.
+/// (A field without a priority has priority 0.)  The derive uses the candidate with the highest
+/// priority, so you can switch which niche the struct uses by adjusting the priorities, without
+/// having to restructure the type.  It's an error for two candidates to tie for the highest
+/// priority.  To use one of the other candidates in a particular context, give it a
+/// `#[niche(strategy = "ByField")]` argument.  The derive then generates a `ByField`
+/// [`NicheStrategy`][] that uses that field's niche, which you can use as the second type
+/// parameter of a [`ControlledOption`][].
+///
+/// You don't need to mark the field if the struct only has one field.  And if the struct is also
+/// `#[repr(transparent)]` (possibly with some extra `PhantomData` fields), the derive delegates
+/// directly to the field's [`Niche`][] implementation, so the struct uses the same `Output` type
+/// as the field.
+///
+/// The derived implementation requires the niche field's type to implement [`Niche`][], by adding
+/// a `FieldType: Niche` bound to its where clause.  If that bound is wrong for your generic
+/// parameters, you can replace it by adding a `#[niche(bound = "...")]` attribute to the struct,
+/// containing a comma-separated list of where predicates.  An empty string suppresses the
+/// automatic bound without adding any others.
+///
+/// If the niche field's type doesn't implement [`Niche`][] (for instance, because it comes from
+/// another crate), you can mark it with `#[niche(with = "module")]` instead.  The module must
+/// contain a `none` function, which returns the niche value, and an `is_none` function, which
+/// checks for it.  Both use some representation type `R`, which must have the same layout as the
+/// field's type:
+///
+/// ```ignore
+/// fn none() -> R;
+/// fn is_none(value: &R) -> bool;
+/// ```
+///
+/// For a field with a plain type, such as `u32`, you can use `#[niche(sentinel = expr)]` instead.
+/// The derive fills in the field with `expr` to represent `None`, and compares the field against
+/// `expr` (using `PartialEq`) to check for it.  You must never create a `Some` value whose field
+/// is equal to the sentinel.
+///
+/// If you need the `None` value to have a consistent bit pattern (for instance, so that you can
+/// hash or compare the raw bytes of an option), add a `#[niche(zero)]` attribute to the struct.
+/// The other fields will then be zeroed instead of left uninitialized.  If the niche field's type
+/// implements [`ZeroNiche`][], the struct's `None` value is then all-zero bytes, and so the
+/// derive implements [`ZeroNiche`][] for the struct as well.  (It does the same for a
+/// `#[repr(transparent)]` struct whose field implements [`ZeroNiche`][], for a struct with a
+/// `#[niche(output)]` type, and for a field-less enum whose niche discriminant is `0`.)
+///
+/// By default, the niche field is stored in the `Output` value as-is, and the derive reinterprets
+/// it as the field type's own `Output` type to fill in and check the niche.  If you add a
+/// `#[niche(repr)]` attribute to the struct, the derive instead generates a parallel
+/// `FooNicheRepr` struct, in which the niche field is stored as its `Output` type, and uses the
+/// field type's [`Niche`][] implementation to convert it.  The struct is stored as a
+/// `MaybeUninit` of that repr struct.  (Since [`as_some`][Niche::as_some] must still return a
+/// reference to the original struct, the two structs must have the same layout, and so the struct
+/// must be `#[repr(C)]` or `#[repr(transparent)]`, which the repr struct copies.)
+///
+/// If you'd rather not have any uninitialized memory in the `Output` type, add a
+/// `#[niche(mirror)]` attribute to the struct instead.  The derive generates the same
+/// `FooNicheRepr` struct, but uses it directly as the `Output` type.  Its `None` value fills in
+/// every field other than the niche field with its [`Default`][] value, and so every field other
+/// than the niche field must implement [`Default`][].  Because the repr struct is always fully
+/// initialized, it can implement the usual traits; you can list the ones to derive for it in the
+/// argument, as in `#[niche(mirror = "Clone, Copy, Debug, PartialEq, Eq, Hash")]`.  (The layout
+/// requirement for [`as_some`][Niche::as_some] still applies.)
+///
+/// A `MaybeUninit` never drops its contents, so by default, the derive produces a compile-time
+/// error if the struct needs to be dropped (for instance, because one of its fields is a
+/// `String` or `Vec`).  If you add a `#[niche(drop)]` attribute to the struct, it is stored as a
+/// [`DropRepr`][] instead, which drops `Some` values properly.  (An option of a `#[niche(drop)]`
+/// struct can't be `Copy`, but it is `Clone` if the struct is.)
+///
+/// By default, the struct is stored as a `MaybeUninit<Self>`.  If you'd rather store it as some
+/// other type, such as a `#[repr(C)]` struct of raw field values that you can pass across an FFI
+/// boundary, add a `#[niche(output = "Type")]` attribute to the struct.  That type must have the
+/// same layout as the struct, and all-zero bytes must be a valid value of it, since the `None`
+/// value is zeroed except for the niche field.  Since the derive can't check that, you must
+/// implement the unsafe [`NicheOutput`][] trait for the output type.  The output type can't drop
+/// the struct's contents, so as with `MaybeUninit`, the struct must not need to be dropped.  In
+/// both cases, the derive overrides
+/// [`write_some`][Niche::write_some] and [`read_some`][Niche::read_some], so that wrapping and
+/// unwrapping a large struct moves it directly into and out of the option.
+///
+/// If a struct's invalid state spans several fields (for instance, a raw slice whose pointer and
+/// length are both zero), add a `#[niche(none_value = expr)]` attribute to the struct instead of
+/// marking a field.  The expression must be a constant of the `Output` type, such as a call to a
+/// `const fn`.  The derive uses it as the `None` value, and checks for it using the `Output`
+/// type's [`PartialEq`][] implementation, so you must never wrap a value equal to it in a `Some`.
+/// The `Output` type is the struct itself, unless you've also provided a `#[niche(output)]` type,
+/// and the derive also implements [`ConstNiche`][].
+///
+/// By default, the derive checks for `None` by calling the niche field's own
+/// [`is_none`][Niche::is_none] method.  If the niche field's type implements [`BitwiseNiche`][]
+/// (its `None` value is a single bit pattern, with no padding or uninitialized bytes, and
+/// [`is_none`][Niche::is_none] returns `true` for that bit pattern and no other), you can add a
+/// `#[niche(bitwise)]` attribute to the struct, though not together with `#[niche(path)]`.  The
+/// derive then compares the field's bytes directly against the bytes of the `None` value, which
+/// is a single branchless comparison for fields of up to 16 bytes, and which lets the compiler
+/// vectorize scans over large arrays of optional structs.
+///
+/// If the niche is a field of a nested struct, you can reach it by adding a
+/// `#[niche(path = "inner.id")]` attribute to the outer struct instead of marking a field.  Each
+/// component of the path is a field name or a tuple index.  The derive can't see the type of the
+/// nested field, so if it depends on a type parameter, you must add the `Niche` bound yourself.
+///
+/// A union works just like a struct: you mark one of its members with `#[niche]`, and all of the
+/// struct attributes described above are supported.  The other members overlap the niche member,
+/// so you must ensure that no `Some` value ever has uninitialized bytes or the niche value in the
+/// niche member's bytes, and you vouch for that by implementing the unsafe [`NicheUnion`][]
+/// trait.
+///
+/// For an enum, you must mark one of the unit variants with a `#[niche]` attribute.  That variant
+/// is used as the `None` value, and the enum is stored as itself, so `ControlledOption<MyEnum>`
+/// has the same layout as `MyEnum`.  The derive also implements [`ConstNiche`][].  You must not
+/// wrap the `#[niche]` variant itself in a `Some`, since it would be indistinguishable from
+/// `None`.
+///
+/// If none of an enum's variants are marked `#[niche]`, then every variant must be a unit
+/// variant, and the enum must have a `#[repr]` attribute with a primitive integer type.  The
+/// derive chooses the largest value of that type that isn't used as a discriminant, and the enum
+/// is stored as that integer type.  You can choose a different unused value by adding a
+/// `#[niche(discriminant = N)]` attribute to the enum.  (Since a proc macro cannot evaluate
+/// constant expressions, `N` and any explicit discriminants must be integer literals.)
+///
+/// For any of these types, you can add a `#[niche(alias = "OptionalFoo")]` attribute to also
+/// generate a `type OptionalFoo = ControlledOption<Foo>` alias, with the same visibility and
+/// generic parameters as the type itself.
+///
+/// ```
+/// # use controlled_option::ControlledOption;
+/// # use controlled_option::Niche;
+/// #[derive(Clone, Copy, Debug, Niche, PartialEq)]
+/// enum State {
+///     #[niche]
+///     Invalid,
+///     Idle,
+///     Running(u32),
+/// }
+///
+/// let state = ControlledOption::some(State::Running(3));
+/// assert_eq!(state.into_option(), Some(State::Running(3)));
+/// assert!(ControlledOption::<State>::none().is_none());
+///
+/// #[derive(Clone, Copy, Debug, Niche, PartialEq)]
+/// #[repr(u8)]
+/// enum Color {
+///     Red,
+///     Green,
+///     Blue,
+/// }
+///
+/// assert_eq!(std::mem::size_of::<ControlledOption<Color>>(), 1);
+/// ```
+///
+/// The derive rejects the misuses described above at compile time.  A `#[niche(output)]` type
+/// must implement [`NicheOutput`][], and a union must implement [`NicheUnion`][]:
+///
+/// ```compile_fail,E0277
+/// # use std::num::NonZeroU32;
+/// # use controlled_option::Niche;
+/// #[repr(C)]
+/// pub struct RawPoint {
+///     id: u32,
+///     x: f32,
+/// }
+///
+/// #[derive(Clone, Copy, Niche)]
+/// #[niche(output = "RawPoint")]
+/// #[repr(C)]
+/// pub struct Point {
+///     #[niche]
+///     id: NonZeroU32,
+///     x: f32,
+/// }
+/// ```
+///
+/// ```compile_fail,E0277
+/// # use std::num::NonZeroU32;
+/// # use controlled_option::Niche;
+/// #[derive(Clone, Copy, Niche)]
+/// pub union Value {
+///     #[niche]
+///     id: NonZeroU32,
+///     bits: u32,
+/// }
+/// ```
+///
+/// `#[niche(repr)]` and `#[niche(mirror)]` require a `#[repr(C)]` or `#[repr(transparent)]`
+/// struct:
+///
+/// ```compile_fail
+/// # use std::num::NonZeroU32;
+/// # use controlled_option::Niche;
+/// #[derive(Clone, Copy, Niche)]
+/// #[niche(repr)]
+/// pub struct Point {
+///     #[niche]
+///     id: NonZeroU32,
+///     x: f32,
+/// }
+/// ```
+///
+/// `#[niche(bitwise)]` requires a [`BitwiseNiche`][] field (which a NaN-boxed float is not, since
+/// it has many `None` bit patterns), and can't be combined with `#[niche(path)]`:
+///
+/// ```compile_fail,E0277
+/// # use controlled_option::Niche;
+/// # use controlled_option::NonNanF32;
+/// #[derive(Clone, Copy, Niche)]
+/// #[niche(bitwise)]
+/// pub struct Sample {
+///     #[niche]
+///     value: NonNanF32,
+///     weight: f32,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use std::num::NonZeroU32;
+/// # use controlled_option::Niche;
+/// #[derive(Clone, Copy)]
+/// pub struct Inner {
+///     id: NonZeroU32,
+/// }
+///
+/// #[derive(Clone, Copy, Niche)]
+/// #[niche(bitwise, path = "inner.id")]
+/// pub struct Outer {
+///     inner: Inner,
+///     x: f32,
+/// }
+/// ```
+///
+/// And a struct that needs to be dropped can't be wrapped in an option without `#[niche(drop)]`.
+/// (The same goes for tuples and arrays, which are also stored as a `MaybeUninit`.)
+///
+/// ```compile_fail,E0080
+/// # use std::num::NonZeroU32;
+/// # use controlled_option::ControlledOption;
+/// # use controlled_option::Niche;
+/// #[derive(Clone, Niche)]
+/// pub struct Named {
+///     #[niche]
+///     id: NonZeroU32,
+///     name: String,
+/// }
+///
+/// let id = NonZeroU32::new(1).unwrap();
+/// let named = ControlledOption::some(Named { id, name: String::new() });
+/// ```
+///
+/// ```compile_fail,E0080
+/// # use std::num::NonZeroU32;
+/// # use controlled_option::ControlledOption;
+/// let id = NonZeroU32::new(1).unwrap();
+/// let pair = ControlledOption::some((id, String::new()));
+/// ```
+pub use controlled_option_macros::Niche;
+
+/// Rewrites the `Option` fields of a struct to use [`ControlledOption`][] instead.
+///
+/// Add this attribute to a struct with named fields, and mark each field that you want to
+/// rewrite with `#[controlled]`.  Each marked field must have an `Option<T>` type, where `T`
+/// implements [`Niche`][]; the attribute replaces it with a `ControlledOption<T>`.  It also
+/// generates accessors that use a plain `Option`, with the same visibility as the field: a `foo`
+/// method that returns an `Option<&T>`, a `set_foo` method that takes an `Option<T>`, and a
+/// `take_foo` method that returns an `Option<T>`, leaving `None` in its place.  (If the field has
+/// any `#[cfg]` attributes, the accessors have them too.)  That lets you adopt the more compact
+/// representation for a large existing struct one field at a time.
+///
+/// ```
+/// # use controlled_option::controlled;
+/// # use controlled_option::ControlledOption;
+/// use std::num::NonZeroU32;
+///
+/// #[controlled]
+/// struct Node {
+///     #[controlled]
+///     parent: Option<NonZeroU32>,
+///     children: Vec<NonZeroU32>,
+/// }
+///
+/// let mut node = Node {
+///     parent: ControlledOption::none(),
+///     children: Vec::new(),
+/// };
+/// assert_eq!(node.parent(), None);
+/// node.set_parent(NonZeroU32::new(1));
+/// assert_eq!(node.take_parent(), NonZeroU32::new(1));
+/// assert_eq!(std::mem::size_of_val(&node.parent), 4);
+/// ```
+pub use controlled_option_macros::controlled;
+
+/// Implements a [`NicheStrategy`][] for a type that you don't own.
+///
+/// The orphan rule prevents you from implementing [`Niche`][] for a type from another crate.  This
+/// macro lets you describe that type's fields instead, in the same way as
+/// [`#[derive(Niche)]`][derive@Niche], and generates a local strategy type that uses the niche
+/// of one of them.  You can then use that strategy as the second type parameter of a
+/// [`ControlledOption`][].
+///
+/// Give the description the name that you want for the strategy type, and add a
+/// `#[niche(remote = "Type")]` attribute with the type that it describes.  Mark the niche field
+/// with `#[niche]`, which supports the same `with` and `sentinel` arguments as the derive.  The
+/// niche field must be visible from where you invoke the macro.  You only have to describe the
+/// fields that you can see, and the macro checks that each of them exists in the remote type
+/// with the type that you've given.  `#[niche(zero)]` and `#[niche(bound = "...")]` work just
+/// like they do in the derive.  Fields can have `#[cfg]` attributes; if any of the candidate
+/// niche fields do, the macro chooses the niche field separately for each configuration.
+///
+/// ```
+/// # use controlled_option::derive_niche_for;
+/// # use controlled_option::ControlledOption;
+/// use std::ops::Range;
+///
+/// derive_niche_for! {
+///     #[niche(remote = "Range<u32>")]
+///     struct RangeNiche {
+///         start: u32,
+///         #[niche(sentinel = u32::MAX)]
+///         end: u32,
+///     }
+/// }
+///
+/// let range = ControlledOption::<Range<u32>, RangeNiche>::some_with_strategy(1..5);
+/// assert_eq!(range.into_option(), Some(1..5));
+/// assert_eq!(std::mem::size_of::<ControlledOption<Range<u32>, RangeNiche>>(), 8);
+/// ```
+pub use controlled_option_macros::derive_niche_for;
+
+// The helpers reinterpret a field (or the whole struct) as another type, which is only sound if
+// the two types have the same layout.  These checks happen at compile time, when the derived
+// implementation instantiates each helper for a particular field type, so a mismatch is a build
+// error even in release builds.
+//
+// Matching sizes and alignments aren't enough on their own, though: the helpers also require
+// that field pointers are valid and properly aligned, and that the source value's bytes are a
+// valid value of the target type.  The compiler can't check those, so the helpers are all unsafe
+// to call, and the derive is responsible for only calling them when that holds.
+struct SameLayout<T, U>(PhantomData<(T, U)>);
+
+impl<T, U> SameLayout<T, U> {
+    const CHECK: () = assert!(
+        core::mem::size_of::<T>() == core::mem::size_of::<U>()
+            && core::mem::align_of::<T>() == core::mem::align_of::<U>(),
+        "#[derive(Niche)] requires the niche field and its representation to have the same layout"
+    );
+}
+
+#[doc(hidden)]
+pub unsafe fn fill_struct_field_with_none<T>(field: *mut T)
+where
+    T: Niche,
+{
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<T, T::Output>::CHECK;
+    let repr = field as *mut T::Output;
+    repr.write(T::none());
+}
+
+#[doc(hidden)]
+pub unsafe fn struct_field_is_none<T>(field: *const T) -> bool
+where
+    T: Niche,
+{
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<T, T::Output>::CHECK;
+    let repr = field as *const T::Output;
+    T::is_none(&*repr)
+}
+
+#[doc(hidden)]
+#[inline]
+pub unsafe fn struct_field_is_none_bitwise<T>(field: *const T) -> bool
+where
+    T: BitwiseNiche,
+{
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<T, T::Output>::CHECK;
+    let none = core::mem::ManuallyDrop::new(T::none());
+    let none = &*none as *const T::Output as *const u8;
+    let field = field as *const u8;
+    // The size is a constant, so this compiles down to a single comparison for the common sizes.
+    match core::mem::size_of::<T>() {
+        1 => *field == *none,
+        2 => read_word::<u16>(field) == read_word::<u16>(none),
+        4 => read_word::<u32>(field) == read_word::<u32>(none),
+        8 => read_word::<u64>(field) == read_word::<u64>(none),
+        16 => read_word::<u128>(field) == read_word::<u128>(none),
+        size => core::slice::from_raw_parts(field, size) == core::slice::from_raw_parts(none, size),
+    }
+}
+
+#[inline]
+unsafe fn read_word<W>(bytes: *const u8) -> W {
+    core::ptr::read_unaligned(bytes as *const W)
+}
+
+#[doc(hidden)]
+pub unsafe fn convert_struct_repr<T, U>(value: T) -> U {
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<T, U>::CHECK;
+    let value = core::mem::ManuallyDrop::new(value);
+    core::ptr::read(&*value as *const T as *const U)
+}
+
+#[doc(hidden)]
+pub unsafe fn write_struct_repr<T, U>(value: T, dest: *mut U) {
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<T, U>::CHECK;
+    (dest as *mut T).write(value)
+}
+
+#[doc(hidden)]
+pub unsafe fn read_struct_repr<T, U>(src: *const U) -> T {
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<T, U>::CHECK;
+    (src as *const T).read()
+}
+
+#[doc(hidden)]
+pub unsafe fn fill_struct_field_with<T, R>(field: *mut T, none: fn() -> R) {
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<T, R>::CHECK;
+    let repr = field as *mut R;
+    repr.write(none());
+}
+
+#[doc(hidden)]
+pub unsafe fn struct_field_is<T, R>(field: *const T, is_none: fn(&R) -> bool) -> bool {
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<T, R>::CHECK;
+    let repr = field as *const R;
+    is_none(&*repr)
+}
+
+#[doc(hidden)]
+pub unsafe fn struct_repr_as_some<R, T>(repr: &MaybeUninit<R>) -> &T {
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<R, T>::CHECK;
+    &*(repr.as_ptr() as *const T)
+}
+
+#[doc(hidden)]
+pub unsafe fn struct_mirror_as_some<R, T>(repr: &R) -> &T {
+    #[allow(clippy::let_unit_value)]
+    let () = SameLayout::<R, T>::CHECK;
+    &*(repr as *const R as *const T)
+}
+
+// The default derived representation is a `MaybeUninit<Self>`, which never drops its contents.
+// That would leak any resources owned by a `Some` value, so the derive refuses to use it for
+// types that need to be dropped, unless you ask for a `DropRepr` with #[niche(drop)].
+
+struct NoDrop<T>(PhantomData<T>);
+
+impl<T> NoDrop<T> {
+    const CHECK: () = assert!(
+        !core::mem::needs_drop::<T>(),
+        "a MaybeUninit representation cannot hold a type that needs to be dropped \
+         (use #[niche(drop)] when deriving Niche)"
+    );
+}
+
+#[doc(hidden)]
+pub fn assert_no_drop<T>() {
+    #[allow(clippy::let_unit_value)]
+    let () = NoDrop::<T>::CHECK;
+}
+
+#[doc(hidden)]
+pub trait DropNiche: Sized {
+    fn repr_is_none(value: &MaybeUninit<Self>) -> bool;
+}
+
+/// The output representation of a struct that derives [`Niche`][] with `#[niche(drop)]`.  It's
+/// laid out just like a `MaybeUninit` of the struct, but unlike `MaybeUninit`, it drops its
+/// contents (if they're not `None`) when it is dropped.
+///
+/// You cannot create one of these directly; they only appear as the `Output` type of derived
+/// [`Niche`][] implementations.
+#[repr(transparent)]
+pub struct DropRepr<T>(MaybeUninit<T>)
+where
+    T: DropNiche;
+
+#[doc(hidden)]
+impl<T> DropRepr<T>
+where
+    T: DropNiche,
+{
+    #[inline]
+    pub fn from_repr(repr: MaybeUninit<T>) -> DropRepr<T> {
+        DropRepr(repr)
+    }
+
+    #[inline]
+    pub fn new(value: T) -> DropRepr<T> {
+        DropRepr(MaybeUninit::new(value))
+    }
+
+    #[inline]
+    pub fn as_repr(&self) -> &MaybeUninit<T> {
+        &self.0
+    }
+
+    // Safety: The representation must not be `None`.
+    #[inline]
+    pub unsafe fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+        this.0.assume_init_read()
+    }
+
+    // Safety: The representation must not be `None`.
+    #[inline]
+    pub unsafe fn as_inner(&self) -> &T {
+        self.0.assume_init_ref()
+    }
+}
+
+impl<T> Drop for DropRepr<T>
+where
+    T: DropNiche,
+{
+    fn drop(&mut self) {
+        if !T::repr_is_none(&self.0) {
+            unsafe { self.0.assume_init_drop() };
+        }
+    }
+}
+
+impl<T> Clone for DropRepr<T>
+where
+    T: Clone + DropNiche,
+{
+    fn clone(&self) -> Self {
+        if T::repr_is_none(&self.0) {
+            // A `None` value doesn't own anything, so we can copy its bytes.
+            DropRepr(unsafe { core::ptr::read(&self.0) })
+        } else {
+            DropRepr::new(unsafe { self.0.assume_init_ref() }.clone())
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// References
+
+impl<T> Niche for &T {
+    type Output = *const T;
+
+    #[inline]
+    fn none() -> Self::Output {
+        core::ptr::null()
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_null()
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value
+    }
+
+    #[inline]
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn from_some(value: Self::Output) -> Self {
+        unsafe { &*value }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
+    }
+}
+
+impl<T> Niche for &mut T {
+    type Output = *mut T;
+
+    #[inline]
+    fn none() -> Self::Output {
+        core::ptr::null_mut()
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_null()
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value
+    }
+
+    #[inline]
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn from_some(value: Self::Output) -> Self {
+        unsafe { &mut *value }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
+    }
+}
+
+unsafe impl<T> ZeroNiche for &T {}
+
+unsafe impl<T> ZeroNiche for &mut T {}
+
+unsafe impl<T> BitwiseNiche for &T {}
+
+unsafe impl<T> BitwiseNiche for &mut T {}
+
+unsafe impl<T> ConstNiche for &T {
+    const NONE: Self::Output = core::ptr::null();
+}
+
+unsafe impl<T> ConstNiche for &mut T {
+    const NONE: Self::Output = core::ptr::null_mut();
+}
+
+// Slices and string slices are fat pointers, and so we can't use the implementations above.  The
+// niche is still a null data pointer; we use a length of zero so that the niche is all-zero bytes.
+// (These aren't `BitwiseNiche`s, since `is_none` only looks at the data pointer.)
+
+impl<T> Niche for &[T] {
+    type Output = *const [T];
+
+    #[inline]
+    fn none() -> Self::Output {
+        core::ptr::slice_from_raw_parts(core::ptr::null(), 0)
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_null()
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value
+    }
+
+    #[inline]
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn from_some(value: Self::Output) -> Self {
+        unsafe { &*value }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
+    }
+}
+
+impl<T> Niche for &mut [T] {
+    type Output = *mut [T];
+
+    #[inline]
+    fn none() -> Self::Output {
+        core::ptr::slice_from_raw_parts_mut(core::ptr::null_mut(), 0)
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_null()
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value
+    }
+
+    #[inline]
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn from_some(value: Self::Output) -> Self {
+        unsafe { &mut *value }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
+    }
+}
+
+impl Niche for &str {
+    type Output = *const str;
+
+    #[inline]
+    fn none() -> Self::Output {
+        core::ptr::slice_from_raw_parts(core::ptr::null::<u8>(), 0) as *const str
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_null()
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value
+    }
+
+    #[inline]
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn from_some(value: Self::Output) -> Self {
+        unsafe { &*value }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
+    }
+}
+
+impl Niche for &mut str {
+    type Output = *mut str;
+
+    #[inline]
+    fn none() -> Self::Output {
+        core::ptr::slice_from_raw_parts_mut(core::ptr::null_mut::<u8>(), 0) as *mut str
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_null()
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value
+    }
+
+    #[inline]
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn from_some(value: Self::Output) -> Self {
+        unsafe { &mut *value }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
+    }
+}
+
+unsafe impl<T> ZeroNiche for &[T] {}
+
+unsafe impl<T> ZeroNiche for &mut [T] {}
+
+unsafe impl ZeroNiche for &str {}
+
+unsafe impl ZeroNiche for &mut str {}
+
+unsafe impl<T> ConstNiche for &[T] {
+    const NONE: Self::Output = core::ptr::slice_from_raw_parts(core::ptr::null(), 0);
+}
+
+unsafe impl<T> ConstNiche for &mut [T] {
+    const NONE: Self::Output = core::ptr::slice_from_raw_parts_mut(core::ptr::null_mut(), 0);
+}
+
+unsafe impl ConstNiche for &str {
+    const NONE: Self::Output = core::ptr::slice_from_raw_parts(core::ptr::null::<u8>(), 0) as _;
+}
+
+unsafe impl ConstNiche for &mut str {
+    const NONE: Self::Output =
+        core::ptr::slice_from_raw_parts_mut(core::ptr::null_mut::<u8>(), 0) as _;
+}
+
+//-------------------------------------------------------------------------------------------------
+// Pointers
+
+impl<T> Niche for core::ptr::NonNull<T> {
+    type Output = *mut T;
+
+    #[inline]
+    fn none() -> Self::Output {
+        core::ptr::null_mut()
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_null()
     }
 
-    fn le(&self, other: &Self) -> bool {
-        self.value.le(&other.value)
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value.as_ptr()
     }
 
-    fn gt(&self, other: &Self) -> bool {
-        self.value.gt(&other.value)
+    #[inline]
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn from_some(value: Self::Output) -> Self {
+        unsafe { Self::new_unchecked(value) }
     }
 
-    fn ge(&self, other: &Self) -> bool {
-        self.value.ge(&other.value)
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
     }
 }
 
-impl<T> Ord for ControlledOption<T>
-where
-    T: Niche,
-    T::Output: Ord,
-{
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.value.cmp(&other.value)
-    }
+unsafe impl<T> ZeroNiche for core::ptr::NonNull<T> {}
 
-    fn max(self, other: Self) -> Self {
-        ControlledOption {
-            value: self.value.max(other.value),
-        }
-    }
+unsafe impl<T> DenseNiche for core::ptr::NonNull<T> {}
 
-    fn min(self, other: Self) -> Self {
-        ControlledOption {
-            value: self.value.min(other.value),
+unsafe impl<T> BitwiseNiche for core::ptr::NonNull<T> {}
+
+unsafe impl<T> ConstNiche for core::ptr::NonNull<T> {
+    const NONE: Self::Output = core::ptr::null_mut();
+}
+
+//-------------------------------------------------------------------------------------------------
+// Function pointers
+
+// Function pointers are never null, so we can use a null pointer as the niche.  Note that these
+// implementations only cover function pointers whose signatures don't have any higher-ranked
+// lifetimes; `fn(&T)` is shorthand for `for<'a> fn(&'a T)`, which is a different type than
+// `fn(&'a T)` for any particular `'a`.
+
+macro_rules! impl_niche_for_fn_pointers {
+    ($($arg:ident),*) => {
+        impl_niche_for_fn_pointers!(@impl [$($arg),*] fn($($arg),*) -> R);
+        impl_niche_for_fn_pointers!(@impl [$($arg),*] unsafe fn($($arg),*) -> R);
+        impl_niche_for_fn_pointers!(@impl [$($arg),*] extern "C" fn($($arg),*) -> R);
+        impl_niche_for_fn_pointers!(@impl [$($arg),*] unsafe extern "C" fn($($arg),*) -> R);
+    };
+
+    (@impl [$($arg:ident),*] $ty:ty) => {
+        impl<R, $($arg),*> Niche for $ty {
+            type Output = *const ();
+
+            #[inline]
+            fn none() -> Self::Output {
+                core::ptr::null()
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                value.is_null()
+            }
+
+            #[inline]
+            fn into_some(value: Self) -> Self::Output {
+                value as *const ()
+            }
+
+            #[inline]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            fn from_some(value: Self::Output) -> Self {
+                unsafe { core::mem::transmute::<*const (), Self>(value) }
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &Self {
+                unsafe { &*(value as *const Self::Output as *const Self) }
+            }
         }
-    }
 
-    fn clamp(self, min: Self, max: Self) -> Self {
-        ControlledOption {
-            value: self.value.clamp(min.value, max.value),
+        unsafe impl<R, $($arg),*> ZeroNiche for $ty {}
+
+        unsafe impl<R, $($arg),*> BitwiseNiche for $ty {}
+
+        unsafe impl<R, $($arg),*> ConstNiche for $ty {
+            const NONE: Self::Output = core::ptr::null();
         }
-    }
+    };
 }
 
-impl<T> std::hash::Hash for ControlledOption<T>
-where
-    T: Niche,
-    T::Output: std::hash::Hash,
-{
-    fn hash<H>(&self, state: &mut H)
-    where
-        H: std::hash::Hasher,
-    {
-        self.value.hash(state)
-    }
-}
+impl_niche_for_fn_pointers!();
+impl_niche_for_fn_pointers!(A);
+impl_niche_for_fn_pointers!(A, B);
+impl_niche_for_fn_pointers!(A, B, C);
+impl_niche_for_fn_pointers!(A, B, C, D);
+impl_niche_for_fn_pointers!(A, B, C, D, E);
+impl_niche_for_fn_pointers!(A, B, C, D, E, F);
+impl_niche_for_fn_pointers!(A, B, C, D, E, F, G);
+impl_niche_for_fn_pointers!(A, B, C, D, E, F, G, H);
+impl_niche_for_fn_pointers!(A, B, C, D, E, F, G, H, I);
+impl_niche_for_fn_pointers!(A, B, C, D, E, F, G, H, I, J);
+impl_niche_for_fn_pointers!(A, B, C, D, E, F, G, H, I, J, K);
+impl_niche_for_fn_pointers!(A, B, C, D, E, F, G, H, I, J, K, L);
 
 //-------------------------------------------------------------------------------------------------
-// Structs
-//
-// The ‘controlled-option-macros’ crate provides a derive macro for the ‘Niche’ trait.  The derived
-// implementation depends on the following functions to get access to the field that you want to
-// use as the struct's niche.
-
-/// Automatically derives a [`Niche`][] implementation for a struct type.
-///
-/// You must mark one of the fields with a `#[niche]` attribute.  This field's type must already
-/// implement [`Niche`][].  The `None` value for the struct will be uninitialized memory, except
-/// for the chosen field, which will be filled in with its `None` niche value.  (This requires that
-/// the [`Niche`][] implementation for the field's type must have the same layout for its `Self`
-/// and `Output` types.)
-pub use controlled_option_macros::Niche;
+// Non-zero types
 
-#[doc(hidden)]
-pub fn fill_struct_field_with_none<T>(field: *mut T)
-where
-    T: Niche,
-{
-    debug_assert!(Layout::new::<T>() == Layout::new::<T::Output>());
-    let repr = field as *mut T::Output;
-    unsafe { repr.write(T::none()) };
+macro_rules! impl_niche_for_nonzero {
+    ($($nonzero:ty => $primitive:ty),* $(,)?) => {
+        $(
+            impl Niche for $nonzero {
+                type Output = $primitive;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    0
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    *value == 0
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    value.get()
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    unsafe { Self::new_unchecked(value) }
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { &*(value as *const Self::Output as *const Self) }
+                }
+            }
+
+            unsafe impl ZeroNiche for $nonzero {}
+            unsafe impl DenseNiche for $nonzero {}
+            unsafe impl BitwiseNiche for $nonzero {}
+
+            unsafe impl ConstNiche for $nonzero {
+                const NONE: Self::Output = 0;
+            }
+        )*
+    };
 }
 
-#[doc(hidden)]
-pub fn struct_field_is_none<T>(field: *const T) -> bool
-where
-    T: Niche,
-{
-    debug_assert!(Layout::new::<T>() == Layout::new::<T::Output>());
-    let repr = field as *const T::Output;
-    T::is_none(unsafe { &*repr })
+impl_niche_for_nonzero! {
+    core::num::NonZeroI8 => i8,
+    core::num::NonZeroI16 => i16,
+    core::num::NonZeroI32 => i32,
+    core::num::NonZeroI64 => i64,
+    core::num::NonZeroI128 => i128,
+    core::num::NonZeroIsize => isize,
+    core::num::NonZeroU8 => u8,
+    core::num::NonZeroU16 => u16,
+    core::num::NonZeroU32 => u32,
+    core::num::NonZeroU64 => u64,
+    core::num::NonZeroU128 => u128,
+    core::num::NonZeroUsize => usize,
 }
 
 //-------------------------------------------------------------------------------------------------
-// References
+// Characters
 
-impl<'a, T> Niche for &'a T {
-    type Output = *const T;
+/// The niche value for `char`.  This is the first surrogate code point, which is never a valid
+/// Unicode scalar value.
+const CHAR_NICHE: u32 = 0xD800;
+
+impl Niche for char {
+    type Output = u32;
 
     #[inline]
     fn none() -> Self::Output {
-        std::ptr::null()
+        CHAR_NICHE
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        value.is_null()
+        *value == CHAR_NICHE
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value
+        value as u32
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { &*value }
+        unsafe { char::from_u32_unchecked(value) }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
     }
 }
 
-impl<'a, T> Niche for &'a mut T {
-    type Output = *mut T;
+unsafe impl BitwiseNiche for char {}
+
+unsafe impl ConstNiche for char {
+    const NONE: Self::Output = CHAR_NICHE;
+}
+
+// The remaining surrogate code points are all available as extra niches.
+const CHAR_LAST_SURROGATE: u32 = 0xDFFF;
+
+unsafe impl MultiNiche for char {
+    const EXTRA_NICHES: usize = (CHAR_LAST_SURROGATE - CHAR_NICHE) as usize;
+
+    #[inline]
+    fn extra_niche(index: usize) -> Self::Output {
+        debug_assert!(index < Self::EXTRA_NICHES);
+        CHAR_NICHE + 1 + index as u32
+    }
+
+    #[inline]
+    fn extra_niche_index(value: &Self::Output) -> Option<usize> {
+        if *value > CHAR_NICHE && *value <= CHAR_LAST_SURROGATE {
+            Some((*value - CHAR_NICHE - 1) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Booleans
+
+/// The niche value for `bool`.  A `bool` is always represented by a `0` or `1` byte, so we use
+/// the next value up.
+const BOOL_NICHE: u8 = 2;
+
+impl Niche for bool {
+    type Output = u8;
 
     #[inline]
     fn none() -> Self::Output {
-        std::ptr::null_mut()
+        BOOL_NICHE
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        value.is_null()
+        *value == BOOL_NICHE
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value
+        value as u8
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { &mut *value }
+        value != 0
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
+    }
+}
+
+unsafe impl BitwiseNiche for bool {}
+
+unsafe impl ConstNiche for bool {
+    const NONE: Self::Output = BOOL_NICHE;
+}
+
+unsafe impl MultiNiche for bool {
+    const EXTRA_NICHES: usize = (u8::MAX - BOOL_NICHE) as usize;
+
+    #[inline]
+    fn extra_niche(index: usize) -> Self::Output {
+        debug_assert!(index < Self::EXTRA_NICHES);
+        BOOL_NICHE + 1 + index as u8
+    }
+
+    #[inline]
+    fn extra_niche_index(value: &Self::Output) -> Option<usize> {
+        if *value > BOOL_NICHE {
+            Some((*value - BOOL_NICHE - 1) as usize)
+        } else {
+            None
+        }
     }
 }
 
 //-------------------------------------------------------------------------------------------------
-// Non-zero types
+// Orderings
 
-impl Niche for std::num::NonZeroI8 {
+/// The niche value for `Ordering`.  `Ordering` is `#[repr(i8)]`, and uses the values `-1`, `0`,
+/// and `1` for `Less`, `Equal`, and `Greater`, respectively.  We use the next value up.
+const ORDERING_NICHE: i8 = 2;
+
+impl Niche for core::cmp::Ordering {
     type Output = i8;
 
     #[inline]
     fn none() -> Self::Output {
-        0
+        ORDERING_NICHE
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        *value == 0
+        *value == ORDERING_NICHE
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value.get()
+        value as i8
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+        unsafe { core::mem::transmute::<i8, Self>(value) }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
     }
 }
 
-impl Niche for std::num::NonZeroI16 {
-    type Output = i16;
+unsafe impl BitwiseNiche for core::cmp::Ordering {}
+
+unsafe impl ConstNiche for core::cmp::Ordering {
+    const NONE: Self::Output = ORDERING_NICHE;
+}
+
+unsafe impl MultiNiche for core::cmp::Ordering {
+    const EXTRA_NICHES: usize = (i8::MAX - ORDERING_NICHE) as usize;
 
     #[inline]
-    fn none() -> Self::Output {
-        0
+    fn extra_niche(index: usize) -> Self::Output {
+        debug_assert!(index < Self::EXTRA_NICHES);
+        ORDERING_NICHE + 1 + index as i8
     }
 
     #[inline]
-    fn is_none(value: &Self::Output) -> bool {
-        *value == 0
+    fn extra_niche_index(value: &Self::Output) -> Option<usize> {
+        if *value > ORDERING_NICHE {
+            Some((*value - ORDERING_NICHE - 1) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Uninhabited types
+
+// An uninhabited type has no values, and so an option of one can only ever be `None`.  That
+// doesn't require any storage at all.  (Once the never type `!` is stable, it should get the same
+// implementation.)
+
+impl Niche for core::convert::Infallible {
+    type Output = ();
+
+    #[inline]
+    fn none() -> Self::Output {}
+
+    #[inline]
+    fn is_none(_value: &Self::Output) -> bool {
+        true
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value.get()
+        match value {}
     }
 
     #[inline]
-    fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+    fn from_some(_value: Self::Output) -> Self {
+        unreachable!("an option of an uninhabited type is always None")
+    }
+
+    #[inline]
+    fn as_some(_value: &Self::Output) -> &Self {
+        unreachable!("an option of an uninhabited type is always None")
     }
 }
 
-impl Niche for std::num::NonZeroI32 {
-    type Output = i32;
+unsafe impl ZeroNiche for core::convert::Infallible {}
+
+//-------------------------------------------------------------------------------------------------
+// Pinned pointers
+
+// A pinned pointer has exactly the same representation as the pointer itself, so we can reuse its
+// niche.  The only way to get a `Some` output is from an existing `Pin`, so re-pinning the pointer
+// in `from_some` doesn't violate any pinning guarantees.
+
+impl<P> Niche for core::pin::Pin<P>
+where
+    P: Niche + core::ops::Deref,
+{
+    type Output = P::Output;
 
     #[inline]
     fn none() -> Self::Output {
-        0
+        P::none()
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        *value == 0
+        P::is_none(value)
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value.get()
+        P::into_some(unsafe { core::pin::Pin::into_inner_unchecked(value) })
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+        unsafe { core::pin::Pin::new_unchecked(P::from_some(value)) }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        let pointer = P::as_some(value);
+        unsafe { &*(pointer as *const P as *const Self) }
     }
 }
 
-impl Niche for std::num::NonZeroI64 {
-    type Output = i64;
+unsafe impl<P> ZeroNiche for core::pin::Pin<P> where P: ZeroNiche + core::ops::Deref {}
+
+unsafe impl<P> BitwiseNiche for core::pin::Pin<P> where P: BitwiseNiche + core::ops::Deref {}
+
+unsafe impl<P> ConstNiche for core::pin::Pin<P>
+where
+    P: ConstNiche + core::ops::Deref,
+{
+    const NONE: Self::Output = P::NONE;
+}
+
+//-------------------------------------------------------------------------------------------------
+// Manually dropped values
+
+// We reuse the wrapped type's niche, but wrap its output type in `ManuallyDrop` as well, so that
+// dropping the option doesn't drop the wrapped value, even if the output type has drop glue.
+
+impl<T> Niche for core::mem::ManuallyDrop<T>
+where
+    T: Niche,
+{
+    type Output = core::mem::ManuallyDrop<T::Output>;
 
     #[inline]
     fn none() -> Self::Output {
-        0
+        core::mem::ManuallyDrop::new(T::none())
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        *value == 0
+        T::is_none(value)
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value.get()
+        core::mem::ManuallyDrop::new(T::into_some(core::mem::ManuallyDrop::into_inner(value)))
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+        core::mem::ManuallyDrop::new(T::from_some(core::mem::ManuallyDrop::into_inner(value)))
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        let inner = T::as_some(value);
+        unsafe { &*(inner as *const T as *const Self) }
+    }
+}
+
+unsafe impl<T> ZeroNiche for core::mem::ManuallyDrop<T> where T: ZeroNiche {}
+
+unsafe impl<T> DenseNiche for core::mem::ManuallyDrop<T> where T: DenseNiche {}
+
+unsafe impl<T> BitwiseNiche for core::mem::ManuallyDrop<T> where T: BitwiseNiche {}
+
+unsafe impl<T> ConstNiche for core::mem::ManuallyDrop<T>
+where
+    T: ConstNiche,
+{
+    const NONE: Self::Output = core::mem::ManuallyDrop::new(T::NONE);
+}
+
+//-------------------------------------------------------------------------------------------------
+// Cells
+
+// Cells reuse the wrapped type's niche, and wrap its output type in the same kind of cell.  To
+// hand out a reference to the cell in `as_some`, we find where the wrapped value lives inside of
+// the output type, and return a pointer to the same location that still points into the output's
+// cell.
+
+/// Returns a pointer to the `T` that lives somewhere within `value`, which must not be `None`.
+/// The result has the same provenance as `value`, which is important if `value` points into an
+/// `UnsafeCell`.
+///
+/// `Niche` is a safe trait, so we can't trust `as_some` to return a reference into `value`.  We
+/// check that it does (in release builds, too), since otherwise the offset would produce a wild
+/// pointer.
+#[inline]
+pub(crate) unsafe fn locate_some<T>(value: *mut T::Output) -> *mut T
+where
+    T: Niche,
+{
+    let inner = T::as_some(&*value) as *const T as usize;
+    let offset = inner
+        .checked_sub(value as usize)
+        .filter(|offset| offset + core::mem::size_of::<T>() <= core::mem::size_of::<T::Output>());
+    match offset {
+        Some(offset) => (value as *mut u8).add(offset) as *mut T,
+        None => panic!("Niche::as_some must return a reference into the value that it is given"),
     }
 }
 
-impl Niche for std::num::NonZeroIsize {
-    type Output = isize;
+impl<T> Niche for core::cell::Cell<T>
+where
+    T: Niche,
+{
+    type Output = core::cell::Cell<T::Output>;
 
     #[inline]
     fn none() -> Self::Output {
-        0
+        core::cell::Cell::new(T::none())
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        *value == 0
+        T::is_none(unsafe { &*value.as_ptr() })
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value.get()
+        core::cell::Cell::new(T::into_some(value.into_inner()))
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+        core::cell::Cell::new(T::from_some(value.into_inner()))
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(locate_some::<T>(value.as_ptr()) as *const Self) }
     }
 }
 
-impl Niche for std::num::NonZeroU8 {
-    type Output = u8;
+impl<T> Niche for core::cell::UnsafeCell<T>
+where
+    T: Niche,
+{
+    type Output = core::cell::UnsafeCell<T::Output>;
 
     #[inline]
     fn none() -> Self::Output {
-        0
+        core::cell::UnsafeCell::new(T::none())
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        *value == 0
+        T::is_none(unsafe { &*value.get() })
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value.get()
+        core::cell::UnsafeCell::new(T::into_some(value.into_inner()))
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+        core::cell::UnsafeCell::new(T::from_some(value.into_inner()))
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(locate_some::<T>(value.get()) as *const Self) }
     }
 }
 
-impl Niche for std::num::NonZeroU16 {
-    type Output = u16;
+unsafe impl<T> ZeroNiche for core::cell::Cell<T> where T: ZeroNiche {}
+
+unsafe impl<T> DenseNiche for core::cell::Cell<T> where T: DenseNiche {}
+
+unsafe impl<T> ZeroNiche for core::cell::UnsafeCell<T> where T: ZeroNiche {}
+
+unsafe impl<T> DenseNiche for core::cell::UnsafeCell<T> where T: DenseNiche {}
+
+unsafe impl<T> BitwiseNiche for core::cell::Cell<T> where T: BitwiseNiche {}
+
+unsafe impl<T> BitwiseNiche for core::cell::UnsafeCell<T> where T: BitwiseNiche {}
+
+unsafe impl<T> ConstNiche for core::cell::Cell<T>
+where
+    T: ConstNiche,
+{
+    const NONE: Self::Output = core::cell::Cell::new(T::NONE);
+}
+
+unsafe impl<T> ConstNiche for core::cell::UnsafeCell<T>
+where
+    T: ConstNiche,
+{
+    const NONE: Self::Output = core::cell::UnsafeCell::new(T::NONE);
+}
+
+//-------------------------------------------------------------------------------------------------
+// Transparent wrappers
+
+macro_rules! impl_niche_for_transparent_wrapper {
+    ($($wrapper:ident),* $(,)?) => {
+        $(
+            impl<T> Niche for $wrapper<T>
+            where
+                T: Niche,
+            {
+                type Output = T::Output;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    T::none()
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    T::is_none(value)
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    T::into_some(value.0)
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    $wrapper(T::from_some(value))
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    let inner = T::as_some(value);
+                    unsafe { &*(inner as *const T as *const Self) }
+                }
+            }
+
+            unsafe impl<T> ZeroNiche for $wrapper<T> where T: ZeroNiche {}
+
+            unsafe impl<T> DenseNiche for $wrapper<T> where T: DenseNiche {}
+
+            unsafe impl<T> BitwiseNiche for $wrapper<T> where T: BitwiseNiche {}
+
+            unsafe impl<T> ConstNiche for $wrapper<T>
+            where
+                T: ConstNiche,
+            {
+                const NONE: Self::Output = T::NONE;
+            }
+        )*
+    };
+}
+
+use core::cmp::Reverse;
+use core::num::Saturating;
+use core::num::Wrapping;
+
+impl_niche_for_transparent_wrapper!(Reverse, Saturating, Wrapping);
+
+//-------------------------------------------------------------------------------------------------
+// Tuples
+
+// Tuples use the niche of their first element, just like a struct that derives `Niche` with a
+// `#[niche]` attribute on its first field.  As with the derive, the tuple is stored as a
+// `MaybeUninit`, which never drops its contents, so the elements must not need to be dropped.
+// (We can't also provide implementations that use the niches of the other elements, since those
+// would overlap whenever more than one element has a niche.  If you need that, define a struct
+// and use the derive macro.)
+
+macro_rules! impl_niche_for_tuple {
+    ($first:ident $(, $rest:ident)*) => {
+        impl<$first, $($rest),*> Niche for ($first, $($rest,)*)
+        where
+            $first: Niche,
+        {
+            type Output = core::mem::MaybeUninit<Self>;
+
+            #[inline]
+            fn none() -> Self::Output {
+                let mut value = Self::Output::uninit();
+                let ptr = value.as_mut_ptr();
+                unsafe { fill_struct_field_with_none(core::ptr::addr_of_mut!((*ptr).0)) };
+                value
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                let ptr = value.as_ptr();
+                unsafe { struct_field_is_none(core::ptr::addr_of!((*ptr).0)) }
+            }
+
+            #[inline]
+            fn into_some(value: Self) -> Self::Output {
+                assert_no_drop::<Self>();
+                core::mem::MaybeUninit::new(value)
+            }
+
+            #[inline]
+            fn from_some(value: Self::Output) -> Self {
+                unsafe { value.assume_init() }
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &Self {
+                unsafe { &*value.as_ptr() }
+            }
+        }
+    };
+}
+
+impl_niche_for_tuple!(A);
+impl_niche_for_tuple!(A, B);
+impl_niche_for_tuple!(A, B, C);
+impl_niche_for_tuple!(A, B, C, D);
+impl_niche_for_tuple!(A, B, C, D, E);
+impl_niche_for_tuple!(A, B, C, D, E, F);
+
+//-------------------------------------------------------------------------------------------------
+// Arrays
+
+// Arrays use the niche of their first element.  Empty arrays don't have a first element, and so
+// `ControlledOption<[T; 0]>` fails to compile.  Like tuples, arrays are stored as a `MaybeUninit`,
+// and so the elements must not need to be dropped.
+
+struct NonEmptyArray<const N: usize>;
+
+impl<const N: usize> NonEmptyArray<N> {
+    const CHECK: () = assert!(N > 0, "an empty array does not have a niche");
+}
+
+impl<T, const N: usize> Niche for [T; N]
+where
+    T: Niche,
+{
+    type Output = core::mem::MaybeUninit<Self>;
 
     #[inline]
     fn none() -> Self::Output {
-        0
+        #[allow(clippy::let_unit_value)]
+        let () = NonEmptyArray::<N>::CHECK;
+        let mut value = Self::Output::uninit();
+        unsafe { fill_struct_field_with_none(value.as_mut_ptr() as *mut T) };
+        value
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        *value == 0
+        #[allow(clippy::let_unit_value)]
+        let () = NonEmptyArray::<N>::CHECK;
+        unsafe { struct_field_is_none(value.as_ptr() as *const T) }
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value.get()
+        assert_no_drop::<Self>();
+        core::mem::MaybeUninit::new(value)
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+        unsafe { value.assume_init() }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*value.as_ptr() }
     }
 }
 
-impl Niche for std::num::NonZeroU32 {
-    type Output = u32;
+//-------------------------------------------------------------------------------------------------
+// Layouts
+
+// A layout's alignment is never zero, so the all-zero bit pattern is not a valid layout.  (We
+// can't look at the alignment field directly, since we don't know where it lives inside of the
+// layout, but a layout doesn't contain any padding, so it's safe to look at all of its bytes.)
+
+impl Niche for Layout {
+    type Output = core::mem::MaybeUninit<Layout>;
 
     #[inline]
     fn none() -> Self::Output {
-        0
+        core::mem::MaybeUninit::zeroed()
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        *value == 0
+        let bytes = unsafe {
+            core::slice::from_raw_parts(value.as_ptr() as *const u8, core::mem::size_of::<Layout>())
+        };
+        bytes.iter().all(|byte| *byte == 0)
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value.get()
+        core::mem::MaybeUninit::new(value)
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+        unsafe { value.assume_init() }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*value.as_ptr() }
     }
 }
 
-impl Niche for std::num::NonZeroU64 {
-    type Output = u64;
+unsafe impl ZeroNiche for Layout {}
+
+//-------------------------------------------------------------------------------------------------
+// Nested options
+
+// A `ControlledOption<T>` uses the same output type as `T`, and represents its `None` using `T`'s
+// first extra niche.  Any remaining extra niches are passed along, so that you can nest options
+// as deeply as `T` allows.
+
+struct HasExtraNiche<T>(PhantomData<T>);
+
+impl<T> HasExtraNiche<T>
+where
+    T: MultiNiche,
+{
+    const CHECK: () = assert!(
+        T::EXTRA_NICHES > 0,
+        "a nested option requires an extra niche value"
+    );
+}
+
+impl<T> Niche for ControlledOption<T>
+where
+    T: MultiNiche,
+{
+    type Output = T::Output;
 
     #[inline]
     fn none() -> Self::Output {
-        0
+        #[allow(clippy::let_unit_value)]
+        let () = HasExtraNiche::<T>::CHECK;
+        T::extra_niche(0)
     }
 
     #[inline]
     fn is_none(value: &Self::Output) -> bool {
-        *value == 0
+        T::extra_niche_index(value) == Some(0)
     }
 
     #[inline]
     fn into_some(value: Self) -> Self::Output {
-        value.get()
+        value.value
     }
 
     #[inline]
     fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+        ControlledOption::from_repr(value)
     }
-}
-
-impl Niche for std::num::NonZeroUsize {
-    type Output = usize;
 
     #[inline]
-    fn none() -> Self::Output {
-        0
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
     }
+}
 
-    #[inline]
-    fn is_none(value: &Self::Output) -> bool {
-        *value == 0
-    }
+unsafe impl<T> MultiNiche for ControlledOption<T>
+where
+    T: MultiNiche,
+{
+    const EXTRA_NICHES: usize = T::EXTRA_NICHES.saturating_sub(1);
 
     #[inline]
-    fn into_some(value: Self) -> Self::Output {
-        value.get()
+    fn extra_niche(index: usize) -> Self::Output {
+        T::extra_niche(index + 1)
     }
 
     #[inline]
-    fn from_some(value: Self::Output) -> Self {
-        unsafe { Self::new_unchecked(value) }
+    fn extra_niche_index(value: &Self::Output) -> Option<usize> {
+        T::extra_niche_index(value)?.checked_sub(1)
     }
 }
@@ -0,0 +1,191 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use crate::ControlledOption;
+use crate::GenerationalId;
+use crate::Niche;
+use crate::NonMaxU16;
+use crate::NonMaxU32;
+use crate::NonMaxU64;
+use crate::NonMaxU8;
+use crate::NonMaxUsize;
+use crate::Symbol;
+
+/// A [`Niche`][] type that identifies an element of a slice.
+pub trait LinkIndex: Copy + Niche {
+    /// Returns the position in the slice of the element that this value identifies.
+    fn to_index(self) -> usize;
+}
+
+macro_rules! impl_link_index_for_nonmax {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl LinkIndex for $ty {
+                #[inline]
+                fn to_index(self) -> usize {
+                    self.get() as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_link_index_for_nonmax!(NonMaxU8, NonMaxU16, NonMaxU32, NonMaxU64, NonMaxUsize);
+
+impl LinkIndex for Symbol {
+    #[inline]
+    fn to_index(self) -> usize {
+        self.index() as usize
+    }
+}
+
+impl LinkIndex for GenerationalId {
+    #[inline]
+    fn to_index(self) -> usize {
+        self.index() as usize
+    }
+}
+
+/// A link to the next element of an intrusive singly-linked list, whose elements all live in a
+/// single slice.
+///
+/// The link stores the index of the next element, and uses the niche of the index type to
+/// represent the end of the list, so you don't need to hand-roll a sentinel index.  You can use
+/// the same type for the head of the list.  The list operations take the slice that contains the
+/// elements, along with a function that finds the link within each element.  (This lets an
+/// element belong to more than one list at a time, by containing more than one link.)  A common
+/// use is a free list, where each vacant slot of an arena links to the next vacant slot.
+#[repr(transparent)]
+pub struct NicheLink<T>
+where
+    T: Niche,
+{
+    next: ControlledOption<T>,
+}
+
+impl<T> NicheLink<T>
+where
+    T: LinkIndex,
+{
+    /// Creates a link that marks the end of a list.
+    #[inline]
+    pub fn none() -> NicheLink<T> {
+        NicheLink {
+            next: ControlledOption::none(),
+        }
+    }
+
+    /// Creates a link to the element at `next`.
+    #[inline]
+    pub fn to(next: T) -> NicheLink<T> {
+        NicheLink {
+            next: ControlledOption::some(next),
+        }
+    }
+
+    /// Returns the index of the element that this link points to, or `None` if this link marks
+    /// the end of a list.
+    #[inline]
+    pub fn get(&self) -> Option<T> {
+        self.next.as_option().copied()
+    }
+
+    /// Returns whether this link marks the end of a list.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.next.is_none()
+    }
+
+    /// Adds the element at `index` to the front of the list that starts at this link.
+    #[inline]
+    pub fn push_front<N, F>(&mut self, nodes: &mut [N], index: T, mut link: F)
+    where
+        F: FnMut(&mut N) -> &mut NicheLink<T>,
+    {
+        let node = link(&mut nodes[index.to_index()]);
+        *node = core::mem::replace(self, NicheLink::to(index));
+    }
+
+    /// Removes the first element of the list that starts at this link, returning its index.
+    #[inline]
+    pub fn pop_front<N, F>(&mut self, nodes: &mut [N], mut link: F) -> Option<T>
+    where
+        F: FnMut(&mut N) -> &mut NicheLink<T>,
+    {
+        let head = self.get()?;
+        *self = core::mem::take(link(&mut nodes[head.to_index()]));
+        Some(head)
+    }
+
+    /// Returns an iterator over the indices of the elements in the list that starts at this
+    /// link.
+    #[inline]
+    pub fn iter<'a, N, F>(&self, nodes: &'a [N], link: F) -> NicheLinkIter<'a, T, N, F>
+    where
+        T: 'a,
+        F: FnMut(&'a N) -> &'a NicheLink<T>,
+    {
+        NicheLinkIter {
+            next: self.get(),
+            nodes,
+            link,
+        }
+    }
+}
+
+impl<T> Default for NicheLink<T>
+where
+    T: LinkIndex,
+{
+    #[inline]
+    fn default() -> NicheLink<T> {
+        NicheLink::none()
+    }
+}
+
+impl<T> Clone for NicheLink<T>
+where
+    T: LinkIndex,
+{
+    #[inline]
+    fn clone(&self) -> NicheLink<T> {
+        NicheLink {
+            next: ControlledOption::from_option(self.get()),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for NicheLink<T>
+where
+    T: core::fmt::Debug + LinkIndex,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_tuple("NicheLink").field(&self.get()).finish()
+    }
+}
+
+/// An iterator over the indices of the elements in an intrusive list.
+pub struct NicheLinkIter<'a, T, N, F> {
+    next: Option<T>,
+    nodes: &'a [N],
+    link: F,
+}
+
+impl<'a, T, N, F> Iterator for NicheLinkIter<'a, T, N, F>
+where
+    T: LinkIndex + 'a,
+    F: FnMut(&'a N) -> &'a NicheLink<T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        let current = self.next?;
+        self.next = (self.link)(&self.nodes[current.to_index()]).get();
+        Some(current)
+    }
+}
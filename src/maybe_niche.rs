@@ -0,0 +1,126 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use crate::Niche;
+use crate::NicheStrategy;
+
+/// A wrapper that lets you use any type in a [`ControlledOption`][crate::ControlledOption], even
+/// if it doesn't implement [`Niche`][].
+///
+/// The option is stored as a standard `Option<T>`, so the compiler decides how to represent it —
+/// typically with an explicit discriminant, unless `T` has a niche that the compiler knows about.
+/// Generic code can require `T: Niche`, and callers can wrap any types that don't have a niche of
+/// their own in `MaybeNiche`.
+///
+/// (Ideally `ControlledOption<T>` would fall back on this representation automatically for any `T`
+/// that doesn't implement `Niche`, but that would require specialization.  If you want generic
+/// code to use `T`'s niche whenever it has one, give it a [`NicheStrategy`][] type parameter
+/// instead, and have callers choose between [`DefaultNiche`][crate::DefaultNiche] and
+/// [`FallbackNiche`][].)
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct MaybeNiche<T>(pub T);
+
+impl<T> MaybeNiche<T> {
+    /// Returns the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for MaybeNiche<T> {
+    #[inline]
+    fn from(value: T) -> MaybeNiche<T> {
+        MaybeNiche(value)
+    }
+}
+
+impl<T> Niche for MaybeNiche<T> {
+    type Output = Option<T>;
+
+    #[inline]
+    fn none() -> Self::Output {
+        None
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_none()
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        Some(value.0)
+    }
+
+    #[inline]
+    fn from_some(value: Self::Output) -> Self {
+        MaybeNiche(unsafe { value.unwrap_unchecked() })
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        let inner = unsafe { value.as_ref().unwrap_unchecked() };
+        unsafe { &*(inner as *const T as *const Self) }
+    }
+}
+
+/// A [`NicheStrategy`][] that works for every type, by storing the option as a standard
+/// `Option<T>`.  This is the same representation that [`MaybeNiche`][] uses, but without having to
+/// wrap each value.
+///
+/// Generic code that stores a `ControlledOption<T, S>` for some `S: NicheStrategy<T>` can then be
+/// instantiated with [`DefaultNiche`][crate::DefaultNiche] to use `T`'s own niche, or with
+/// `FallbackNiche` for types that don't have one:
+///
+/// ```
+/// # use std::num::NonZeroU32;
+/// # use controlled_option::ControlledOption;
+/// # use controlled_option::DefaultNiche;
+/// # use controlled_option::FallbackNiche;
+/// # use controlled_option::NicheStrategy;
+/// struct Slot<T, S: NicheStrategy<T> = DefaultNiche> {
+///     value: ControlledOption<T, S>,
+/// }
+///
+/// let compact = Slot::<NonZeroU32> { value: ControlledOption::none() };
+/// let fallback = Slot::<String, FallbackNiche> { value: ControlledOption::none_with_strategy() };
+/// assert_eq!(std::mem::size_of_val(&compact), 4);
+/// assert!(fallback.value.is_none());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FallbackNiche;
+
+impl<T> NicheStrategy<T> for FallbackNiche {
+    type Output = Option<T>;
+
+    #[inline]
+    fn none() -> Self::Output {
+        None
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_none()
+    }
+
+    #[inline]
+    fn into_some(value: T) -> Self::Output {
+        Some(value)
+    }
+
+    #[inline]
+    fn from_some(value: Self::Output) -> T {
+        unsafe { value.unwrap_unchecked() }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &T {
+        unsafe { value.as_ref().unwrap_unchecked() }
+    }
+}
@@ -0,0 +1,79 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! `Niche` implementations for the types in the `nonmax` crate.
+//!
+//! Each `nonmax` type is a `#[repr(transparent)]` wrapper around a `NonZero` type, and so the
+//! standard library guarantees that an `Option` of it is the same size, with `None` represented
+//! by all-zero bytes.  We store the option as exactly that, which means that we never depend on
+//! how the crate encodes its values, and that options compare in the same order as the values.
+
+use crate::BitwiseNiche;
+use crate::ConstNiche;
+use crate::DenseNiche;
+use crate::Niche;
+use crate::StdNiche;
+use crate::ZeroNiche;
+
+macro_rules! impl_niche_for_nonmax {
+    ($($nonmax:ty),* $(,)?) => {
+        $(
+            impl Niche for $nonmax {
+                type Output = Option<Self>;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    None
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    value.is_none()
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    Some(value)
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    unsafe { value.unwrap_unchecked() }
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { value.as_ref().unwrap_unchecked() }
+                }
+            }
+
+            unsafe impl ZeroNiche for $nonmax {}
+            unsafe impl DenseNiche for $nonmax {}
+            unsafe impl BitwiseNiche for $nonmax {}
+            unsafe impl StdNiche for $nonmax {}
+
+            unsafe impl ConstNiche for $nonmax {
+                const NONE: Self::Output = None;
+            }
+        )*
+    };
+}
+
+impl_niche_for_nonmax! {
+    ::nonmax::NonMaxI8,
+    ::nonmax::NonMaxI16,
+    ::nonmax::NonMaxI32,
+    ::nonmax::NonMaxI64,
+    ::nonmax::NonMaxI128,
+    ::nonmax::NonMaxIsize,
+    ::nonmax::NonMaxU8,
+    ::nonmax::NonMaxU16,
+    ::nonmax::NonMaxU32,
+    ::nonmax::NonMaxU64,
+    ::nonmax::NonMaxU128,
+    ::nonmax::NonMaxUsize,
+}
@@ -0,0 +1,175 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! Floating-point types that cannot be NaN.
+//!
+//! Since these types exclude every NaN value, we can use the canonical NaN bit pattern as the
+//! niche, so that an optional value is still the same size as the underlying float.  Excluding
+//! NaN also means that these types can implement `Eq`, `Ord`, and `Hash`.  They use the IEEE 754
+//! total ordering, as implemented by `total_cmp`; this means that `-0.0` and `+0.0` are _not_
+//! equal, and that `-0.0` sorts before `+0.0`.
+
+use core::cmp::Ordering;
+use core::hash::Hash;
+use core::hash::Hasher;
+
+use crate::ConstNiche;
+use crate::MultiNiche;
+use crate::Niche;
+
+macro_rules! non_nan_float {
+    ($($(#[$attr:meta])* $name:ident => $primitive:ty, $bits:ty, $niche:expr);* $(;)?) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy)]
+            #[repr(transparent)]
+            pub struct $name($primitive);
+
+            impl $name {
+                /// The bit pattern of the canonical NaN value, which represents `None` in a
+                /// `ControlledOption`.
+                pub const NICHE: $bits = $niche;
+
+                /// Creates a new value, returning `None` if `value` is NaN.
+                #[inline]
+                pub const fn new(value: $primitive) -> Option<Self> {
+                    if value.is_nan() {
+                        None
+                    } else {
+                        Some($name(value))
+                    }
+                }
+
+                /// Creates a new value without checking whether it is NaN.
+                ///
+                /// # Safety
+                ///
+                /// `value` must not be NaN.
+                #[inline]
+                pub const unsafe fn new_unchecked(value: $primitive) -> Self {
+                    $name(value)
+                }
+
+                /// Returns the underlying floating-point value.
+                #[inline]
+                pub const fn get(self) -> $primitive {
+                    self.0
+                }
+            }
+
+            impl From<$name> for $primitive {
+                #[inline]
+                fn from(value: $name) -> $primitive {
+                    value.get()
+                }
+            }
+
+            impl PartialEq for $name {
+                #[inline]
+                fn eq(&self, other: &Self) -> bool {
+                    self.0.to_bits() == other.0.to_bits()
+                }
+            }
+
+            impl Eq for $name {}
+
+            impl PartialOrd for $name {
+                #[inline]
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for $name {
+                #[inline]
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.0.total_cmp(&other.0)
+                }
+            }
+
+            impl Hash for $name {
+                #[inline]
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.0.to_bits().hash(state)
+                }
+            }
+
+            impl core::fmt::Debug for $name {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            impl core::fmt::Display for $name {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            impl Niche for $name {
+                type Output = $bits;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    Self::NICHE
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    *value == Self::NICHE
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    value.0.to_bits()
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    $name(<$primitive>::from_bits(value))
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { &*(value as *const Self::Output as *const Self) }
+                }
+            }
+
+            unsafe impl ConstNiche for $name {
+                const NONE: Self::Output = Self::NICHE;
+            }
+
+            // The quiet NaNs that follow the canonical one are available as extra niches.
+            unsafe impl MultiNiche for $name {
+                const EXTRA_NICHES: usize = 0x3f_ffff;
+
+                #[inline]
+                fn extra_niche(index: usize) -> Self::Output {
+                    debug_assert!(index < Self::EXTRA_NICHES);
+                    Self::NICHE + 1 + index as $bits
+                }
+
+                #[inline]
+                fn extra_niche_index(value: &Self::Output) -> Option<usize> {
+                    let index = value.checked_sub(Self::NICHE + 1)?;
+                    if index < Self::EXTRA_NICHES as $bits {
+                        Some(index as usize)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+non_nan_float! {
+    /// An `f32` that is known not to be NaN.
+    NonNanF32 => f32, u32, 0x7fc0_0000;
+    /// An `f64` that is known not to be NaN.
+    NonNanF64 => f64, u64, 0x7ff8_0000_0000_0000;
+}
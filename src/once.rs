@@ -0,0 +1,230 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::Ordering;
+
+use crate::AtomicControlledOption;
+use crate::AtomicOutput;
+use crate::ControlledOption;
+use crate::Niche;
+
+/// A cell that can be written to at most once, which uses the niche of `T` to represent the empty
+/// state.  This is the counterpart of the standard `OnceCell` type.
+pub struct ControlledOnceCell<T>
+where
+    T: Niche,
+{
+    value: UnsafeCell<ControlledOption<T>>,
+}
+
+impl<T> ControlledOnceCell<T>
+where
+    T: Niche,
+{
+    /// Creates a new empty cell.
+    #[inline]
+    pub fn new() -> ControlledOnceCell<T> {
+        ControlledOnceCell {
+            value: UnsafeCell::new(ControlledOption::none()),
+        }
+    }
+
+    /// Returns a reference to the cell's value, or `None` if the cell is empty.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        // Safety: Once the cell has been filled, its value is never modified again except via
+        // a mutable reference, so it's safe to hand out shared references to it.
+        unsafe { &*self.value.get() }.as_option()
+    }
+
+    /// Returns a mutable reference to the cell's value, or `None` if the cell is empty.
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        let value = self.value.get_mut();
+        if value.is_none() {
+            None
+        } else {
+            Some(unsafe { &mut *crate::locate_some::<T>(&mut value.value) })
+        }
+    }
+
+    /// Fills the cell with `value`.  Returns `value` back to you as an error if the cell is
+    /// already full.
+    #[inline]
+    pub fn set(&self, value: T) -> Result<(), T> {
+        if self.get().is_some() {
+            return Err(value);
+        }
+        // Safety: The cell is empty, so there aren't any outstanding references to its value.
+        unsafe { *self.value.get() = ControlledOption::some(value) };
+        Ok(())
+    }
+
+    /// Returns a reference to the cell's value, filling it with the result of `f` if the cell is
+    /// empty.  Panics if `f` tries to fill the cell itself.
+    #[inline]
+    pub fn get_or_init<F>(&self, f: F) -> &T
+    where
+        F: FnOnce() -> T,
+    {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        let value = f();
+        if self.set(value).is_err() {
+            panic!("reentrant initialization of ControlledOnceCell");
+        }
+        self.get().unwrap()
+    }
+
+    /// Consumes the cell, returning its value.
+    #[inline]
+    pub fn into_inner(self) -> ControlledOption<T> {
+        self.value.into_inner()
+    }
+
+    /// Takes the value out of the cell, leaving it empty.
+    #[inline]
+    pub fn take(&mut self) -> ControlledOption<T> {
+        core::mem::take(self.value.get_mut())
+    }
+}
+
+impl<T> Default for ControlledOnceCell<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn default() -> ControlledOnceCell<T> {
+        ControlledOnceCell::new()
+    }
+}
+
+impl<T> core::fmt::Debug for ControlledOnceCell<T>
+where
+    T: core::fmt::Debug + Niche,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("ControlledOnceCell").field(value).finish(),
+            None => write!(f, "ControlledOnceCell(<uninit>)"),
+        }
+    }
+}
+
+/// A thread-safe cell that can be written to at most once, which uses the niche of `T` to
+/// represent the empty state.  The cell's value is stored in an [`AtomicControlledOption`][], and
+/// so this is only available for niches whose output is an integer or a pointer.
+///
+/// Unlike the standard `OnceLock` type, there is no separate "initializing" state that other
+/// threads can block on.  If several threads call [`get_or_init`][Self::get_or_init] at the same
+/// time, each of them might call its initialization function, but only one result will be stored
+/// in the cell, and every thread will see that result.
+pub struct ControlledOnceLock<T>
+where
+    T: Niche,
+    T::Output: AtomicOutput,
+{
+    value: AtomicControlledOption<T>,
+}
+
+impl<T> ControlledOnceLock<T>
+where
+    T: Niche,
+    T::Output: AtomicOutput,
+{
+    /// Creates a new empty cell.
+    #[inline]
+    pub fn new() -> ControlledOnceLock<T> {
+        ControlledOnceLock {
+            value: AtomicControlledOption::none(),
+        }
+    }
+
+    /// Returns a copy of the cell's value, or `None` if the cell is empty.
+    #[inline]
+    pub fn get(&self) -> Option<T>
+    where
+        T: Copy,
+    {
+        self.value.load(Ordering::Acquire).into_option()
+    }
+
+    /// Fills the cell with `value`.  Returns `value` back to you as an error if the cell is
+    /// already full.
+    #[inline]
+    pub fn set(&self, value: T) -> Result<(), T>
+    where
+        T: Copy,
+    {
+        let value = ControlledOption::some(value);
+        match self.value.compare_exchange(
+            ControlledOption::none(),
+            value,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(value.into_option().unwrap()),
+        }
+    }
+
+    /// Returns a copy of the cell's value, filling it with the result of `f` if the cell is
+    /// empty.
+    #[inline]
+    pub fn get_or_init<F>(&self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+        T: Copy,
+    {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        let value = f();
+        match self.set(value) {
+            Ok(()) => value,
+            Err(_) => self.get().unwrap(),
+        }
+    }
+
+    /// Consumes the cell, returning its value.
+    #[inline]
+    pub fn into_inner(self) -> ControlledOption<T> {
+        self.value.into_inner()
+    }
+
+    /// Takes the value out of the cell, leaving it empty.
+    #[inline]
+    pub fn take(&mut self) -> ControlledOption<T> {
+        self.value.take(Ordering::Acquire)
+    }
+}
+
+impl<T> Default for ControlledOnceLock<T>
+where
+    T: Niche,
+    T::Output: AtomicOutput,
+{
+    #[inline]
+    fn default() -> ControlledOnceLock<T> {
+        ControlledOnceLock::new()
+    }
+}
+
+impl<T> core::fmt::Debug for ControlledOnceLock<T>
+where
+    T: Copy + core::fmt::Debug + Niche,
+    T::Output: AtomicOutput,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.value.load(Ordering::Acquire).into_option() {
+            Some(value) => f.debug_tuple("ControlledOnceLock").field(&value).finish(),
+            None => write!(f, "ControlledOnceLock(<uninit>)"),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use crate::ControlledOption;
+use crate::Niche;
+
+/// Extra methods for the standard [`Option`][] type.
+///
+/// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+pub trait OptionExt<T>
+where
+    T: Niche,
+{
+    /// Transforms an `Option` into a [`ControlledOption`][].  This is the mirror image of
+    /// [`ControlledOption::into_option`][], and lets you convert at the end of a chain of `Option`
+    /// combinators.
+    fn into_controlled(self) -> ControlledOption<T>;
+}
+
+impl<T> OptionExt<T> for Option<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn into_controlled(self) -> ControlledOption<T> {
+        ControlledOption::from_option(self)
+    }
+}
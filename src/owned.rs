@@ -0,0 +1,201 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! Niches for owned pointer types.
+//!
+//! Owned pointers (like `Box`, `Rc`, and `Arc`) are never null, so the all-zero bit pattern is a
+//! perfect niche.  The same is true of owned buffers like `Vec` and `String`.  But unlike
+//! references, we have to make sure that the pointed-to value is dropped when the option is, which
+//! means that the output type needs its own `Drop` implementation.  (We can't add one to
+//! `ControlledOption` itself, since then options could never be `Copy`.)
+
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ffi::CStr;
+use core::hash::Hash;
+use core::hash::Hasher;
+use core::mem::ManuallyDrop;
+use core::mem::MaybeUninit;
+
+use crate::Niche;
+use crate::ZeroNiche;
+
+/// The output representation of an owned type whose all-zero bit pattern is never valid, such as
+/// a `Box` or `Rc`.  The all-zero bit pattern represents `None`; anything else is a valid
+/// instance of `T`, which is dropped when the representation is.
+///
+/// You cannot create one of these directly; they only appear as the `Output` type of the
+/// [`Niche`][] implementations in this crate.
+#[repr(transparent)]
+pub struct OwnedRepr<T>(MaybeUninit<T>);
+
+impl<T> OwnedRepr<T> {
+    // Safety: All-zero bytes must not be a valid instance of `T`, and `T` must not contain any
+    // padding bytes.  Every type that we use this for is a (possibly fat) non-null pointer.
+    #[inline]
+    pub(crate) unsafe fn none() -> OwnedRepr<T> {
+        OwnedRepr(MaybeUninit::zeroed())
+    }
+
+    #[inline]
+    pub(crate) fn some(value: T) -> OwnedRepr<T> {
+        OwnedRepr(MaybeUninit::new(value))
+    }
+
+    #[inline]
+    pub(crate) fn is_none(&self) -> bool {
+        let bytes = unsafe {
+            core::slice::from_raw_parts(self.0.as_ptr() as *const u8, core::mem::size_of::<T>())
+        };
+        bytes.iter().all(|byte| *byte == 0)
+    }
+
+    #[inline]
+    pub(crate) fn as_option(&self) -> Option<&T> {
+        if self.is_none() {
+            None
+        } else {
+            Some(unsafe { self.0.assume_init_ref() })
+        }
+    }
+
+    // Safety: The representation must not be `None`.
+    #[inline]
+    pub(crate) unsafe fn into_some(self) -> T {
+        let this = ManuallyDrop::new(self);
+        this.0.assume_init_read()
+    }
+
+    // Safety: The representation must not be `None`.
+    #[inline]
+    pub(crate) unsafe fn as_some(&self) -> &T {
+        self.0.assume_init_ref()
+    }
+}
+
+impl<T> Drop for OwnedRepr<T> {
+    fn drop(&mut self) {
+        if !self.is_none() {
+            unsafe { self.0.assume_init_drop() };
+        }
+    }
+}
+
+// All of the remaining traits are implemented with the semantics of `Option<T>`.
+
+impl<T> Clone for OwnedRepr<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        match self.as_option() {
+            Some(value) => OwnedRepr::some(value.clone()),
+            None => unsafe { OwnedRepr::none() },
+        }
+    }
+}
+
+impl<T> PartialEq for OwnedRepr<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.as_option() == other.as_option()
+    }
+}
+
+impl<T> Eq for OwnedRepr<T> where T: Eq {}
+
+impl<T> PartialOrd for OwnedRepr<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.as_option().partial_cmp(&other.as_option())
+    }
+}
+
+impl<T> Ord for OwnedRepr<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.as_option().cmp(&other.as_option())
+    }
+}
+
+impl<T> Hash for OwnedRepr<T>
+where
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.as_option().hash(state)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Smart pointers
+
+macro_rules! impl_owned_niche {
+    ([$($param:ident),*] $ty:ty) => {
+        impl<$($param),*> Niche for $ty {
+            type Output = OwnedRepr<$ty>;
+
+            #[inline]
+            fn none() -> Self::Output {
+                unsafe { OwnedRepr::none() }
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                value.is_none()
+            }
+
+            #[inline]
+            fn into_some(value: Self) -> Self::Output {
+                OwnedRepr::some(value)
+            }
+
+            #[inline]
+            fn from_some(value: Self::Output) -> Self {
+                unsafe { value.into_some() }
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &Self {
+                unsafe { value.as_some() }
+            }
+        }
+
+        unsafe impl<$($param),*> ZeroNiche for $ty {}
+    };
+}
+
+impl_owned_niche!([T] Box<T>);
+impl_owned_niche!([T] Rc<T>);
+impl_owned_niche!([T] Arc<T>);
+
+// Boxed slices and strings are fat pointers, but their data pointers are still never null.
+impl_owned_niche!([T] Box<[T]>);
+impl_owned_niche!([] Box<str>);
+impl_owned_niche!([] Box<CStr>);
+
+//-------------------------------------------------------------------------------------------------
+// Owned buffers
+
+// Owned buffers contain a pointer to their heap allocation, which (just like with a `Box`) is
+// never null, even when the buffer is empty.
+impl_owned_niche!([T] Vec<T>);
+impl_owned_niche!([] String);
+impl_owned_niche!([] CString);
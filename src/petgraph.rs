@@ -0,0 +1,58 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! `Niche` implementations for petgraph's graph indices.
+//!
+//! petgraph already reserves the maximum value of each index type as an invalid index (it's what
+//! `NodeIndex::end` and `EdgeIndex::end` return), and a graph never hands out that index for a
+//! node or edge.  We use it as the niche, so the output type is the index type itself.
+
+use ::petgraph::graph::EdgeIndex;
+use ::petgraph::graph::IndexType;
+use ::petgraph::graph::NodeIndex;
+
+use crate::Niche;
+
+macro_rules! impl_niche_for_graph_index {
+    ($($index:ident),* $(,)?) => {
+        $(
+            impl<Ix> Niche for $index<Ix>
+            where
+                Ix: IndexType,
+            {
+                type Output = $index<Ix>;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    $index::end()
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    *value == $index::end()
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    value
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    value
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    value
+                }
+            }
+        )*
+    };
+}
+
+impl_niche_for_graph_index!(NodeIndex, EdgeIndex);
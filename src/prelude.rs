@@ -0,0 +1,37 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! The most commonly used types and traits from this crate.
+//!
+//! ```
+//! use controlled_option::prelude::*;
+//!
+//! #[derive(Clone, Copy, Niche)]
+//! struct Node {
+//!     #[niche]
+//!     id: std::num::NonZeroU32,
+//!     weight: u32,
+//! }
+//!
+//! let nodes = [ControlledOption::<Node>::none(); 4];
+//! assert_eq!(nodes.count_some(), 0);
+//! ```
+
+pub use crate::ConstNiche;
+pub use crate::ControlledCell;
+pub use crate::ControlledEither;
+pub use crate::ControlledOption;
+#[cfg(feature = "alloc")]
+pub use crate::ControlledOptionVec;
+pub use crate::ControlledResult;
+pub use crate::DefaultNiche;
+pub use crate::Niche;
+pub use crate::NicheStrategy;
+pub use crate::OptionArray;
+pub use crate::OptionExt;
+pub use crate::OptionSliceExt;
+pub use crate::ResultNiche;
@@ -0,0 +1,64 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! proptest support for `ControlledOption`.
+//!
+//! `ControlledOption` implements proptest's `Arbitrary` trait, and this module provides strategy
+//! constructors that mirror the ones in `proptest::option`.  In all cases, we generate values via
+//! the corresponding `Option` strategy, so options shrink toward `None` in exactly the same way.
+
+use ::proptest::arbitrary::any_with;
+use ::proptest::arbitrary::Arbitrary;
+use ::proptest::option::OptionStrategy;
+use ::proptest::option::Probability;
+use ::proptest::strategy::Map;
+use ::proptest::strategy::Strategy;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+/// A strategy that produces `ControlledOption`s by wrapping the values produced by another
+/// strategy.
+pub type ControlledOptionStrategy<S> = Map<
+    OptionStrategy<S>,
+    fn(Option<<S as Strategy>::Value>) -> ControlledOption<<S as Strategy>::Value>,
+>;
+
+/// Returns a strategy producing `ControlledOption`s that wrap values from the given strategy.
+/// `None` and `Some` values are produced with equal probability.
+pub fn of<S>(strategy: S) -> ControlledOptionStrategy<S>
+where
+    S: Strategy,
+    S::Value: Niche,
+{
+    ::proptest::option::of(strategy).prop_map(ControlledOption::from)
+}
+
+/// Returns a strategy producing `ControlledOption`s that wrap values from the given strategy.
+/// `Some` values are produced with the given probability.
+pub fn weighted<S>(
+    probability_of_some: impl Into<Probability>,
+    strategy: S,
+) -> ControlledOptionStrategy<S>
+where
+    S: Strategy,
+    S::Value: Niche,
+{
+    ::proptest::option::weighted(probability_of_some, strategy).prop_map(ControlledOption::from)
+}
+
+impl<T> Arbitrary for ControlledOption<T>
+where
+    T: Niche + Arbitrary,
+{
+    type Parameters = <Option<T> as Arbitrary>::Parameters;
+    type Strategy = ControlledOptionStrategy<T::Strategy>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<Option<T>>(args).prop_map(ControlledOption::from)
+    }
+}
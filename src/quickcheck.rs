@@ -0,0 +1,38 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! quickcheck support for `ControlledOption`.
+//!
+//! We generate and shrink values via the corresponding `Option`, so options shrink toward `None`
+//! in exactly the same way.
+
+use alloc::boxed::Box;
+
+use ::quickcheck::Arbitrary;
+use ::quickcheck::Gen;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+impl<T> Arbitrary for ControlledOption<T>
+where
+    T: Niche + Arbitrary,
+    T::Output: Clone + 'static,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        Option::<T>::arbitrary(g).into()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(
+            self.clone()
+                .into_option()
+                .shrink()
+                .map(ControlledOption::from),
+        )
+    }
+}
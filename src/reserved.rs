@@ -0,0 +1,101 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! A compatibility layer for code that uses `cranelift-entity`'s `ReservedValue` and
+//! `PackedOption` types.
+//!
+//! We can't provide a blanket implementation of [`Niche`][crate::Niche] for every type that
+//! implements [`ReservedValue`][], since it would overlap with the implementations for references,
+//! boxes, and other wrapper types.  Instead, use the
+//! [`impl_niche_for_reserved_value!`][crate::impl_niche_for_reserved_value] macro for each of your
+//! types.
+
+use crate::ControlledOption;
+
+/// Types that have a reserved value, which can never be created as a normal value of the type.
+///
+/// This has the same definition as the trait in `cranelift-entity`.
+pub trait ReservedValue {
+    /// Creates an instance of the reserved value.
+    fn reserved_value() -> Self;
+
+    /// Checks whether this value is the reserved value.
+    fn is_reserved_value(&self) -> bool;
+}
+
+/// An optional value that uses a type's [`ReservedValue`][] to represent `None`.
+///
+/// This is an alias for [`ControlledOption`][], and so the type must also implement
+/// [`Niche`][crate::Niche], which you can do with the
+/// [`impl_niche_for_reserved_value!`][crate::impl_niche_for_reserved_value] macro.  Note that
+/// `cranelift-entity`'s `expand` method is called [`into_option`][ControlledOption::into_option]
+/// here.
+pub type PackedOption<T> = ControlledOption<T>;
+
+/// Implements [`Niche`][crate::Niche] for types that implement [`ReservedValue`][], using the
+/// reserved value as the niche.
+///
+/// ```
+/// # use controlled_option::impl_niche_for_reserved_value;
+/// # use controlled_option::PackedOption;
+/// # use controlled_option::ReservedValue;
+/// #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// pub struct Block(u32);
+///
+/// impl ReservedValue for Block {
+///     fn reserved_value() -> Block {
+///         Block(u32::MAX)
+///     }
+///
+///     fn is_reserved_value(&self) -> bool {
+///         self.0 == u32::MAX
+///     }
+/// }
+///
+/// impl_niche_for_reserved_value!(Block);
+///
+/// let option = PackedOption::some(Block(0));
+/// assert_eq!(option.into_option(), Some(Block(0)));
+/// assert!(PackedOption::<Block>::none().is_none());
+/// ```
+#[macro_export]
+macro_rules! impl_niche_for_reserved_value {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            // The output type is the type itself, holding the reserved value when the option is
+            // `None`.
+            impl $crate::Niche for $ty {
+                type Output = $ty;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    <$ty as $crate::ReservedValue>::reserved_value()
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    <$ty as $crate::ReservedValue>::is_reserved_value(value)
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    value
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    value
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    value
+                }
+            }
+        )*
+    };
+}
@@ -0,0 +1,282 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::marker::PhantomData;
+
+use crate::Niche;
+
+/// A type should implement `ResultNiche<E>` if its memory representation has enough unused bit
+/// patterns to encode every value of the error type `E`.  If so, a [`ControlledResult`][] can use
+/// those bit patterns to represent its `Err` variant, without needing a separate discriminant.
+///
+/// Every type that implements [`Niche`][] implements `ResultNiche<()>`, using its niche to
+/// represent the (only possible) error value.  For richer error types, such as an index that uses
+/// its upper range to encode error codes, you will need to implement this trait yourself.
+pub trait ResultNiche<E>: Sized {
+    /// The type that is used to store values of `Self` and `E` inside of a `ControlledResult`.
+    type Output;
+
+    /// Returns whether the given value represents an `Ok` value.
+    fn is_ok(value: &Self::Output) -> bool;
+
+    /// Converts an `Ok` value into its output representation.
+    fn from_ok(value: Self) -> Self::Output;
+
+    /// Converts an `Err` value into its output representation.
+    fn from_err(error: E) -> Self::Output;
+
+    /// Converts the output representation of an `Ok` value back into a `Self`.
+    fn into_ok(value: Self::Output) -> Self;
+
+    /// Converts the output representation of an `Err` value back into an `E`.
+    fn into_err(value: Self::Output) -> E;
+
+    /// Returns a reference to an `Ok` value, given a reference to its output representation.
+    fn as_ok(value: &Self::Output) -> &Self;
+
+    /// Returns a reference to an `Err` value, given a reference to its output representation.
+    fn as_err(value: &Self::Output) -> &E;
+}
+
+impl<T> ResultNiche<()> for T
+where
+    T: Niche,
+{
+    type Output = T::Output;
+
+    #[inline]
+    fn is_ok(value: &Self::Output) -> bool {
+        !T::is_none(value)
+    }
+
+    #[inline]
+    fn from_ok(value: Self) -> Self::Output {
+        T::into_some(value)
+    }
+
+    #[inline]
+    fn from_err(_error: ()) -> Self::Output {
+        T::none()
+    }
+
+    #[inline]
+    fn into_ok(value: Self::Output) -> Self {
+        T::from_some(value)
+    }
+
+    #[inline]
+    fn into_err(_value: Self::Output) {}
+
+    #[inline]
+    fn as_ok(value: &Self::Output) -> &Self {
+        T::as_some(value)
+    }
+
+    #[inline]
+    fn as_err(_value: &Self::Output) -> &() {
+        &()
+    }
+}
+
+/// An `Result` type where you have control over how the `Ok` and `Err` variants are represented
+/// in memory.  The `Ok` type must implement [`ResultNiche`][] for the `Err` type, which determines
+/// how both variants are packed into a single value.
+#[repr(transparent)]
+pub struct ControlledResult<T, E>
+where
+    T: ResultNiche<E>,
+{
+    value: T::Output,
+    _phantom: PhantomData<(T, E)>,
+}
+
+impl<T, E> ControlledResult<T, E>
+where
+    T: ResultNiche<E>,
+{
+    /// Creates a new `Ok` instance for this result.
+    #[inline]
+    pub fn ok(value: T) -> ControlledResult<T, E> {
+        let value = T::from_ok(value);
+        debug_assert!(T::is_ok(&value));
+        ControlledResult::from_repr(value)
+    }
+
+    /// Creates a new `Err` instance for this result.
+    #[inline]
+    pub fn err(error: E) -> ControlledResult<T, E> {
+        let value = T::from_err(error);
+        debug_assert!(!T::is_ok(&value));
+        ControlledResult::from_repr(value)
+    }
+
+    #[inline]
+    fn from_repr(value: T::Output) -> ControlledResult<T, E> {
+        ControlledResult {
+            value,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the result is an `Ok` value.
+    #[inline]
+    pub fn is_ok(&self) -> bool {
+        T::is_ok(&self.value)
+    }
+
+    /// Returns `true` if the result is an `Err` value.
+    #[inline]
+    pub fn is_err(&self) -> bool {
+        !T::is_ok(&self.value)
+    }
+
+    /// Transforms a [`Result`][] into a `ControlledResult`.
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    #[inline]
+    pub fn from_result(value: Result<T, E>) -> ControlledResult<T, E> {
+        value.into()
+    }
+
+    /// Transforms a `ControlledResult` into a [`Result`][].  This gives you access to all of the
+    /// usual assortment of useful methods that you expect from a `Result`.
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    #[inline]
+    pub fn into_result(self) -> Result<T, E> {
+        self.into()
+    }
+
+    /// Converts from `&ControlledResult<T, E>` to a [`Result<&T, &E>`][`Result`], without
+    /// consuming the original result.
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    #[inline]
+    pub fn as_result(&self) -> Result<&T, &E> {
+        if T::is_ok(&self.value) {
+            Ok(T::as_ok(&self.value))
+        } else {
+            Err(T::as_err(&self.value))
+        }
+    }
+}
+
+impl<T, E> From<Result<T, E>> for ControlledResult<T, E>
+where
+    T: ResultNiche<E>,
+{
+    #[inline]
+    fn from(value: Result<T, E>) -> ControlledResult<T, E> {
+        match value {
+            Ok(value) => ControlledResult::ok(value),
+            Err(error) => ControlledResult::err(error),
+        }
+    }
+}
+
+impl<T, E> From<ControlledResult<T, E>> for Result<T, E>
+where
+    T: ResultNiche<E>,
+{
+    #[inline]
+    fn from(value: ControlledResult<T, E>) -> Result<T, E> {
+        if T::is_ok(&value.value) {
+            Ok(T::into_ok(value.value))
+        } else {
+            Err(T::into_err(value.value))
+        }
+    }
+}
+
+// As with `ControlledOption`, these traits depend on T::Output, and not on T or E.
+
+impl<T, E> Clone for ControlledResult<T, E>
+where
+    T: ResultNiche<E>,
+    T::Output: Clone,
+{
+    fn clone(&self) -> Self {
+        ControlledResult::from_repr(self.value.clone())
+    }
+}
+
+impl<T, E> Copy for ControlledResult<T, E>
+where
+    T: ResultNiche<E>,
+    T::Output: Copy,
+{
+}
+
+impl<T, E> core::fmt::Debug for ControlledResult<T, E>
+where
+    T: core::fmt::Debug + ResultNiche<E>,
+    E: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.as_result() {
+            Ok(value) => f.debug_tuple("ControlledResult::Ok").field(value).finish(),
+            Err(error) => f.debug_tuple("ControlledResult::Err").field(error).finish(),
+        }
+    }
+}
+
+impl<T, E> PartialEq for ControlledResult<T, E>
+where
+    T: ResultNiche<E>,
+    T::Output: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value.eq(&other.value)
+    }
+}
+
+impl<T, E> Eq for ControlledResult<T, E>
+where
+    T: ResultNiche<E>,
+    T::Output: Eq,
+{
+}
+
+impl<T, E> core::hash::Hash for ControlledResult<T, E>
+where
+    T: ResultNiche<E>,
+    T::Output: core::hash::Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: core::hash::Hasher,
+    {
+        self.value.hash(state)
+    }
+}
+
+// A `ControlledResult<T, E>` is semantically just a `Result<T, E>`, but the value that it
+// actually stores is a `T::Output`, which `ResultNiche` (a safe trait) is free to choose.  As with
+// `ControlledOption`, these auto traits must depend on both.
+
+unsafe impl<T, E> Send for ControlledResult<T, E>
+where
+    T: ResultNiche<E> + Send,
+    T::Output: Send,
+    E: Send,
+{
+}
+
+unsafe impl<T, E> Sync for ControlledResult<T, E>
+where
+    T: ResultNiche<E> + Sync,
+    T::Output: Sync,
+    E: Sync,
+{
+}
+
+impl<T, E> Unpin for ControlledResult<T, E>
+where
+    T: ResultNiche<E> + Unpin,
+    E: Unpin,
+{
+}
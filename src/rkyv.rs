@@ -0,0 +1,208 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! rkyv support for `ControlledOption`.
+//!
+//! The archived form of a `ControlledOption<T>` is a `ControlledOption<T::Archived>`, which means
+//! that the archived type must also implement `Niche`.  That lets archived options use the same
+//! compact representation as the live ones, without an extra tag byte.  We provide `Niche`
+//! implementations for the endian-aware non-zero integer types from `rend`, which rkyv uses as
+//! the archived form of the standard non-zero types.
+//!
+//! rkyv resolves each archived value in place, directly in the archive's buffer, and so we resolve
+//! a `Some` value by resolving the archived `T` on top of the bytes of its niche output.  That's
+//! only sound if the archived type's `Output` is a bitwise copy of the archived value, which the
+//! [`ArchivedNiche`][] marker trait vouches for.
+
+use core::marker::PhantomData;
+
+use ::rkyv::bytecheck::CheckBytes;
+use ::rkyv::rancor::Fallible;
+use ::rkyv::traits::NoUndef;
+use ::rkyv::Archive;
+use ::rkyv::Deserialize;
+use ::rkyv::Place;
+use ::rkyv::Portable;
+use ::rkyv::Serialize;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+/// A marker trait for [`Niche`][] types that can be the archived form of a `ControlledOption`'s
+/// contents.  rkyv resolves and validates archived values in place, so for these types, the niche
+/// `Output` must just be a reinterpretation of `Self`'s bytes.
+///
+/// # Safety
+///
+/// `Self` and `Self::Output` must have the same size and alignment.  Every valid value of `Self`
+/// must also be a valid value of `Self::Output` with the same bytes, for which
+/// [`is_none`][Niche::is_none] returns `false`, and [`into_some`][Niche::into_some] must return
+/// that value unchanged.
+pub unsafe trait ArchivedNiche: Niche {}
+
+// The trait's safety requirements can't check the size and alignment for us, so we also verify
+// them at compile time whenever we archive or validate an option.
+
+struct ArchivedLayout<T>(PhantomData<T>);
+
+impl<T> ArchivedLayout<T>
+where
+    T: ArchivedNiche,
+{
+    const CHECK: () = assert!(
+        core::mem::size_of::<T>() == core::mem::size_of::<T::Output>()
+            && core::mem::align_of::<T>() == core::mem::align_of::<T::Output>(),
+        "ArchivedNiche requires a type to have the same layout as its niche output"
+    );
+}
+
+// SAFETY: ControlledOption is #[repr(transparent)], and so has the same layout as the output type.
+unsafe impl<T> Portable for ControlledOption<T>
+where
+    T: Niche + Portable,
+    T::Output: Portable,
+{
+}
+
+impl<T> Archive for ControlledOption<T>
+where
+    T: Niche + Archive,
+    T::Archived: ArchivedNiche,
+    <T::Archived as Niche>::Output: NoUndef + Portable,
+{
+    type Archived = ControlledOption<T::Archived>;
+    type Resolver = Option<T::Resolver>;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        match (self.as_option(), resolver) {
+            (Some(value), Some(resolver)) => {
+                // `ArchivedNiche` guarantees that the archived type has the same layout as its
+                // niche output, and that its bytes are a valid `Some` output, so we can resolve
+                // the archived value in place.
+                #[allow(clippy::let_unit_value)]
+                let () = ArchivedLayout::<T::Archived>::CHECK;
+                let out = unsafe { out.cast_unchecked::<T::Archived>() };
+                value.resolve(resolver, out);
+            }
+            _ => {
+                let out = unsafe { out.cast_unchecked::<<T::Archived as Niche>::Output>() };
+                out.write(T::Archived::none());
+            }
+        }
+    }
+}
+
+impl<T, S> Serialize<S> for ControlledOption<T>
+where
+    T: Niche + Serialize<S>,
+    T::Archived: ArchivedNiche,
+    <T::Archived as Niche>::Output: NoUndef + Portable,
+    S: Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        self.as_option()
+            .map(|value| value.serialize(serializer))
+            .transpose()
+    }
+}
+
+impl<T, D> Deserialize<ControlledOption<T>, D> for ControlledOption<T::Archived>
+where
+    T: Niche + Archive,
+    T::Archived: Niche + Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<ControlledOption<T>, D::Error> {
+        match self.as_option() {
+            Some(value) => Ok(ControlledOption::some(value.deserialize(deserializer)?)),
+            None => Ok(ControlledOption::none()),
+        }
+    }
+}
+
+// SAFETY: We first verify that the bytes are a valid output value.  If that output value is not
+// the niche, we then verify that the bytes are also a valid instance of the underlying type.
+// `ArchivedNiche` (whose layout we check at compile time) guarantees that the two types cover
+// the same bytes, so neither check reads out of bounds, even for untrusted input.
+unsafe impl<T, C> CheckBytes<C> for ControlledOption<T>
+where
+    T: ArchivedNiche + CheckBytes<C>,
+    T::Output: CheckBytes<C>,
+    C: Fallible + ?Sized,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        #[allow(clippy::let_unit_value)]
+        let () = ArchivedLayout::<T>::CHECK;
+        let repr = value as *const T::Output;
+        T::Output::check_bytes(repr, context)?;
+        if !T::is_none(&*repr) {
+            T::check_bytes(value as *const T, context)?;
+        }
+        Ok(())
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Archived non-zero types
+
+macro_rules! impl_niche_for_rend_nonzero {
+    ($($nonzero:ident => $output:ident),* $(,)?) => {
+        $(
+            impl Niche for ::rkyv::rend::$nonzero {
+                type Output = ::rkyv::rend::$output;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    ::rkyv::rend::$output::from_native(0)
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    value.to_native() == 0
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    ::rkyv::rend::$output::from_native(value.get())
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    unsafe { Self::new_unchecked(value.to_native()) }
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { &*(value as *const Self::Output as *const Self) }
+                }
+            }
+
+            // SAFETY: Each non-zero type has the same `#[repr(C, align(N))]` layout as its
+            // integer type, and its bytes are the same integer in the same byte order.
+            unsafe impl ArchivedNiche for ::rkyv::rend::$nonzero {}
+        )*
+    };
+}
+
+impl_niche_for_rend_nonzero! {
+    NonZeroI16_be => i16_be,
+    NonZeroI16_le => i16_le,
+    NonZeroI32_be => i32_be,
+    NonZeroI32_le => i32_le,
+    NonZeroI64_be => i64_be,
+    NonZeroI64_le => i64_le,
+    NonZeroI128_be => i128_be,
+    NonZeroI128_le => i128_le,
+    NonZeroU16_be => u16_be,
+    NonZeroU16_le => u16_le,
+    NonZeroU32_be => u32_be,
+    NonZeroU32_le => u32_le,
+    NonZeroU64_be => u64_be,
+    NonZeroU64_le => u64_le,
+    NonZeroU128_be => u128_be,
+    NonZeroU128_le => u128_le,
+}
@@ -0,0 +1,43 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! schemars support for `ControlledOption`.
+//!
+//! Since we serialize options in the same way as `Option`, we can reuse `Option`'s schema.  We only
+//! use schemars's public API, though, and schemars only treats a struct field as optional via its
+//! hidden trait methods.  A derived schema will therefore mark a `ControlledOption` field as
+//! required, unless you also mark it with `#[serde(default)]`.
+
+use alloc::borrow::Cow;
+
+use ::schemars::JsonSchema;
+use ::schemars::Schema;
+use ::schemars::SchemaGenerator;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+impl<T> JsonSchema for ControlledOption<T>
+where
+    T: Niche + JsonSchema,
+{
+    fn inline_schema() -> bool {
+        Option::<T>::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        Option::<T>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        Option::<T>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        Option::<T>::json_schema(generator)
+    }
+}
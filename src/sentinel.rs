@@ -0,0 +1,277 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! Integer types that are known not to equal a particular sentinel value.
+//!
+//! The `Sentinel` types let you choose any sentinel value, via a const generic parameter.  (Ideally
+//! there would be a single `Sentinel<T, const S: T>` type, but Rust does not allow the type of a
+//! const parameter to depend on another generic parameter, so there is a separate type for each
+//! primitive integer type.)
+//!
+//! The `NonMax` and `NonMin` aliases are the counterparts to the standard library's `NonZero`
+//! types.  The `NonMax` types are useful for indices, where zero is a perfectly valid value, and
+//! the maximum value is the natural choice for a sentinel.  The `NonMin` types are useful for
+//! signed values like offsets and error codes, where the minimum value is the natural choice.
+
+use crate::ConstNiche;
+use crate::DenseNiche;
+use crate::Niche;
+
+macro_rules! sentinel_integer {
+    ($($(#[$attr:meta])* $name:ident => $primitive:ty),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+            #[repr(transparent)]
+            pub struct $name<const S: $primitive>($primitive);
+
+            impl<const S: $primitive> $name<S> {
+                /// The value of the underlying integer type that cannot be represented by this
+                /// type.  This is the niche that represents `None` in a `ControlledOption`.
+                pub const NICHE: $primitive = S;
+
+                /// The smallest value that can be represented by this type.
+                pub const MIN: Self = if S == <$primitive>::MIN {
+                    $name(<$primitive>::MIN + 1)
+                } else {
+                    $name(<$primitive>::MIN)
+                };
+
+                /// The largest value that can be represented by this type.
+                pub const MAX: Self = if S == <$primitive>::MAX {
+                    $name(<$primitive>::MAX - 1)
+                } else {
+                    $name(<$primitive>::MAX)
+                };
+
+                /// Creates a new value, returning `None` if `value` is [`NICHE`][Self::NICHE].
+                #[inline]
+                pub const fn new(value: $primitive) -> Option<Self> {
+                    if value == S {
+                        None
+                    } else {
+                        Some($name(value))
+                    }
+                }
+
+                /// Creates a new value without checking whether it is [`NICHE`][Self::NICHE].
+                ///
+                /// # Safety
+                ///
+                /// `value` must not be [`NICHE`][Self::NICHE].
+                #[inline]
+                pub const unsafe fn new_unchecked(value: $primitive) -> Self {
+                    $name(value)
+                }
+
+                /// Returns the underlying integer value.
+                #[inline]
+                pub const fn get(self) -> $primitive {
+                    self.0
+                }
+            }
+
+            impl<const S: $primitive> From<$name<S>> for $primitive {
+                #[inline]
+                fn from(value: $name<S>) -> $primitive {
+                    value.get()
+                }
+            }
+
+            impl<const S: $primitive> core::fmt::Debug for $name<S> {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            impl<const S: $primitive> core::fmt::Display for $name<S> {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            impl<const S: $primitive> Niche for $name<S> {
+                type Output = $primitive;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    S
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    *value == S
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    value.0
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    $name(value)
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    unsafe { &*(value as *const Self::Output as *const Self) }
+                }
+            }
+
+            unsafe impl<const S: $primitive> DenseNiche for $name<S> {}
+
+            unsafe impl<const S: $primitive> ConstNiche for $name<S> {
+                const NONE: Self::Output = S;
+            }
+        )*
+    };
+}
+
+sentinel_integer! {
+    /// A `u8` that is known not to equal the sentinel value `S`.
+    SentinelU8 => u8,
+    /// A `u16` that is known not to equal the sentinel value `S`.
+    SentinelU16 => u16,
+    /// A `u32` that is known not to equal the sentinel value `S`.
+    SentinelU32 => u32,
+    /// A `u64` that is known not to equal the sentinel value `S`.
+    SentinelU64 => u64,
+    /// A `usize` that is known not to equal the sentinel value `S`.
+    SentinelUsize => usize,
+    /// An `i8` that is known not to equal the sentinel value `S`.
+    SentinelI8 => i8,
+    /// An `i16` that is known not to equal the sentinel value `S`.
+    SentinelI16 => i16,
+    /// An `i32` that is known not to equal the sentinel value `S`.
+    SentinelI32 => i32,
+    /// An `i64` that is known not to equal the sentinel value `S`.
+    SentinelI64 => i64,
+    /// An `isize` that is known not to equal the sentinel value `S`.
+    SentinelIsize => isize,
+}
+
+/// A `u8` that is known not to equal `u8::MAX`.
+pub type NonMaxU8 = SentinelU8<{ u8::MAX }>;
+/// A `u16` that is known not to equal `u16::MAX`.
+pub type NonMaxU16 = SentinelU16<{ u16::MAX }>;
+/// A `u32` that is known not to equal `u32::MAX`.
+pub type NonMaxU32 = SentinelU32<{ u32::MAX }>;
+/// A `u64` that is known not to equal `u64::MAX`.
+pub type NonMaxU64 = SentinelU64<{ u64::MAX }>;
+/// A `usize` that is known not to equal `usize::MAX`.
+pub type NonMaxUsize = SentinelUsize<{ usize::MAX }>;
+
+/// An `i8` that is known not to equal `i8::MIN`.
+pub type NonMinI8 = SentinelI8<{ i8::MIN }>;
+/// An `i16` that is known not to equal `i16::MIN`.
+pub type NonMinI16 = SentinelI16<{ i16::MIN }>;
+/// An `i32` that is known not to equal `i32::MIN`.
+pub type NonMinI32 = SentinelI32<{ i32::MIN }>;
+/// An `i64` that is known not to equal `i64::MIN`.
+pub type NonMinI64 = SentinelI64<{ i64::MIN }>;
+/// An `isize` that is known not to equal `isize::MIN`.
+pub type NonMinIsize = SentinelIsize<{ isize::MIN }>;
+
+/// Implements [`Niche`][crate::Niche] for a newtype wrapper around a primitive integer, using a
+/// particular sentinel value as the niche.
+///
+/// The newtype must be a tuple struct with a single field, of the given primitive type.  The macro
+/// also generates `new`, `new_unchecked`, and `get` methods (and a `SENTINEL` constant), which
+/// ensure that you can only create instances that don't equal the sentinel value.
+/// The newtype also implements [`ConstNiche`][crate::ConstNiche], so you can create options
+/// that contain it in `const` and `static` items.
+///
+/// ```
+/// # use controlled_option::impl_niche_with_sentinel;
+/// # use controlled_option::ControlledOption;
+/// #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// pub struct NodeId(u32);
+///
+/// impl_niche_with_sentinel!(NodeId, u32, u32::MAX);
+///
+/// let node = NodeId::new(0).unwrap();
+/// let option = ControlledOption::some(node);
+/// assert_eq!(option.into_option(), Some(node));
+/// assert_eq!(NodeId::new(u32::MAX), None);
+/// assert_eq!(std::mem::size_of::<ControlledOption<NodeId>>(), 4);
+/// ```
+#[macro_export]
+macro_rules! impl_niche_with_sentinel {
+    ($name:ident, $primitive:ty, $sentinel:expr $(,)?) => {
+        impl $name {
+            /// The value of the underlying integer type that represents `None`.
+            pub const SENTINEL: $primitive = $sentinel;
+
+            /// Creates a new value, returning `None` if `value` is the sentinel value.
+            #[inline]
+            #[allow(dead_code)]
+            pub const fn new(value: $primitive) -> Option<$name> {
+                if value == Self::SENTINEL {
+                    None
+                } else {
+                    Some($name(value))
+                }
+            }
+
+            /// Creates a new value without checking whether it is the sentinel value.
+            ///
+            /// # Safety
+            ///
+            /// `value` must not be the sentinel value.
+            #[inline]
+            #[allow(dead_code)]
+            pub const unsafe fn new_unchecked(value: $primitive) -> $name {
+                $name(value)
+            }
+
+            /// Returns the underlying integer value.
+            #[inline]
+            #[allow(dead_code)]
+            pub const fn get(self) -> $primitive {
+                self.0
+            }
+        }
+
+        // The output type is the newtype itself, holding the sentinel value when the option is
+        // `None`.
+        impl $crate::Niche for $name {
+            type Output = $name;
+
+            #[inline]
+            fn none() -> Self::Output {
+                $name(Self::SENTINEL)
+            }
+
+            #[inline]
+            fn is_none(value: &Self::Output) -> bool {
+                value.0 == Self::SENTINEL
+            }
+
+            #[inline]
+            fn into_some(value: Self) -> Self::Output {
+                value
+            }
+
+            #[inline]
+            fn from_some(value: Self::Output) -> Self {
+                value
+            }
+
+            #[inline]
+            fn as_some(value: &Self::Output) -> &Self {
+                value
+            }
+        }
+
+        unsafe impl $crate::DenseNiche for $name {}
+
+        unsafe impl $crate::ConstNiche for $name {
+            const NONE: Self::Output = $name(Self::SENTINEL);
+        }
+    };
+}
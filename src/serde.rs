@@ -0,0 +1,174 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! Serde support for `ControlledOption`.
+//!
+//! We use the same data model as the standard `Option`, so a `ControlledOption<T>` serializes
+//! exactly like the corresponding `Option<T>` would.  With the `serde` feature, `ControlledOption`
+//! implements `Serialize` and `Deserialize` directly.  With only the `serde-with` feature, you use
+//! the functions in this module to opt in on a field-by-field basis:
+//!
+//! ```
+//! # use std::num::NonZeroU32;
+//! # use controlled_option::ControlledOption;
+//! # use serde::Deserialize;
+//! # use serde::Serialize;
+//! #[derive(Deserialize, Serialize)]
+//! struct Node {
+//!     #[serde(with = "controlled_option::serde", default)]
+//!     parent: ControlledOption<NonZeroU32>,
+//!     #[serde(with = "controlled_option::serde::vec")]
+//!     children: Vec<ControlledOption<NonZeroU32>>,
+//! }
+//! ```
+//!
+//! Because `ControlledOption` implements `Default` (as `None`), you can add serde's `default`
+//! attribute to have a missing field deserialize as `None`, just like serde does automatically for
+//! `Option` fields.
+//!
+//! If your wire format uses the same sentinel value as your niche, you can use the [`raw`][raw]
+//! module instead, which serializes the niche's output value directly.
+//!
+//! [raw]: raw/index.html
+
+use ::serde::Deserialize;
+use ::serde::Deserializer;
+use ::serde::Serialize;
+use ::serde::Serializer;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+/// Serializes a `ControlledOption` in the same way as the corresponding `Option`.
+pub fn serialize<T, S>(value: &ControlledOption<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Niche + Serialize,
+    S: Serializer,
+{
+    value.as_option().serialize(serializer)
+}
+
+/// Deserializes a `ControlledOption` in the same way as the corresponding `Option`.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<ControlledOption<T>, D::Error>
+where
+    T: Niche + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(ControlledOption::from)
+}
+
+/// Serde helpers for `Vec<ControlledOption<T>>` fields, which serialize in the same way as the
+/// corresponding `Vec<Option<T>>`.
+#[cfg(feature = "alloc")]
+pub mod vec {
+    use alloc::vec::Vec;
+
+    use ::serde::Deserialize;
+    use ::serde::Deserializer;
+    use ::serde::Serialize;
+    use ::serde::Serializer;
+
+    use crate::ControlledOption;
+    use crate::Niche;
+
+    /// Serializes a sequence of `ControlledOption`s in the same way as a sequence of `Option`s.
+    pub fn serialize<T, S>(values: &[ControlledOption<T>], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Niche + Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(values.iter().map(ControlledOption::as_option))
+    }
+
+    /// Deserializes a sequence of `ControlledOption`s in the same way as a sequence of `Option`s.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<ControlledOption<T>>, D::Error>
+    where
+        T: Niche + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let values = Vec::<Option<T>>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(ControlledOption::from).collect())
+    }
+}
+
+/// Serde helpers that serialize the raw output representation of a `ControlledOption`, instead of
+/// using `Option`'s data model.  A `None` value is serialized as the niche value itself.  This is
+/// useful for wire formats and C-compatible binary protocols where the sentinel _is_ the
+/// on-the-wire representation.
+///
+/// ```
+/// # use std::num::NonZeroU32;
+/// # use controlled_option::ControlledOption;
+/// # use serde::Serialize;
+/// #[derive(Serialize)]
+/// struct Packet {
+///     #[serde(with = "controlled_option::serde::raw")]
+///     id: ControlledOption<NonZeroU32>,
+/// }
+///
+/// let packet = Packet { id: ControlledOption::none() };
+/// assert_eq!(serde_json::to_string(&packet).unwrap(), r#"{"id":0}"#);
+/// ```
+///
+/// Since we can't verify that an arbitrary output value is valid, you can only deserialize raw
+/// values for types that implement [`DenseNiche`][crate::DenseNiche].
+pub mod raw {
+    use ::serde::Deserialize;
+    use ::serde::Deserializer;
+    use ::serde::Serialize;
+    use ::serde::Serializer;
+
+    use crate::ControlledOption;
+    use crate::DenseNiche;
+    use crate::Niche;
+
+    /// Serializes the raw output representation of a `ControlledOption`.
+    pub fn serialize<T, S>(value: &ControlledOption<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Niche,
+        T::Output: Serialize,
+        S: Serializer,
+    {
+        value.value.serialize(serializer)
+    }
+
+    /// Deserializes a `ControlledOption` from its raw output representation.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<ControlledOption<T>, D::Error>
+    where
+        T: DenseNiche,
+        T::Output: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::Output::deserialize(deserializer).map(ControlledOption::from_output)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> Serialize for ControlledOption<T>
+where
+    T: Niche + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> Deserialize<'de> for ControlledOption<T>
+where
+    T: Niche + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer)
+    }
+}
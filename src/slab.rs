@@ -0,0 +1,189 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use alloc::vec::Vec;
+
+use crate::ControlledOption;
+use crate::IterSome;
+use crate::Niche;
+use crate::OptionSliceExt;
+
+/// A slab allocator that stores values of `T` in a contiguous list of slots, handing out stable
+/// `usize` keys.
+///
+/// Each slot is a [`ControlledOption`][], so vacant slots are marked using `T`'s niche, and
+/// occupied slots don't need any additional tag.  Inserting and removing values both take
+/// constant time, and a key remains valid until you remove its value, at which point the slot can
+/// be reused by a later insertion.
+///
+/// The list of vacant slots is kept in a separate stack of keys, and is _not_ threaded through
+/// the vacant slots themselves.  Doing that would need a distinct niche value for every possible
+/// link, but most niches (the `NonZero` integers, references, `Box`, and so on) only have room for
+/// a single `None` value, which can't also record where the next vacant slot is.  The stack only
+/// holds the keys of removed values, so a slab that you only insert into has no overhead beyond
+/// the slots themselves.  If your element type has room for a link (for instance, an enum with a
+/// separate vacant variant), you can build an arena with an intrusive free list yourself using
+/// [`NicheLink`][crate::NicheLink].
+pub struct ControlledSlab<T>
+where
+    T: Niche,
+{
+    entries: Vec<ControlledOption<T>>,
+    vacant: Vec<usize>,
+}
+
+impl<T> ControlledSlab<T>
+where
+    T: Niche,
+{
+    /// Creates a new empty slab.
+    #[inline]
+    pub fn new() -> ControlledSlab<T> {
+        ControlledSlab {
+            entries: Vec::new(),
+            vacant: Vec::new(),
+        }
+    }
+
+    /// Creates a new empty slab with space for at least `capacity` values.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> ControlledSlab<T> {
+        ControlledSlab {
+            entries: Vec::with_capacity(capacity),
+            vacant: Vec::new(),
+        }
+    }
+
+    /// Returns the number of values in the slab.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len() - self.vacant.len()
+    }
+
+    /// Returns whether the slab is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a value to the slab, returning the key that you can use to access it.
+    pub fn insert(&mut self, value: T) -> usize {
+        match self.vacant.pop() {
+            Some(key) => {
+                self.entries[key] = ControlledOption::some(value);
+                key
+            }
+            None => {
+                self.entries.push(ControlledOption::some(value));
+                self.entries.len() - 1
+            }
+        }
+    }
+
+    /// Returns whether the slab contains a value for `key`.
+    #[inline]
+    pub fn contains(&self, key: usize) -> bool {
+        self.entries.get(key).is_some_and(ControlledOption::is_some)
+    }
+
+    /// Returns a reference to the value for `key`, or `None` if there isn't one.
+    #[inline]
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.entries.get(key).and_then(ControlledOption::as_option)
+    }
+
+    /// Returns a mutable reference to the value for `key`, or `None` if there isn't one.
+    #[inline]
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        let entry = self.entries.get_mut(key)?;
+        if entry.is_none() {
+            return None;
+        }
+        Some(unsafe { &mut *crate::locate_some::<T>(&mut entry.value) })
+    }
+
+    /// Removes and returns the value for `key`, or `None` if there isn't one.  The key may be
+    /// handed out again by a later call to [`insert`][Self::insert].
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        let entry = self.entries.get_mut(key)?;
+        let value = core::mem::take(entry).into_option()?;
+        self.vacant.push(key);
+        Some(value)
+    }
+
+    /// Retains only the values for which `f` returns `true`, removing all of the others.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        for key in 0..self.entries.len() {
+            if let Some(value) = self.get_mut(key) {
+                if !f(key, value) {
+                    self.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator over the values in the slab, along with their keys.
+    #[inline]
+    pub fn iter(&self) -> IterSome<'_, T> {
+        self.entries.iter_some()
+    }
+
+    /// Removes all values from the slab.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.vacant.clear();
+    }
+}
+
+impl<T> Default for ControlledSlab<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn default() -> ControlledSlab<T> {
+        ControlledSlab::new()
+    }
+}
+
+impl<T> Clone for ControlledSlab<T>
+where
+    T: Niche,
+    T::Output: Clone,
+{
+    fn clone(&self) -> Self {
+        ControlledSlab {
+            entries: self.entries.clone(),
+            vacant: self.vacant.clone(),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for ControlledSlab<T>
+where
+    T: core::fmt::Debug + Niche,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ControlledSlab<T>
+where
+    T: Niche,
+{
+    type Item = (usize, &'a T);
+    type IntoIter = IterSome<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
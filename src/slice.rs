@@ -0,0 +1,172 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use crate::ControlledOption;
+use crate::Niche;
+use crate::ZeroNiche;
+
+/// Extra methods for slices of [`ControlledOption`][]s.
+///
+/// These methods test each element's niche directly, without converting it into a standard
+/// `Option` first.
+pub trait OptionSliceExt<T>
+where
+    T: Niche,
+{
+    /// Returns the number of `Some` elements in the slice.
+    fn count_some(&self) -> usize;
+
+    /// Returns the index of the first `Some` element in the slice.
+    fn position_some(&self) -> Option<usize>;
+
+    /// Returns the index of the first `None` element in the slice.
+    fn position_none(&self) -> Option<usize>;
+
+    /// Splits the slice just before its first `None` element.  Every element of the first half
+    /// is `Some`.  The second half is empty if there are no `None` elements.
+    fn split_at_first_none(&self) -> (&[ControlledOption<T>], &[ControlledOption<T>]);
+
+    /// Reorders the slice so that all of its `Some` elements come before all of its `None`
+    /// elements, returning the number of `Some` elements.  The relative order of the `Some`
+    /// elements is not preserved.
+    fn partition_in_place(&mut self) -> usize;
+
+    /// Sets every element of the slice to `None`.
+    fn fill_none(&mut self);
+
+    /// Sets every element of the slice to `None`.  Since `T`'s niche is all-zero bytes, this can
+    /// usually be done with a single `memset`, which is much faster than
+    /// [`fill_none`][Self::fill_none] for large slices.  (If the elements need to be dropped, we
+    /// still have to visit each one, and this is no faster than `fill_none`.)
+    fn fill_none_zeroed(&mut self)
+    where
+        T: ZeroNiche;
+
+    /// Returns an iterator over the `Some` elements of the slice, along with their indices.
+    fn iter_some(&self) -> IterSome<'_, T>;
+}
+
+impl<T> OptionSliceExt<T> for [ControlledOption<T>]
+where
+    T: Niche,
+{
+    #[inline]
+    fn count_some(&self) -> usize {
+        self.iter()
+            .map(|value| !T::is_none(&value.value) as usize)
+            .sum()
+    }
+
+    #[inline]
+    fn position_some(&self) -> Option<usize> {
+        self.iter().position(|value| !T::is_none(&value.value))
+    }
+
+    #[inline]
+    fn position_none(&self) -> Option<usize> {
+        self.iter().position(|value| T::is_none(&value.value))
+    }
+
+    #[inline]
+    fn split_at_first_none(&self) -> (&[ControlledOption<T>], &[ControlledOption<T>]) {
+        let index = self.position_none().unwrap_or(self.len());
+        self.split_at(index)
+    }
+
+    fn partition_in_place(&mut self) -> usize {
+        let mut front = 0;
+        let mut back = self.len();
+        loop {
+            while front < back && !T::is_none(&self[front].value) {
+                front += 1;
+            }
+            while front < back && T::is_none(&self[back - 1].value) {
+                back -= 1;
+            }
+            if front >= back {
+                return front;
+            }
+            self.swap(front, back - 1);
+            front += 1;
+            back -= 1;
+        }
+    }
+
+    #[inline]
+    fn fill_none(&mut self) {
+        self.fill_with(ControlledOption::none);
+    }
+
+    #[inline]
+    fn fill_none_zeroed(&mut self)
+    where
+        T: ZeroNiche,
+    {
+        if core::mem::needs_drop::<ControlledOption<T>>() {
+            self.fill_none();
+            return;
+        }
+        // Safety: ZeroNiche guarantees that all-zero bytes are a valid None value, and none of
+        // the existing elements need to be dropped before we overwrite them.
+        unsafe { core::ptr::write_bytes(self.as_mut_ptr(), 0, self.len()) };
+    }
+
+    #[inline]
+    fn iter_some(&self) -> IterSome<'_, T> {
+        IterSome {
+            iter: self.iter().enumerate(),
+        }
+    }
+}
+
+/// An iterator over the `Some` elements of a slice of options, along with their indices.  Created
+/// by [`OptionSliceExt::iter_some`][].
+pub struct IterSome<'a, T>
+where
+    T: Niche,
+{
+    iter: core::iter::Enumerate<core::slice::Iter<'a, ControlledOption<T>>>,
+}
+
+impl<'a, T> Iterator for IterSome<'a, T>
+where
+    T: Niche,
+{
+    type Item = (usize, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, value) in &mut self.iter {
+            if !T::is_none(&value.value) {
+                return Some((index, T::as_some(&value.value)));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<T> DoubleEndedIterator for IterSome<'_, T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some((index, value)) = self.iter.next_back() {
+            if !T::is_none(&value.value) {
+                return Some((index, T::as_some(&value.value)));
+            }
+        }
+        None
+    }
+}
+
+impl<T> core::iter::FusedIterator for IterSome<'_, T> where T: Niche {}
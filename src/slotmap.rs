@@ -0,0 +1,127 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+//! `Niche` implementations for slotmap keys.
+//!
+//! slotmap doesn't make any promises about the layout of its keys, but it does reserve a _null_
+//! key, which is never returned by any of its maps.  We use the null key as the niche, so the
+//! output type is the key type itself.
+
+use ::slotmap::DefaultKey;
+use ::slotmap::Key;
+use ::slotmap::KeyData;
+
+use crate::Niche;
+
+#[doc(hidden)]
+#[inline]
+pub fn slotmap_null_key<K>() -> K
+where
+    K: Key,
+{
+    K::null()
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn slotmap_key_is_null<K>(key: &K) -> bool
+where
+    K: Key,
+{
+    key.is_null()
+}
+
+/// Implements [`Niche`][crate::Niche] for slotmap key types, using slotmap's null key as the
+/// niche.
+///
+/// slotmap's own [`DefaultKey`][::slotmap::DefaultKey] and [`KeyData`][::slotmap::KeyData] types
+/// already implement `Niche`.  Use this macro for the key types that you define with slotmap's
+/// `new_key_type!` macro.  Note that the null key is also each key type's `Default` value, and
+/// that you must never wrap a null key in a `Some`.
+///
+/// ```
+/// # use controlled_option::impl_niche_for_slotmap_key;
+/// # use controlled_option::ControlledOption;
+/// # use slotmap::SlotMap;
+/// slotmap::new_key_type! { pub struct PlayerKey; }
+///
+/// impl_niche_for_slotmap_key!(PlayerKey);
+///
+/// let mut players = SlotMap::<PlayerKey, &str>::with_key();
+/// let key = players.insert("alice");
+/// let option = ControlledOption::some(key);
+/// assert_eq!(option.into_option(), Some(key));
+/// assert_eq!(std::mem::size_of::<ControlledOption<PlayerKey>>(), 8);
+/// ```
+#[macro_export]
+macro_rules! impl_niche_for_slotmap_key {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl $crate::Niche for $ty {
+                type Output = $ty;
+
+                #[inline]
+                fn none() -> Self::Output {
+                    $crate::slotmap_null_key::<$ty>()
+                }
+
+                #[inline]
+                fn is_none(value: &Self::Output) -> bool {
+                    $crate::slotmap_key_is_null(value)
+                }
+
+                #[inline]
+                fn into_some(value: Self) -> Self::Output {
+                    value
+                }
+
+                #[inline]
+                fn from_some(value: Self::Output) -> Self {
+                    value
+                }
+
+                #[inline]
+                fn as_some(value: &Self::Output) -> &Self {
+                    value
+                }
+            }
+        )*
+    };
+}
+
+impl_niche_for_slotmap_key!(DefaultKey);
+
+// KeyData doesn't implement Key, but it converts to and from DefaultKey, whose null key has the
+// same data.
+impl Niche for KeyData {
+    type Output = KeyData;
+
+    #[inline]
+    fn none() -> Self::Output {
+        DefaultKey::null().data()
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        DefaultKey::from(*value).is_null()
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value
+    }
+
+    #[inline]
+    fn from_some(value: Self::Output) -> Self {
+        value
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        value
+    }
+}
@@ -0,0 +1,157 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+
+use crate::ControlledOption;
+use crate::Niche;
+
+/// A marker trait for [`Niche`][] types whose niche is the same one that the compiler uses for
+/// the standard [`Option`][].
+///
+/// For these types, a `ControlledOption<T>` has exactly the same representation as an
+/// `Option<T>`, and you can convert between them (and between slices of them) without touching
+/// any of the elements.  See [`ControlledOption::from_std`][] and friends.
+///
+/// This is implemented for the types whose `Option` representation is [guaranteed by the
+/// standard library][repr]: references, `NonNull`, `Box`, and the `NonZero` integers.
+///
+/// # Safety
+///
+/// `ControlledOption<Self>` must have the same size and alignment as `Option<Self>`, a `None` of
+/// either type must be a valid `None` of the other, and likewise for every `Some` value.
+///
+/// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+/// [repr]: https://doc.rust-lang.org/std/option/index.html#representation
+pub unsafe trait StdNiche: Niche {}
+
+// The trait's safety requirements can't check the size and alignment for us, so we also verify
+// them at compile time whenever you use one of the conversions.
+
+struct StdLayout<T>(PhantomData<T>);
+
+impl<T> StdLayout<T>
+where
+    T: StdNiche,
+{
+    const CHECK: () = assert!(
+        core::mem::size_of::<ControlledOption<T>>() == core::mem::size_of::<Option<T>>()
+            && core::mem::align_of::<ControlledOption<T>>() == core::mem::align_of::<Option<T>>(),
+        "StdNiche requires ControlledOption<T> to have the same layout as Option<T>"
+    );
+}
+
+#[inline]
+fn check_std_layout<T>()
+where
+    T: StdNiche,
+{
+    #[allow(clippy::let_unit_value)]
+    let () = StdLayout::<T>::CHECK;
+}
+
+impl<T> ControlledOption<T>
+where
+    T: StdNiche,
+{
+    /// Transforms a standard [`Option`][] into a `ControlledOption`, without re-encoding it.
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn from_std(value: Option<T>) -> ControlledOption<T> {
+        check_std_layout::<T>();
+        let value = ManuallyDrop::new(value);
+        unsafe { core::ptr::read(&*value as *const Option<T> as *const ControlledOption<T>) }
+    }
+
+    /// Transforms a `ControlledOption` into a standard [`Option`][], without re-encoding it.
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn to_std(self) -> Option<T> {
+        check_std_layout::<T>();
+        let value = ManuallyDrop::new(self);
+        unsafe { core::ptr::read(&*value as *const ControlledOption<T> as *const Option<T>) }
+    }
+
+    /// Views a `ControlledOption` as a standard [`Option`][].
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn as_std(&self) -> &Option<T> {
+        check_std_layout::<T>();
+        unsafe { &*(self as *const ControlledOption<T> as *const Option<T>) }
+    }
+
+    /// Views a `ControlledOption` as a mutable standard [`Option`][].
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn as_std_mut(&mut self) -> &mut Option<T> {
+        check_std_layout::<T>();
+        unsafe { &mut *(self as *mut ControlledOption<T> as *mut Option<T>) }
+    }
+
+    /// Views a slice of standard [`Option`][]s as a slice of `ControlledOption`s.
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn slice_from_std(slice: &[Option<T>]) -> &[ControlledOption<T>] {
+        check_std_layout::<T>();
+        unsafe { core::slice::from_raw_parts(slice.as_ptr() as *const _, slice.len()) }
+    }
+
+    /// Views a mutable slice of standard [`Option`][]s as a mutable slice of `ControlledOption`s.
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn slice_from_std_mut(slice: &mut [Option<T>]) -> &mut [ControlledOption<T>] {
+        check_std_layout::<T>();
+        unsafe { core::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut _, slice.len()) }
+    }
+
+    /// Views a slice of `ControlledOption`s as a slice of standard [`Option`][]s.
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn slice_to_std(slice: &[ControlledOption<T>]) -> &[Option<T>] {
+        check_std_layout::<T>();
+        unsafe { core::slice::from_raw_parts(slice.as_ptr() as *const _, slice.len()) }
+    }
+
+    /// Views a mutable slice of `ControlledOption`s as a mutable slice of standard [`Option`][]s.
+    ///
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    #[inline]
+    pub fn slice_to_std_mut(slice: &mut [ControlledOption<T>]) -> &mut [Option<T>] {
+        check_std_layout::<T>();
+        unsafe { core::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut _, slice.len()) }
+    }
+}
+
+unsafe impl<T> StdNiche for &T {}
+
+unsafe impl<T> StdNiche for &mut T {}
+
+unsafe impl<T> StdNiche for core::ptr::NonNull<T> {}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> StdNiche for alloc::boxed::Box<T> {}
+
+unsafe impl StdNiche for core::num::NonZeroI8 {}
+unsafe impl StdNiche for core::num::NonZeroI16 {}
+unsafe impl StdNiche for core::num::NonZeroI32 {}
+unsafe impl StdNiche for core::num::NonZeroI64 {}
+unsafe impl StdNiche for core::num::NonZeroI128 {}
+unsafe impl StdNiche for core::num::NonZeroIsize {}
+unsafe impl StdNiche for core::num::NonZeroU8 {}
+unsafe impl StdNiche for core::num::NonZeroU16 {}
+unsafe impl StdNiche for core::num::NonZeroU32 {}
+unsafe impl StdNiche for core::num::NonZeroU64 {}
+unsafe impl StdNiche for core::num::NonZeroU128 {}
+unsafe impl StdNiche for core::num::NonZeroUsize {}
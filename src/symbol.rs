@@ -0,0 +1,170 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::num::NonZeroU32;
+
+use crate::ConstNiche;
+use crate::DenseNiche;
+use crate::Niche;
+use crate::ZeroNiche;
+
+/// A handle to a string that has been stored in an interner.
+///
+/// Symbols are 32 bits wide, and are backed by a `NonZeroU32`, so an optional symbol is also 32
+/// bits wide.  You can use the [`Interner`][] type to create symbols (when the `std` feature is
+/// enabled), or use [`from_index`][Self::from_index] to create them from your own interner's
+/// indices.
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct Symbol(NonZeroU32);
+
+impl Symbol {
+    /// Creates a symbol for the `index`th interned string.  Panics if `index` is `u32::MAX`.
+    #[inline]
+    pub const fn from_index(index: u32) -> Symbol {
+        match index.checked_add(1) {
+            Some(raw) => Symbol(unsafe { NonZeroU32::new_unchecked(raw) }),
+            None => panic!("symbol index out of range"),
+        }
+    }
+
+    /// Returns the index of the interned string that this symbol refers to.
+    #[inline]
+    pub const fn index(self) -> u32 {
+        self.0.get() - 1
+    }
+
+    /// Creates a symbol from its raw representation.
+    #[inline]
+    pub const fn from_raw(raw: NonZeroU32) -> Symbol {
+        Symbol(raw)
+    }
+
+    /// Returns the raw representation of this symbol.
+    #[inline]
+    pub const fn to_raw(self) -> NonZeroU32 {
+        self.0
+    }
+}
+
+impl core::fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Symbol").field(&self.index()).finish()
+    }
+}
+
+impl Niche for Symbol {
+    type Output = u32;
+
+    #[inline]
+    fn none() -> Self::Output {
+        0
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        *value == 0
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value.0.get()
+    }
+
+    #[inline]
+    fn from_some(value: Self::Output) -> Self {
+        Symbol(unsafe { NonZeroU32::new_unchecked(value) })
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
+    }
+}
+
+unsafe impl ZeroNiche for Symbol {}
+
+unsafe impl DenseNiche for Symbol {}
+
+unsafe impl ConstNiche for Symbol {
+    const NONE: Self::Output = 0;
+}
+
+#[cfg(feature = "std")]
+pub use self::interner::Interner;
+
+#[cfg(feature = "std")]
+mod interner {
+    use std::boxed::Box;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+    use std::vec::Vec;
+
+    use super::Symbol;
+
+    /// A simple string interner, which hands out a [`Symbol`][] for each distinct string.
+    ///
+    /// Each string is stored twice (once for each direction of the mapping), so this is best
+    /// suited to small or moderately sized vocabularies.
+    #[derive(Clone, Debug, Default)]
+    pub struct Interner {
+        strings: Vec<Box<str>>,
+        symbols: HashMap<Box<str>, Symbol>,
+    }
+
+    impl Interner {
+        /// Creates a new empty interner.
+        #[inline]
+        pub fn new() -> Interner {
+            Interner::default()
+        }
+
+        /// Returns the number of distinct strings in the interner.
+        #[inline]
+        pub fn len(&self) -> usize {
+            self.strings.len()
+        }
+
+        /// Returns whether the interner is empty.
+        #[inline]
+        pub fn is_empty(&self) -> bool {
+            self.strings.is_empty()
+        }
+
+        /// Returns the symbol for `string`, adding it to the interner if needed.  Panics if the
+        /// interner already contains `u32::MAX` distinct strings.
+        pub fn intern(&mut self, string: &str) -> Symbol {
+            if let Some(symbol) = self.symbols.get(string) {
+                return *symbol;
+            }
+            let symbol = Symbol::from_index(
+                u32::try_from(self.strings.len()).expect("too many interned strings"),
+            );
+            self.strings.push(string.into());
+            self.symbols.insert(string.into(), symbol);
+            symbol
+        }
+
+        /// Returns the symbol for `string`, if it has been interned.
+        #[inline]
+        pub fn lookup(&self, string: &str) -> Option<Symbol> {
+            self.symbols.get(string).copied()
+        }
+
+        /// Returns the string for `symbol`, or `None` if it didn't come from this interner.
+        #[inline]
+        pub fn get(&self, symbol: Symbol) -> Option<&str> {
+            self.strings.get(symbol.index() as usize).map(|s| &**s)
+        }
+
+        /// Returns the string for `symbol`.  Panics if it didn't come from this interner.
+        #[inline]
+        pub fn resolve(&self, symbol: Symbol) -> &str {
+            &self.strings[symbol.index() as usize]
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::hash::Hasher;
+use core::ptr::NonNull;
+
+use crate::Niche;
+use crate::ZeroNiche;
+
+/// A non-null pointer that stores a small tag in the low bits of the pointer.
+///
+/// Those bits are always zero in a pointer to a suitably aligned `T`, so we can use them to store
+/// `BITS` bits of extra information without making the pointer any larger.  The alignment of `T`
+/// must be at least `2^BITS`; you will get a compile-time error if it isn't.
+///
+/// The pointer itself is never null, so the all-zero bit pattern (a null pointer with a zero tag)
+/// is the niche that we use to represent `None`.
+#[repr(transparent)]
+pub struct TaggedPtr<T, const BITS: u32> {
+    ptr: NonNull<T>,
+}
+
+impl<T, const BITS: u32> TaggedPtr<T, BITS> {
+    /// A mask that selects the tag bits of the pointer.
+    pub const TAG_MASK: usize = (1 << BITS) - 1;
+
+    const CHECK: () = assert!(
+        BITS < usize::BITS && (1 << BITS) <= core::mem::align_of::<T>(),
+        "type is not aligned enough to store that many tag bits",
+    );
+
+    /// Creates a new tagged pointer.  Panics if `ptr` is not aligned, or if `tag` does not fit
+    /// into `BITS` bits.
+    #[inline]
+    pub fn new(ptr: NonNull<T>, tag: usize) -> TaggedPtr<T, BITS> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHECK;
+        assert!(
+            ptr.as_ptr().addr() & Self::TAG_MASK == 0,
+            "pointer is not aligned"
+        );
+        assert!(
+            tag & !Self::TAG_MASK == 0,
+            "tag does not fit into {} bits",
+            BITS
+        );
+        let ptr = ptr.as_ptr().map_addr(|addr| addr | tag);
+        TaggedPtr {
+            ptr: unsafe { NonNull::new_unchecked(ptr) },
+        }
+    }
+
+    /// Returns the (untagged) pointer.
+    #[inline]
+    pub fn ptr(self) -> NonNull<T> {
+        let ptr = self.ptr.as_ptr().map_addr(|addr| addr & !Self::TAG_MASK);
+        unsafe { NonNull::new_unchecked(ptr) }
+    }
+
+    /// Returns the tag.
+    #[inline]
+    pub fn tag(self) -> usize {
+        self.ptr.as_ptr().addr() & Self::TAG_MASK
+    }
+
+    /// Returns a copy of this pointer with a different tag.  Panics if `tag` does not fit into
+    /// `BITS` bits.
+    #[inline]
+    pub fn with_tag(self, tag: usize) -> TaggedPtr<T, BITS> {
+        TaggedPtr::new(self.ptr(), tag)
+    }
+}
+
+impl<T, const BITS: u32> Clone for TaggedPtr<T, BITS> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const BITS: u32> Copy for TaggedPtr<T, BITS> {}
+
+impl<T, const BITS: u32> Debug for TaggedPtr<T, BITS> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TaggedPtr")
+            .field("ptr", &self.ptr())
+            .field("tag", &self.tag())
+            .finish()
+    }
+}
+
+impl<T, const BITS: u32> PartialEq for TaggedPtr<T, BITS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+}
+
+impl<T, const BITS: u32> Eq for TaggedPtr<T, BITS> {}
+
+impl<T, const BITS: u32> Hash for TaggedPtr<T, BITS> {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.ptr.hash(state)
+    }
+}
+
+impl<T, const BITS: u32> Niche for TaggedPtr<T, BITS> {
+    type Output = *mut T;
+
+    #[inline]
+    fn none() -> Self::Output {
+        core::ptr::null_mut()
+    }
+
+    #[inline]
+    fn is_none(value: &Self::Output) -> bool {
+        value.is_null()
+    }
+
+    #[inline]
+    fn into_some(value: Self) -> Self::Output {
+        value.ptr.as_ptr()
+    }
+
+    #[inline]
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn from_some(value: Self::Output) -> Self {
+        TaggedPtr {
+            ptr: unsafe { NonNull::new_unchecked(value) },
+        }
+    }
+
+    #[inline]
+    fn as_some(value: &Self::Output) -> &Self {
+        unsafe { &*(value as *const Self::Output as *const Self) }
+    }
+}
+
+unsafe impl<T, const BITS: u32> ZeroNiche for TaggedPtr<T, BITS> {}
@@ -0,0 +1,437 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::ops::Deref;
+use core::ops::DerefMut;
+
+use crate::ControlledOption;
+use crate::IterSome;
+use crate::Niche;
+use crate::OptionSliceExt;
+use crate::ZeroNiche;
+
+/// A growable vector of optional values.
+///
+/// This trait is implemented by [`ControlledOptionVec`][], which uses each element's niche to
+/// represent `None`, and by [`BitmaskOptionVec`][crate::BitmaskOptionVec], which works with any
+/// element type and tracks which elements are present in a separate bitmask.  If you write your
+/// generic code in terms of this trait, your callers can choose whichever storage fits their
+/// element type.
+pub trait OptionVec<T>: Default {
+    /// Creates a new vector containing `len` `None` elements.
+    fn from_none(len: usize) -> Self;
+
+    /// Returns the number of elements in the vector.
+    fn len(&self) -> usize;
+
+    /// Returns whether the vector is empty.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if that element is `None` or
+    /// `index` is out of bounds.
+    fn get(&self, index: usize) -> Option<&T>;
+
+    /// Appends a `Some` element to the end of the vector.
+    fn push_some(&mut self, value: T);
+
+    /// Appends a `None` element to the end of the vector.
+    fn push_none(&mut self);
+
+    /// Replaces the element at `index`, returning its previous value.  Panics if `index` is out
+    /// of bounds.
+    fn set(&mut self, index: usize, value: Option<T>) -> Option<T>;
+
+    /// Takes the element at `index`, leaving `None` in its place.  Panics if `index` is out of
+    /// bounds.
+    fn take(&mut self, index: usize) -> Option<T>;
+
+    /// Returns the number of `Some` elements in the vector.
+    fn count_some(&self) -> usize;
+
+    /// Returns an iterator over the `Some` elements of the vector, along with their indices.
+    fn iter_some<'a>(&'a self) -> impl Iterator<Item = (usize, &'a T)>
+    where
+        T: 'a;
+
+    /// Sets every element of the vector to `None`, without changing its length.
+    fn fill_none(&mut self);
+
+    /// Shortens the vector to `len` elements.  Does nothing if the vector is already shorter
+    /// than that.
+    fn truncate(&mut self, len: usize);
+
+    /// Resizes the vector to `len` elements, filling any new elements with `None`.
+    fn resize_none(&mut self, len: usize);
+
+    /// Removes all elements from the vector.
+    fn clear(&mut self);
+}
+
+/// A growable vector of [`ControlledOption`][]s.
+///
+/// Each element is stored as its niche's output type, with no additional discriminant, so the
+/// elements are packed just as tightly as in a vector of `T`.  The vector dereferences to a slice
+/// of options, so you can use all of the usual slice methods to access its elements.
+pub struct ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    values: Vec<ControlledOption<T>>,
+}
+
+impl<T> ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    /// Creates a new empty vector.
+    #[inline]
+    pub fn new() -> ControlledOptionVec<T> {
+        ControlledOptionVec { values: Vec::new() }
+    }
+
+    /// Creates a new empty vector with space for at least `capacity` elements.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> ControlledOptionVec<T> {
+        ControlledOptionVec {
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Creates a new vector containing `len` `None` elements.
+    pub fn from_none(len: usize) -> ControlledOptionVec<T> {
+        let mut result = ControlledOptionVec::with_capacity(len);
+        result.values.resize_with(len, ControlledOption::none);
+        result
+    }
+
+    /// Returns the number of elements that the vector can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Appends an option to the end of the vector.
+    #[inline]
+    pub fn push(&mut self, value: ControlledOption<T>) {
+        self.values.push(value);
+    }
+
+    /// Appends a `Some` element to the end of the vector.
+    #[inline]
+    pub fn push_some(&mut self, value: T) {
+        self.values.push(ControlledOption::some(value));
+    }
+
+    /// Appends a `None` element to the end of the vector.
+    #[inline]
+    pub fn push_none(&mut self) {
+        self.values.push(ControlledOption::none());
+    }
+
+    /// Removes the last element of the vector, returning `None` if the vector is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<ControlledOption<T>> {
+        self.values.pop()
+    }
+
+    /// Replaces the element at `index`, returning its previous value.  Panics if `index` is out
+    /// of bounds.
+    #[inline]
+    pub fn set(&mut self, index: usize, value: ControlledOption<T>) -> ControlledOption<T> {
+        core::mem::replace(&mut self.values[index], value)
+    }
+
+    /// Takes the element at `index`, leaving `None` in its place.  Panics if `index` is out of
+    /// bounds.
+    #[inline]
+    pub fn take(&mut self, index: usize) -> ControlledOption<T> {
+        core::mem::take(&mut self.values[index])
+    }
+
+    /// Returns an iterator over the `Some` elements of the vector, along with their indices.
+    #[inline]
+    pub fn iter_some(&self) -> IterSome<'_, T> {
+        self.values.iter_some()
+    }
+
+    /// Returns the number of `Some` elements in the vector.
+    #[inline]
+    pub fn count_some(&self) -> usize {
+        self.values.count_some()
+    }
+
+    /// Sets every element of the vector to `None`, without changing its length.
+    #[inline]
+    pub fn fill_none(&mut self) {
+        self.values.fill_none();
+    }
+
+    /// Sets every element of the vector to `None`, without changing its length.  This uses
+    /// [`OptionSliceExt::fill_none_zeroed`][], which is usually a single `memset`.
+    #[inline]
+    pub fn fill_none_zeroed(&mut self)
+    where
+        T: ZeroNiche,
+    {
+        self.values.fill_none_zeroed();
+    }
+
+    /// Shortens the vector to `len` elements.  Does nothing if the vector is already shorter
+    /// than that.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.values.truncate(len);
+    }
+
+    /// Resizes the vector to `len` elements, filling any new elements with `None`.
+    #[inline]
+    pub fn resize_none(&mut self, len: usize) {
+        self.values.resize_with(len, ControlledOption::none);
+    }
+
+    /// Removes all elements from the vector.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Consumes the vector, returning a standard `Vec` of options.
+    #[inline]
+    pub fn into_vec(self) -> Vec<ControlledOption<T>> {
+        self.values
+    }
+}
+
+impl<T> OptionVec<T> for ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn from_none(len: usize) -> Self {
+        ControlledOptionVec::from_none(len)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<&T> {
+        self.values.get(index).and_then(ControlledOption::as_option)
+    }
+
+    #[inline]
+    fn push_some(&mut self, value: T) {
+        ControlledOptionVec::push_some(self, value)
+    }
+
+    #[inline]
+    fn push_none(&mut self) {
+        ControlledOptionVec::push_none(self)
+    }
+
+    #[inline]
+    fn set(&mut self, index: usize, value: Option<T>) -> Option<T> {
+        ControlledOptionVec::set(self, index, ControlledOption::from_option(value)).into_option()
+    }
+
+    #[inline]
+    fn take(&mut self, index: usize) -> Option<T> {
+        ControlledOptionVec::take(self, index).into_option()
+    }
+
+    #[inline]
+    fn count_some(&self) -> usize {
+        ControlledOptionVec::count_some(self)
+    }
+
+    #[inline]
+    fn iter_some<'a>(&'a self) -> impl Iterator<Item = (usize, &'a T)>
+    where
+        T: 'a,
+    {
+        ControlledOptionVec::iter_some(self)
+    }
+
+    #[inline]
+    fn fill_none(&mut self) {
+        ControlledOptionVec::fill_none(self)
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        ControlledOptionVec::truncate(self, len)
+    }
+
+    #[inline]
+    fn resize_none(&mut self, len: usize) {
+        ControlledOptionVec::resize_none(self, len)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        ControlledOptionVec::clear(self)
+    }
+}
+
+impl<T> Deref for ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    type Target = [ControlledOption<T>];
+
+    #[inline]
+    fn deref(&self) -> &[ControlledOption<T>] {
+        &self.values
+    }
+}
+
+impl<T> DerefMut for ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [ControlledOption<T>] {
+        &mut self.values
+    }
+}
+
+impl<T> Default for ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn default() -> ControlledOptionVec<T> {
+        ControlledOptionVec::new()
+    }
+}
+
+impl<T> From<Vec<ControlledOption<T>>> for ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    #[inline]
+    fn from(values: Vec<ControlledOption<T>>) -> ControlledOptionVec<T> {
+        ControlledOptionVec { values }
+    }
+}
+
+impl<T> FromIterator<ControlledOption<T>> for ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    fn from_iter<I>(iter: I) -> ControlledOptionVec<T>
+    where
+        I: IntoIterator<Item = ControlledOption<T>>,
+    {
+        ControlledOptionVec {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> FromIterator<Option<T>> for ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    fn from_iter<I>(iter: I) -> ControlledOptionVec<T>
+    where
+        I: IntoIterator<Item = Option<T>>,
+    {
+        ControlledOptionVec {
+            values: iter.into_iter().map(ControlledOption::from).collect(),
+        }
+    }
+}
+
+impl<T> Extend<ControlledOption<T>> for ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = ControlledOption<T>>,
+    {
+        self.values.extend(iter);
+    }
+}
+
+impl<T> IntoIterator for ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    type Item = ControlledOption<T>;
+    type IntoIter = alloc::vec::IntoIter<ControlledOption<T>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ControlledOptionVec<T>
+where
+    T: Niche,
+{
+    type Item = &'a ControlledOption<T>;
+    type IntoIter = core::slice::Iter<'a, ControlledOption<T>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl<T> Clone for ControlledOptionVec<T>
+where
+    T: Niche,
+    T::Output: Clone,
+{
+    fn clone(&self) -> Self {
+        ControlledOptionVec {
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for ControlledOptionVec<T>
+where
+    T: core::fmt::Debug + Niche,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_list().entries(self.values.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for ControlledOptionVec<T>
+where
+    T: Niche + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<T> Eq for ControlledOptionVec<T> where T: Niche + Eq {}
+
+impl<T> core::hash::Hash for ControlledOptionVec<T>
+where
+    T: Niche + core::hash::Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: core::hash::Hasher,
+    {
+        self.values.hash(state)
+    }
+}
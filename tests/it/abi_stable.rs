@@ -0,0 +1,22 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use abi_stable::abi_stability::abi_checking::check_layout_compatibility;
+use abi_stable::StableAbi;
+use controlled_option::ControlledOption;
+
+#[test]
+fn options_have_stable_abi() {
+    let layout = <ControlledOption<NonZeroU32> as StableAbi>::LAYOUT;
+    assert_eq!(layout.size(), std::mem::size_of::<u32>());
+    assert!(check_layout_compatibility(layout, layout).is_ok());
+
+    let other = <ControlledOption<&u32> as StableAbi>::LAYOUT;
+    assert!(check_layout_compatibility(layout, other).is_err());
+}
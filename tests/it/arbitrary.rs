@@ -0,0 +1,32 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use arbitrary::Arbitrary;
+use arbitrary::Unstructured;
+use controlled_option::ControlledOption;
+
+#[test]
+fn can_generate_arbitrary_options() {
+    let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+    let mut u = Unstructured::new(&data);
+    let mut saw_none = false;
+    let mut saw_some = false;
+    while !u.is_empty() {
+        let value = ControlledOption::<NonZeroU32>::arbitrary(&mut u).unwrap();
+        match value.into_option() {
+            Some(value) => {
+                assert_ne!(value.get(), 0);
+                saw_some = true;
+            }
+            None => saw_none = true,
+        }
+    }
+    assert!(saw_none);
+    assert!(saw_some);
+}
@@ -0,0 +1,51 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use controlled_option::OptionArray;
+
+fn nz(value: u32) -> ControlledOption<NonZeroU32> {
+    ControlledOption::from(NonZeroU32::new(value))
+}
+
+#[test]
+fn can_scan_empty_arrays() {
+    let array = OptionArray::<NonZeroU32, 8>::none();
+    assert_eq!(std::mem::size_of::<OptionArray<NonZeroU32, 8>>(), 32);
+    assert_eq!(array.count_some(), 0);
+    assert!(array.any_none());
+    assert_eq!(array.first_none(), Some(0));
+    assert_eq!(array.first_some(), None);
+}
+
+#[test]
+fn can_scan_full_arrays() {
+    let array = OptionArray::from([nz(1), nz(2), nz(3), nz(4)]);
+    assert_eq!(array.count_some(), 4);
+    assert!(!array.any_none());
+    assert_eq!(array.first_none(), None);
+    assert_eq!(array.first_some(), Some((0, &NonZeroU32::new(1).unwrap())));
+}
+
+#[test]
+fn can_scan_partial_arrays() {
+    let mut array = OptionArray::<NonZeroU32, 4>::none();
+    array[2] = nz(3);
+    array[3] = nz(4);
+    assert_eq!(array.count_some(), 2);
+    assert!(array.any_none());
+    assert_eq!(array.first_none(), Some(0));
+    assert_eq!(array.first_some(), Some((2, &NonZeroU32::new(3).unwrap())));
+    let some = array
+        .iter_some()
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    assert_eq!(some, vec![2, 3]);
+    assert_eq!(array.into_array(), [nz(0), nz(0), nz(3), nz(4)]);
+}
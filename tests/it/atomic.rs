@@ -0,0 +1,67 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+use std::sync::atomic::Ordering;
+
+use controlled_option::AtomicControlledOption;
+use controlled_option::ControlledOption;
+
+#[test]
+fn can_load_and_store_atomic_options() {
+    let one = ControlledOption::from(NonZeroU32::new(1));
+    let two = ControlledOption::from(NonZeroU32::new(2));
+
+    let atomic = AtomicControlledOption::<NonZeroU32>::none();
+    assert_eq!(
+        std::mem::size_of::<AtomicControlledOption<NonZeroU32>>(),
+        std::mem::size_of::<u32>()
+    );
+    assert!(atomic.load(Ordering::SeqCst).is_none());
+    atomic.store(one, Ordering::SeqCst);
+    assert_eq!(atomic.load(Ordering::SeqCst), one);
+    assert_eq!(atomic.swap(two, Ordering::SeqCst), one);
+    assert_eq!(atomic.take(Ordering::SeqCst), two);
+    assert!(atomic.into_inner().is_none());
+}
+
+#[test]
+fn can_compare_exchange_atomic_options() {
+    let one = ControlledOption::from(NonZeroU32::new(1));
+    let two = ControlledOption::from(NonZeroU32::new(2));
+
+    let atomic = AtomicControlledOption::new(ControlledOption::none());
+    assert_eq!(
+        atomic.compare_exchange(
+            ControlledOption::none(),
+            one,
+            Ordering::SeqCst,
+            Ordering::SeqCst
+        ),
+        Ok(ControlledOption::none())
+    );
+    assert_eq!(
+        atomic.compare_exchange(
+            two,
+            ControlledOption::none(),
+            Ordering::SeqCst,
+            Ordering::SeqCst
+        ),
+        Err(one)
+    );
+    assert_eq!(atomic.load(Ordering::SeqCst), one);
+}
+
+#[test]
+fn can_share_atomic_references_between_threads() {
+    static VALUE: u32 = 75;
+    let atomic = AtomicControlledOption::<&u32>::none();
+    std::thread::scope(|scope| {
+        scope.spawn(|| atomic.store(ControlledOption::some(&VALUE), Ordering::Release));
+    });
+    assert_eq!(atomic.load(Ordering::Acquire).into_option(), Some(&75));
+}
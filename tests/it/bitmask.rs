@@ -0,0 +1,122 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::cell::Cell;
+use std::num::NonZeroU32;
+use std::panic::AssertUnwindSafe;
+use std::rc::Rc;
+
+use controlled_option::BitmaskOptionVec;
+use controlled_option::ControlledOptionVec;
+use controlled_option::OptionVec;
+
+#[test]
+fn can_store_values_without_niches() {
+    let mut vec = BitmaskOptionVec::new();
+    for value in 0..100u32 {
+        if value % 3 == 0 {
+            vec.push_none();
+        } else {
+            vec.push_some(value);
+        }
+    }
+    assert_eq!(vec.len(), 100);
+    assert_eq!(vec.count_some(), 66);
+    assert_eq!(vec.get(0), None);
+    assert_eq!(vec.get(1), Some(&1));
+    assert_eq!(vec.set(0, Some(0)), None);
+    assert_eq!(vec.take(1), Some(1));
+    assert_eq!(vec.pop(), Some(None));
+    assert_eq!(vec.pop(), Some(Some(98)));
+    vec.truncate(3);
+    assert_eq!(format!("{:?}", vec), "[Some(0), None, Some(2)]");
+    vec.resize_none(70);
+    assert_eq!(vec.count_some(), 2);
+    vec.fill_none();
+    assert_eq!(vec.iter_some().count(), 0);
+}
+
+#[test]
+fn drops_present_values() {
+    let value = Rc::new(75);
+    let mut vec = BitmaskOptionVec::from_none(10);
+    vec.set(3, Some(value.clone()));
+    vec.set(7, Some(value.clone()));
+    assert_eq!(Rc::strong_count(&value), 3);
+    vec.truncate(5);
+    assert_eq!(Rc::strong_count(&value), 2);
+    let copy = vec.clone();
+    assert_eq!(copy, vec);
+    assert_eq!(Rc::strong_count(&value), 3);
+    drop(vec);
+    drop(copy);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+struct PanicOnDrop<'a> {
+    drops: &'a Cell<u32>,
+    panics: bool,
+}
+
+impl Drop for PanicOnDrop<'_> {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+        if self.panics {
+            panic!("dropped a PanicOnDrop");
+        }
+    }
+}
+
+#[test]
+fn panicking_destructors_are_not_run_twice() {
+    let drops = Cell::new(0);
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut vec = BitmaskOptionVec::from_none(4);
+        vec.set(
+            1,
+            Some(PanicOnDrop {
+                drops: &drops,
+                panics: true,
+            }),
+        );
+        vec.set(
+            2,
+            Some(PanicOnDrop {
+                drops: &drops,
+                panics: false,
+            }),
+        );
+        vec.truncate(1);
+    }));
+    assert!(result.is_err());
+    // Each element is dropped exactly once: the panicking one by `truncate`, and the other one
+    // when the vector itself is dropped during unwinding.
+    assert_eq!(drops.get(), 2);
+}
+
+fn fill_squares<V>(len: u32) -> V
+where
+    V: OptionVec<NonZeroU32>,
+{
+    let mut vec = V::from_none(len as usize);
+    for value in 1..len {
+        if value % 2 == 0 {
+            vec.set(value as usize, NonZeroU32::new(value * value));
+        }
+    }
+    vec
+}
+
+#[test]
+fn generic_code_can_use_either_storage() {
+    let controlled: ControlledOptionVec<NonZeroU32> = fill_squares(10);
+    let bitmask: BitmaskOptionVec<NonZeroU32> = fill_squares(10);
+    let controlled = OptionVec::iter_some(&controlled).collect::<Vec<_>>();
+    let bitmask = bitmask.iter_some().collect::<Vec<_>>();
+    assert_eq!(controlled, bitmask);
+    assert_eq!(bitmask.len(), 4);
+}
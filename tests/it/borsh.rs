@@ -0,0 +1,23 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+
+#[test]
+fn options_use_same_encoding_as_std_option() {
+    for id in [None, NonZeroU32::new(75)] {
+        let expected = borsh::to_vec(&id).unwrap();
+        let actual = borsh::to_vec(&ControlledOption::from(id)).unwrap();
+        assert_eq!(actual, expected);
+
+        let decoded: ControlledOption<NonZeroU32> = borsh::from_slice(&actual).unwrap();
+        assert_eq!(decoded, id);
+    }
+    assert!(borsh::from_slice::<ControlledOption<NonZeroU32>>(&[1, 0, 0, 0, 0]).is_err());
+}
@@ -0,0 +1,37 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use bytemuck::TransparentWrapper;
+use controlled_option::ControlledOption;
+
+#[test]
+fn can_cast_option_slices() {
+    let options: Vec<ControlledOption<NonZeroU32>> =
+        vec![NonZeroU32::new(75).into(), ControlledOption::none()];
+    let raw: &[u32] = bytemuck::cast_slice(&options);
+    assert_eq!(raw, &[75, 0]);
+    assert_eq!(bytemuck::cast_slice::<_, u8>(&options).len(), 8);
+
+    let back: &[ControlledOption<NonZeroU32>] = bytemuck::cast_slice(&[0u32, 125]);
+    assert!(back[0].is_none());
+    assert_eq!(back[1], ControlledOption::from(NonZeroU32::new(125)));
+}
+
+#[test]
+fn zeroed_options_are_none() {
+    let zeroed: ControlledOption<&u32> = bytemuck::Zeroable::zeroed();
+    assert!(zeroed.is_none());
+}
+
+#[test]
+fn can_wrap_output_values() {
+    let wrapped: ControlledOption<NonZeroU32> = ControlledOption::wrap(75);
+    assert_eq!(wrapped, ControlledOption::from(NonZeroU32::new(75)));
+    assert_eq!(ControlledOption::peel(wrapped), 75);
+}
@@ -0,0 +1,38 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledCell;
+use controlled_option::ControlledOption;
+
+#[test]
+fn can_get_and_set_cells() {
+    let one = ControlledOption::from(NonZeroU32::new(1));
+    let two = ControlledOption::from(NonZeroU32::new(2));
+
+    let cell = ControlledCell::<NonZeroU32>::none();
+    assert_eq!(std::mem::size_of::<ControlledCell<NonZeroU32>>(), 4);
+    assert!(cell.is_none());
+    cell.set(one);
+    assert!(cell.is_some());
+    assert_eq!(cell.get(), one);
+    assert_eq!(cell.replace(two), one);
+    assert_eq!(cell.take(), two);
+    assert!(cell.get().is_none());
+}
+
+#[test]
+fn can_use_cells_with_owned_values() {
+    let mut cell = ControlledCell::some(Box::new(75));
+    assert_eq!(
+        cell.replace(ControlledOption::some(Box::new(76))),
+        Box::new(75)
+    );
+    assert_eq!(cell.get_mut().as_option(), Some(&Box::new(76)));
+    assert_eq!(cell.into_inner(), Box::new(76));
+}
@@ -0,0 +1,51 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use controlled_option::NonMaxU16;
+
+const ONE: NonZeroU32 = match NonZeroU32::new(1) {
+    Some(value) => value,
+    None => panic!(),
+};
+
+static NUMBERS: [ControlledOption<NonZeroU32>; 3] = [
+    ControlledOption::const_some(ONE),
+    ControlledOption::NONE,
+    ControlledOption::const_none(),
+];
+
+const NAMES: [ControlledOption<&str>; 2] = [
+    ControlledOption::const_some("hello"),
+    ControlledOption::NONE,
+];
+
+const EMPTY: [ControlledOption<&u32>; 4] = [ControlledOption::NONE; 4];
+
+#[test]
+fn can_create_options_in_statics() {
+    assert_eq!(NUMBERS[0].into_option(), Some(ONE));
+    assert!(NUMBERS[1].is_none());
+    assert!(NUMBERS[2].is_none());
+    assert_eq!(NAMES[0].into_option(), Some("hello"));
+    assert!(NAMES[1].is_none());
+    assert!(EMPTY.iter().all(ControlledOption::is_none));
+}
+
+#[test]
+fn can_create_const_options_for_other_types() {
+    const CHAR: ControlledOption<char> = ControlledOption::const_some('x');
+    const BOOL: ControlledOption<bool> = ControlledOption::NONE;
+    const SLICE: ControlledOption<&[u8]> = ControlledOption::const_some(&[1, 2, 3]);
+    const NONMAX: ControlledOption<NonMaxU16> = ControlledOption::NONE;
+    assert_eq!(CHAR.into_option(), Some('x'));
+    assert!(BOOL.is_none());
+    assert_eq!(SLICE.into_option(), Some(&[1, 2, 3][..]));
+    assert!(NONMAX.is_none());
+}
@@ -0,0 +1,68 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::controlled;
+use controlled_option::ControlledOption;
+use controlled_option::Niche;
+
+#[controlled]
+#[derive(Clone, Debug, Default)]
+struct Node {
+    #[controlled]
+    parent: Option<NonZeroU32>,
+    #[controlled]
+    pub(crate) first_child: Option<&'static str>,
+    weight: Option<u32>,
+    #[cfg(any())]
+    #[controlled]
+    disabled: Option<NonZeroU32>,
+}
+
+#[controlled]
+struct Wrapper<T>
+where
+    T: Niche,
+{
+    #[controlled]
+    value: Option<T>,
+}
+
+#[test]
+fn can_rewrite_option_fields() {
+    let mut node = Node::default();
+    assert_eq!(node.parent(), None);
+    assert_eq!(node.first_child(), None);
+    assert_eq!(node.weight, None);
+    assert_eq!(
+        std::mem::size_of_val(&node.parent),
+        std::mem::size_of::<NonZeroU32>()
+    );
+
+    node.set_parent(NonZeroU32::new(1));
+    node.set_first_child(Some("child"));
+    node.weight = Some(2);
+    assert_eq!(node.parent(), NonZeroU32::new(1).as_ref());
+    assert_eq!(node.first_child(), Some(&"child"));
+    assert_eq!(node.clone().parent.into_option(), NonZeroU32::new(1));
+
+    assert_eq!(node.take_parent(), NonZeroU32::new(1));
+    assert_eq!(node.parent(), None);
+    assert_eq!(node.take_parent(), None);
+}
+
+#[test]
+fn can_rewrite_generic_option_fields() {
+    let mut wrapper = Wrapper::<&u32> {
+        value: ControlledOption::none(),
+    };
+    assert_eq!(wrapper.value(), None);
+    wrapper.set_value(Some(&5));
+    assert_eq!(wrapper.value(), Some(&&5));
+    assert_eq!(wrapper.take_value(), Some(&5));
+}
@@ -0,0 +1,21 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+// defmt output can only be decoded on an embedded target, so all we can check here is that the
+// expected types implement `Format`.
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+
+fn assert_format<T: defmt::Format>() {}
+
+#[test]
+fn options_implement_format() {
+    assert_format::<ControlledOption<NonZeroU32>>();
+    assert_format::<ControlledOption<&u32>>();
+}
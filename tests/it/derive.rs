@@ -0,0 +1,1033 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::marker::PhantomData;
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use controlled_option::ControlledOption;
+use controlled_option::Niche;
+use controlled_option::NicheOutput;
+use controlled_option::NicheUnion;
+use controlled_option::ZeroNiche;
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+enum State {
+    Idle,
+    Running(u32),
+    #[niche]
+    Invalid,
+    Done {
+        code: i32,
+    },
+}
+
+#[test]
+fn can_option_enums() {
+    let none = ControlledOption::<State>::none();
+    assert!(none.is_none());
+    assert_eq!(none.into_option(), None);
+
+    for &state in &[State::Idle, State::Running(7), State::Done { code: -1 }] {
+        let some = ControlledOption::some(state);
+        assert!(some.is_some());
+        assert_eq!(some.as_option(), Some(&state));
+        assert_eq!(some.into_option(), Some(state));
+    }
+
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<State>>(),
+        std::mem::size_of::<State>()
+    );
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+enum Slot<T: Copy> {
+    #[niche]
+    Vacant,
+    Occupied(T),
+}
+
+const EMPTY_SLOT: ControlledOption<Slot<u8>> = ControlledOption::NONE;
+
+#[test]
+fn can_option_generic_enums() {
+    assert!(EMPTY_SLOT.is_none());
+    let some = ControlledOption::some(Slot::Occupied(5u8));
+    assert_eq!(some.into_option(), Some(Slot::Occupied(5)));
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[repr(u8)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[test]
+fn can_option_c_like_enums() {
+    let none = ControlledOption::<Color>::none();
+    assert!(none.is_none());
+    let none_repr: u8 = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr, u8::MAX);
+
+    for &color in &[Color::Red, Color::Green, Color::Blue] {
+        let some = ControlledOption::some(color);
+        assert_eq!(some.as_option(), Some(&color));
+        assert_eq!(some.into_option(), Some(color));
+    }
+
+    assert_eq!(std::mem::size_of::<ControlledOption<Color>>(), 1);
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[repr(i8)]
+enum Sign {
+    Negative = -1,
+    Zero,
+    Positive,
+    Max = 127,
+}
+
+#[test]
+fn c_like_enum_niche_skips_used_discriminants() {
+    let none = ControlledOption::<Sign>::NONE;
+    assert!(none.is_none());
+    let none_repr: i8 = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr, 126);
+
+    for &sign in &[Sign::Negative, Sign::Zero, Sign::Positive, Sign::Max] {
+        assert_eq!(ControlledOption::some(sign).into_option(), Some(sign));
+    }
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[repr(u16)]
+#[niche(discriminant = 0)]
+enum Opcode {
+    Load = 1,
+    Store,
+    Jump = 0x80,
+}
+
+#[test]
+fn can_choose_c_like_enum_niche() {
+    let none = ControlledOption::<Opcode>::none();
+    assert!(none.is_none());
+    let none_repr: u16 = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr, 0);
+
+    for &opcode in &[Opcode::Load, Opcode::Store, Opcode::Jump] {
+        assert_eq!(ControlledOption::some(opcode).into_option(), Some(opcode));
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Key {
+    generation: u32,
+    id: NonZeroU32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(path = "key.id")]
+struct Entry {
+    weight: u32,
+    key: Key,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(path = "1.key.id")]
+struct TupleEntry(u8, Entry);
+
+#[test]
+fn can_option_structs_with_nested_niche() {
+    let none = ControlledOption::<Entry>::none();
+    assert!(none.is_none());
+    let none_repr: [u32; 3] = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr[2], 0);
+
+    let entry = Entry {
+        weight: 1,
+        key: Key {
+            generation: 2,
+            id: NonZeroU32::new(3).unwrap(),
+        },
+    };
+    let some = ControlledOption::some(entry);
+    assert_eq!(some.into_option(), Some(entry));
+
+    assert!(ControlledOption::<TupleEntry>::none().is_none());
+    let some = ControlledOption::some(TupleEntry(4, entry));
+    assert_eq!(some.into_option(), Some(TupleEntry(4, entry)));
+}
+
+// Pretend that this type is defined in some other crate, so that we can't implement `Niche` for it
+// ourselves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Fd(i32);
+
+mod fd_niche {
+    pub fn none() -> i32 {
+        -1
+    }
+
+    pub fn is_none(value: &i32) -> bool {
+        *value == -1
+    }
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct File {
+    #[niche(with = "fd_niche")]
+    fd: Fd,
+    flags: u32,
+}
+
+#[test]
+fn can_option_structs_with_custom_niche_functions() {
+    let none = ControlledOption::<File>::none();
+    assert!(none.is_none());
+
+    let file = File {
+        fd: Fd(3),
+        flags: 0,
+    };
+    let some = ControlledOption::some(file);
+    assert!(some.is_some());
+    assert_eq!(some.into_option(), Some(file));
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct Span {
+    start: u32,
+    #[niche(sentinel = u32::MAX)]
+    end: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct Offset(#[niche(sentinel = -1)] i64);
+
+#[test]
+fn can_option_structs_with_sentinel_fields() {
+    let none = ControlledOption::<Span>::none();
+    assert!(none.is_none());
+    let none_repr: [u32; 2] = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr[1], u32::MAX);
+
+    let span = Span { start: 0, end: 10 };
+    assert_eq!(ControlledOption::some(span).into_option(), Some(span));
+
+    assert!(ControlledOption::<Offset>::none().is_none());
+    assert_eq!(
+        ControlledOption::some(Offset(0)).into_option(),
+        Some(Offset(0))
+    );
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Offset>>(),
+        std::mem::size_of::<i64>()
+    );
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct RawPoint {
+    x: u32,
+    y: u32,
+}
+
+// Safety: RawPoint has the same fields as Point, and all-zero bytes are a valid RawPoint.
+unsafe impl NicheOutput<Point> for RawPoint {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(output = "RawPoint")]
+struct Point {
+    x: u32,
+    #[niche]
+    y: NonZeroU32,
+}
+
+#[test]
+fn can_option_structs_with_custom_output() {
+    let none = ControlledOption::<Point>::none();
+    assert!(none.is_none());
+    let none_repr: RawPoint = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr, RawPoint { x: 0, y: 0 });
+
+    let point = Point {
+        x: 1,
+        y: NonZeroU32::new(2).unwrap(),
+    };
+    let some = ControlledOption::some(point);
+    assert_eq!(some.as_option(), Some(&point));
+    let some_repr: RawPoint = unsafe { std::mem::transmute(some) };
+    assert_eq!(some_repr, RawPoint { x: 1, y: 2 });
+    assert_eq!(ControlledOption::some(point).into_option(), Some(point));
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(zero)]
+struct Range {
+    start: u32,
+    #[niche(sentinel = u32::MAX)]
+    end: u32,
+    step: u32,
+}
+
+#[test]
+fn can_zero_non_niche_fields() {
+    let none = ControlledOption::<Range>::none();
+    assert!(none.is_none());
+    let none_repr: [u32; 3] = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr, [0, u32::MAX, 0]);
+
+    let range = Range {
+        start: 1,
+        end: 10,
+        step: 3,
+    };
+    assert_eq!(ControlledOption::some(range).into_option(), Some(range));
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct NodeId(NonZeroU32);
+
+#[repr(transparent)]
+#[derive(Debug, Niche, PartialEq)]
+struct TypedId<T> {
+    id: NonZeroU32,
+    _phantom: PhantomData<T>,
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct Wrapper(NonZeroU32);
+
+#[test]
+fn transparent_newtypes_delegate_to_their_field() {
+    fn assert_output<T: Niche<Output = u32>>() {}
+    assert_output::<NodeId>();
+    assert_output::<TypedId<String>>();
+
+    let none = ControlledOption::<NodeId>::none();
+    assert!(none.is_none());
+    let none_repr: u32 = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr, 0);
+
+    let id = NodeId(NonZeroU32::new(7).unwrap());
+    let some = ControlledOption::some(id);
+    assert_eq!(some.as_option(), Some(&id));
+    assert_eq!(some.into_option(), Some(id));
+
+    let typed = TypedId::<String> {
+        id: NonZeroU32::new(8).unwrap(),
+        _phantom: PhantomData,
+    };
+    let some = ControlledOption::some(typed);
+    assert_eq!(some.as_option().map(|typed| typed.id.get()), Some(8));
+    assert!(ControlledOption::<TypedId<String>>::none().is_none());
+}
+
+#[test]
+fn single_field_structs_dont_need_niche_attribute() {
+    assert!(ControlledOption::<Wrapper>::none().is_none());
+    let wrapper = Wrapper(NonZeroU32::new(9).unwrap());
+    assert_eq!(ControlledOption::some(wrapper).into_option(), Some(wrapper));
+}
+
+#[derive(Debug, Niche)]
+struct Borrowed<'a, T: PartialEq + ?Sized> {
+    #[niche]
+    value: &'a T,
+    len: usize,
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct Buffer<const N: usize, T = u8>
+where
+    T: Copy,
+{
+    data: [T; N],
+    #[niche]
+    id: NonZeroU32,
+}
+
+#[repr(transparent)]
+#[derive(Debug, Niche)]
+struct Tagged<'a, T: ?Sized, const TAG: u8 = 0>(&'a T);
+
+#[test]
+fn can_option_structs_with_full_generics() {
+    let text = String::from("hello");
+    let borrowed = Borrowed {
+        value: text.as_str(),
+        len: text.len(),
+    };
+    let some = ControlledOption::some(borrowed);
+    assert_eq!(
+        some.as_option()
+            .map(|borrowed| (borrowed.value, borrowed.len)),
+        Some(("hello", 5))
+    );
+    assert!(ControlledOption::<Borrowed<str>>::none().is_none());
+
+    let buffer: Buffer<4> = Buffer {
+        data: [1, 2, 3, 4],
+        id: NonZeroU32::new(1).unwrap(),
+    };
+    assert_eq!(ControlledOption::some(buffer).into_option(), Some(buffer));
+    assert!(ControlledOption::<Buffer<4>>::none().is_none());
+
+    let tagged: Tagged<str, 3> = Tagged("tagged");
+    let some = ControlledOption::some(tagged);
+    assert_eq!(some.as_option().map(|tagged| tagged.0), Some("tagged"));
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Tagged<str>>>(),
+        std::mem::size_of::<&str>()
+    );
+}
+
+#[derive(Clone, Debug, Niche)]
+#[niche(drop)]
+struct Resource {
+    #[niche]
+    id: NonZeroU32,
+    data: Rc<String>,
+}
+
+#[test]
+fn can_option_structs_that_need_drop() {
+    let data = Rc::new(String::from("data"));
+    let resource = Resource {
+        id: NonZeroU32::new(1).unwrap(),
+        data: data.clone(),
+    };
+
+    let some = ControlledOption::some(resource);
+    assert_eq!(Rc::strong_count(&data), 2);
+    let cloned = some.clone();
+    assert_eq!(Rc::strong_count(&data), 3);
+    assert_eq!(cloned.as_option().map(|r| r.id.get()), Some(1));
+    drop(cloned);
+    drop(some);
+    assert_eq!(Rc::strong_count(&data), 1);
+
+    let none = ControlledOption::<Resource>::none();
+    assert!(none.is_none());
+    let cloned = none.clone();
+    assert!(cloned.is_none());
+    drop(none);
+
+    let resource = ControlledOption::some(Resource {
+        id: NonZeroU32::new(2).unwrap(),
+        data: data.clone(),
+    })
+    .into_option()
+    .unwrap();
+    assert_eq!(resource.data.as_str(), "data");
+    assert_eq!(Rc::strong_count(&data), 2);
+    drop(resource);
+    assert_eq!(Rc::strong_count(&data), 1);
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct LargeNode {
+    #[niche]
+    kind: NonZeroU32,
+    children: [u64; 31],
+}
+
+#[test]
+fn can_wrap_large_structs_in_place() {
+    let mut children = [0; 31];
+    for (index, child) in children.iter_mut().enumerate() {
+        *child = index as u64 * 3;
+    }
+    let node = LargeNode {
+        kind: NonZeroU32::new(7).unwrap(),
+        children,
+    };
+
+    let some = ControlledOption::some(node);
+    assert!(some.is_some());
+    assert_eq!(some.as_option(), Some(&node));
+    assert_eq!(some.into_option(), Some(node));
+    assert_eq!(ControlledOption::from(Some(node)).into_option(), Some(node));
+    assert_eq!(ControlledOption::<LargeNode>::none().into_option(), None);
+
+    let mut slot = std::mem::MaybeUninit::<<LargeNode as Niche>::Output>::uninit();
+    unsafe {
+        LargeNode::write_some(node, slot.as_mut_ptr());
+        assert!(!LargeNode::is_none(&*slot.as_ptr()));
+        assert_eq!(LargeNode::read_some(slot.as_ptr()), node);
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Niche)]
+union Value {
+    #[niche]
+    handle: NonZeroU32,
+    bits: u32,
+    bytes: [u8; 4],
+}
+
+// Safety: Every member covers all four bytes, and so the `handle` member is always initialized.
+// The tests below never construct a `Some` value whose bytes are all zero.
+unsafe impl NicheUnion for Value {}
+
+#[test]
+fn can_option_unions() {
+    let none = ControlledOption::<Value>::none();
+    assert!(none.is_none());
+    let none_repr: u32 = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr, 0);
+
+    let value = Value { bits: 0x0102_0304 };
+    let some = ControlledOption::some(value);
+    assert!(some.is_some());
+    assert_eq!(
+        some.as_option().map(|v| unsafe { v.bits }),
+        Some(0x0102_0304)
+    );
+
+    let value = Value {
+        bytes: [0, 0, 0, 1],
+    };
+    let some = ControlledOption::some(value);
+    assert!(some.is_some());
+    assert_eq!(
+        some.into_option().map(|v| unsafe { v.handle.get() }),
+        Some(u32::from_ne_bytes([0, 0, 0, 1]))
+    );
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Value>>(),
+        std::mem::size_of::<u32>()
+    );
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct Inner {
+    #[niche]
+    id: NonZeroU32,
+    x: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(repr)]
+struct Outer {
+    y: u64,
+    #[niche]
+    inner: Inner,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(repr, zero)]
+struct OuterTuple(u16, #[niche] Inner);
+
+#[test]
+fn can_option_structs_with_generated_repr() {
+    fn assert_output<T: Niche<Output = std::mem::MaybeUninit<R>>, R>() {}
+    assert_output::<Outer, OuterNicheRepr>();
+    assert_output::<OuterTuple, OuterTupleNicheRepr>();
+
+    let none = ControlledOption::<Outer>::none();
+    assert!(none.is_none());
+
+    let outer = Outer {
+        y: 5,
+        inner: Inner {
+            id: NonZeroU32::new(6).unwrap(),
+            x: 7,
+        },
+    };
+    let some = ControlledOption::some(outer);
+    assert!(some.is_some());
+    assert_eq!(some.as_option(), Some(&outer));
+    assert_eq!(some.into_option(), Some(outer));
+
+    let none = ControlledOption::<OuterTuple>::none();
+    assert!(none.is_none());
+    let tuple = OuterTuple(1, outer.inner);
+    assert_eq!(ControlledOption::some(tuple).into_option(), Some(tuple));
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(alias = "OptionalAliased")]
+struct Aliased {
+    #[niche]
+    id: NonZeroU32,
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(alias = "OptionalGenericAliased", zero)]
+struct GenericAliased<'a, T: Copy, const N: usize>
+where
+    T: 'a,
+{
+    #[niche]
+    id: NonZeroU32,
+    values: &'a [T; N],
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(alias = "OptionalAliasedEnum")]
+#[repr(u8)]
+enum AliasedEnum {
+    A,
+    B,
+}
+
+#[test]
+fn can_alias_derived_options() {
+    let aliased = Aliased {
+        id: NonZeroU32::new(1).unwrap(),
+    };
+    let option: OptionalAliased = ControlledOption::some(aliased);
+    assert_eq!(option.into_option(), Some(aliased));
+    assert!(OptionalAliased::none().is_none());
+
+    let values = [1u8, 2, 3];
+    let generic = GenericAliased {
+        id: NonZeroU32::new(1).unwrap(),
+        values: &values,
+    };
+    let option: OptionalGenericAliased<u8, 3> = ControlledOption::some(generic);
+    assert_eq!(option.into_option(), Some(generic));
+
+    for value in [AliasedEnum::A, AliasedEnum::B] {
+        let option: OptionalAliasedEnum = ControlledOption::some(value);
+        assert_eq!(option.into_option(), Some(value));
+    }
+    assert!(OptionalAliasedEnum::none().is_none());
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct Prioritized {
+    #[niche(priority = 1, strategy = "ByLow")]
+    low: NonZeroU32,
+    #[niche(priority = 2, sentinel = u32::MAX)]
+    high: u32,
+    #[niche]
+    default: NonZeroU32,
+}
+
+#[test]
+fn uses_highest_priority_niche_field() {
+    assert!(ControlledOption::<Prioritized>::none().is_none());
+    // The `high` field is the niche, so a `Some` value can have any value for the other
+    // candidates.
+    let value = Prioritized {
+        low: NonZeroU32::new(1).unwrap(),
+        high: 2,
+        default: NonZeroU32::new(3).unwrap(),
+    };
+    let some = ControlledOption::some(value);
+    assert!(some.is_some());
+    assert_eq!(some.into_option(), Some(value));
+
+    // The `ByLow` strategy uses the `low` field instead.
+    let value = Prioritized {
+        high: u32::MAX,
+        ..value
+    };
+    assert!(ControlledOption::<Prioritized, ByLow>::none_with_strategy().is_none());
+    let some = ControlledOption::<Prioritized, ByLow>::some_with_strategy(value);
+    assert!(some.is_some());
+    assert_eq!(some.into_option(), Some(value));
+}
+
+trait Keyed {
+    type Key;
+}
+
+trait NicheKeyed {
+    type Key: Niche;
+}
+
+struct U32Keys;
+
+impl Keyed for U32Keys {
+    type Key = NonZeroU32;
+}
+
+impl NicheKeyed for U32Keys {
+    type Key = NonZeroU32;
+}
+
+#[derive(Niche)]
+#[niche(bound = "K::Key: Niche + Copy")]
+struct KeyedEntry<K: Keyed> {
+    #[niche]
+    key: K::Key,
+    value: u32,
+}
+
+#[derive(Niche)]
+#[niche(bound = "")]
+struct NicheKeyedEntry<K: NicheKeyed> {
+    #[niche]
+    key: K::Key,
+    value: u32,
+}
+
+#[test]
+fn can_override_derived_bounds() {
+    let none = ControlledOption::<KeyedEntry<U32Keys>>::none();
+    assert!(none.is_none());
+    let entry = KeyedEntry::<U32Keys> {
+        key: NonZeroU32::new(1).unwrap(),
+        value: 2,
+    };
+    let entry = ControlledOption::some(entry).into_option().unwrap();
+    assert_eq!((entry.key.get(), entry.value), (1, 2));
+
+    let none = ControlledOption::<NicheKeyedEntry<U32Keys>>::none();
+    assert!(none.is_none());
+    let entry = NicheKeyedEntry::<U32Keys> {
+        key: NonZeroU32::new(3).unwrap(),
+        value: 4,
+    };
+    let entry = ControlledOption::some(entry).into_option().unwrap();
+    assert_eq!((entry.key.get(), entry.value), (3, 4));
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(zero)]
+struct Zeroed {
+    #[niche]
+    id: NonZeroU32,
+    value: u32,
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(discriminant = 0)]
+#[repr(u8)]
+enum ZeroedEnum {
+    A = 1,
+    B = 2,
+}
+
+fn assert_zero_niche<T: ZeroNiche>() {}
+
+#[test]
+fn derives_zero_niche_when_none_is_zeroed() {
+    assert_zero_niche::<Zeroed>();
+    assert_zero_niche::<NodeId>();
+    assert_zero_niche::<ZeroedEnum>();
+
+    let none_repr: [u32; 2] = unsafe { std::mem::transmute(ControlledOption::<Zeroed>::none()) };
+    assert_eq!(none_repr, [0, 0]);
+    let none_repr: u8 = unsafe { std::mem::transmute(ControlledOption::<ZeroedEnum>::none()) };
+    assert_eq!(none_repr, 0);
+    for value in [ZeroedEnum::A, ZeroedEnum::B] {
+        assert_eq!(ControlledOption::some(value).into_option(), Some(value));
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(mirror = "Clone, Copy, Debug, PartialEq, Eq, Hash")]
+struct MirroredInner {
+    #[niche]
+    id: NonZeroU32,
+    x: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(mirror = "Clone, Copy, Debug, PartialEq, Eq, Hash")]
+struct Mirrored {
+    count: u32,
+    #[niche]
+    inner: MirroredInner,
+    flag: bool,
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(mirror)]
+struct MirroredNewtype(NonZeroU32);
+
+#[test]
+fn can_option_structs_with_mirror_repr() {
+    let none = ControlledOption::<Mirrored>::none();
+    assert!(none.is_none());
+    // Two `None` values compare equal, since every field is initialized.
+    assert_eq!(Mirrored::none(), Mirrored::none());
+    let none_repr = Mirrored::none();
+    assert_eq!((none_repr.count, none_repr.flag), (0, false));
+
+    let value = Mirrored {
+        count: 1,
+        inner: MirroredInner {
+            id: NonZeroU32::new(2).unwrap(),
+            x: 3,
+        },
+        flag: true,
+    };
+    let some = ControlledOption::some(value);
+    assert!(some.is_some());
+    assert_eq!(some.as_option(), Some(&value));
+    assert_eq!(some.into_option(), Some(value));
+    assert_ne!(Mirrored::into_some(value), Mirrored::none());
+
+    let newtype = MirroredNewtype(NonZeroU32::new(4).unwrap());
+    let _: MirroredNewtypeNicheRepr = MirroredNewtype::none();
+    assert!(ControlledOption::<MirroredNewtype>::none().is_none());
+    assert_eq!(ControlledOption::some(newtype).into_option(), Some(newtype));
+}
+
+mod remote {
+    use std::num::NonZeroU32;
+
+    pub struct Handle<T> {
+        pub index: NonZeroU32,
+        pub generation: u32,
+        pub value: T,
+    }
+}
+
+controlled_option::derive_niche_for! {
+    #[niche(remote = "std::ops::Range<u32>", zero)]
+    struct RangeNiche {
+        start: u32,
+        #[niche(sentinel = u32::MAX)]
+        end: u32,
+    }
+}
+
+controlled_option::derive_niche_for! {
+    #[niche(remote = "remote::Handle<T>")]
+    struct HandleNiche<T: Copy> {
+        #[niche]
+        index: NonZeroU32,
+        generation: u32,
+    }
+}
+
+#[test]
+fn can_derive_strategies_for_remote_types() {
+    type OptionalRange = ControlledOption<std::ops::Range<u32>, RangeNiche>;
+    assert!(OptionalRange::none_with_strategy().is_none());
+    let range = OptionalRange::some_with_strategy(1..5);
+    assert!(range.is_some());
+    assert_eq!(range.into_option(), Some(1..5));
+    assert_eq!(
+        std::mem::size_of::<OptionalRange>(),
+        std::mem::size_of::<std::ops::Range<u32>>()
+    );
+
+    type OptionalHandle = ControlledOption<remote::Handle<char>, HandleNiche>;
+    assert!(OptionalHandle::none_with_strategy().is_none());
+    let handle = remote::Handle {
+        index: NonZeroU32::new(1).unwrap(),
+        generation: 2,
+        value: 'x',
+    };
+    let handle = OptionalHandle::some_with_strategy(handle)
+        .into_option()
+        .unwrap();
+    assert_eq!(
+        (handle.index.get(), handle.generation, handle.value),
+        (1, 2, 'x')
+    );
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(none_value = RawSlice::invalid())]
+struct RawSlice {
+    ptr: *const u8,
+    len: usize,
+}
+
+impl RawSlice {
+    const fn invalid() -> RawSlice {
+        RawSlice {
+            ptr: std::ptr::null(),
+            len: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct RawInterval {
+    start: u32,
+    end: u32,
+}
+
+// Safety: RawInterval has the same fields as Interval, and all-zero bytes are a valid
+// RawInterval.
+unsafe impl NicheOutput<Interval> for RawInterval {}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(output = "RawInterval", none_value = RawInterval { start: 1, end: 0 })]
+struct Interval {
+    start: u32,
+    end: u32,
+}
+
+#[test]
+fn can_provide_whole_none_value() {
+    const NONE: ControlledOption<RawSlice> = ControlledOption::NONE;
+    assert!(NONE.is_none());
+    assert!(ControlledOption::<RawSlice>::none().is_none());
+    // Only the combination of fields is invalid.
+    let data = [1u8, 2, 3];
+    for value in [
+        RawSlice {
+            ptr: data.as_ptr(),
+            len: 3,
+        },
+        RawSlice {
+            ptr: data.as_ptr(),
+            len: 0,
+        },
+        RawSlice {
+            ptr: std::ptr::null(),
+            len: 1,
+        },
+    ] {
+        assert_eq!(ControlledOption::some(value).into_option(), Some(value));
+    }
+
+    let none = ControlledOption::<Interval>::none();
+    assert!(none.is_none());
+    let none_repr: RawInterval = unsafe { std::mem::transmute(none) };
+    assert_eq!(none_repr, RawInterval { start: 1, end: 0 });
+    let interval = Interval { start: 0, end: 0 };
+    let some = ControlledOption::some(interval);
+    assert_eq!(some.as_option(), Some(&interval));
+    assert_eq!(some.into_option(), Some(interval));
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+struct Configured {
+    #[cfg(target_pointer_width = "64")]
+    #[niche]
+    wide: NonZeroU32,
+    #[cfg(not(target_pointer_width = "64"))]
+    #[niche]
+    narrow: NonZeroU32,
+    value: u32,
+}
+
+impl Configured {
+    fn new(id: NonZeroU32, value: u32) -> Configured {
+        Configured {
+            #[cfg(target_pointer_width = "64")]
+            wide: id,
+            #[cfg(not(target_pointer_width = "64"))]
+            narrow: id,
+            value,
+        }
+    }
+}
+
+struct RemotePair {
+    first: u32,
+    second: u32,
+}
+
+controlled_option::derive_niche_for! {
+    #[niche(remote = "RemotePair")]
+    struct ConfiguredPairNiche {
+        #[cfg(target_pointer_width = "64")]
+        #[niche(sentinel = 0)]
+        first: u32,
+        #[cfg(not(target_pointer_width = "64"))]
+        #[niche(sentinel = 0)]
+        second: u32,
+        #[cfg(any())]
+        missing: u32,
+    }
+}
+
+#[test]
+fn respects_cfg_gated_fields() {
+    assert!(ControlledOption::<Configured>::none().is_none());
+    let value = Configured::new(NonZeroU32::new(1).unwrap(), 2);
+    assert_eq!(ControlledOption::some(value).into_option(), Some(value));
+
+    type OptionalPair = ControlledOption<RemotePair, ConfiguredPairNiche>;
+    assert!(OptionalPair::none_with_strategy().is_none());
+    let pair = OptionalPair::some_with_strategy(RemotePair {
+        first: 1,
+        second: 2,
+    })
+    .into_option()
+    .unwrap();
+    assert_eq!((pair.first, pair.second), (1, 2));
+    // Only the field for this configuration is the niche.
+    #[cfg(target_pointer_width = "64")]
+    let pair = RemotePair {
+        first: 1,
+        second: 0,
+    };
+    #[cfg(not(target_pointer_width = "64"))]
+    let pair = RemotePair {
+        first: 0,
+        second: 1,
+    };
+    assert!(OptionalPair::some_with_strategy(pair).is_some());
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(bitwise)]
+struct Particle {
+    position: [f32; 3],
+    #[niche]
+    id: std::num::NonZeroU64,
+    velocity: [f32; 3],
+}
+
+#[derive(Clone, Copy, Debug, Niche, PartialEq)]
+#[niche(zero, path = "particle.id")]
+struct Tracked {
+    generation: u16,
+    particle: Particle,
+}
+
+#[test]
+fn can_check_niche_fields_bitwise() {
+    let particles = [
+        ControlledOption::none(),
+        ControlledOption::some(Particle {
+            position: [1.0, 2.0, 3.0],
+            id: std::num::NonZeroU64::new(1).unwrap(),
+            velocity: [0.0; 3],
+        }),
+        ControlledOption::none(),
+        ControlledOption::some(Particle {
+            position: [0.0; 3],
+            id: std::num::NonZeroU64::new(u64::MAX).unwrap(),
+            velocity: [0.0; 3],
+        }),
+    ];
+    let present = particles.iter().filter(|p| p.is_some()).count();
+    assert_eq!(present, 2);
+    assert_eq!(particles[1].as_option().unwrap().position, [1.0, 2.0, 3.0]);
+
+    assert!(ControlledOption::<Tracked>::none().is_none());
+    let tracked = Tracked {
+        generation: 1,
+        particle: *particles[3].as_option().unwrap(),
+    };
+    assert_eq!(ControlledOption::some(tracked).into_option(), Some(tracked));
+}
@@ -0,0 +1,55 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledEither;
+
+#[test]
+fn can_create_eithers() {
+    let value = NonZeroU32::new(75).unwrap();
+
+    let left = ControlledEither::<NonZeroU32, ()>::from_left(value);
+    assert_eq!(std::mem::size_of::<ControlledEither<NonZeroU32, ()>>(), 4);
+    assert!(left.is_left());
+    assert_eq!(left.as_left(), Some(&value));
+    assert_eq!(left.as_right(), None);
+    assert_eq!(left.left(), Some(value));
+
+    let right = ControlledEither::<NonZeroU32, ()>::from_right(());
+    assert!(right.is_right());
+    assert_eq!(right.right(), Some(()));
+    assert_eq!(right.left(), None);
+    assert_ne!(left, right);
+}
+
+#[test]
+fn can_map_eithers() {
+    let value = NonZeroU32::new(75).unwrap();
+
+    let left = ControlledEither::<NonZeroU32, ()>::from_left(value);
+    let mapped = left.map_left(|value| value.get() as u8 as char);
+    assert_eq!(mapped.left(), Some('K'));
+    let mapped = left.map_right(|()| ());
+    assert_eq!(mapped.left(), Some(value));
+
+    let right = ControlledEither::<NonZeroU32, ()>::from_right(());
+    let mapped = right.map_left(|value| value.get() as u8 as char);
+    assert!(mapped.is_right());
+}
+
+#[cfg(feature = "either")]
+#[test]
+fn can_convert_to_and_from_either() {
+    use either::Either;
+
+    let value = NonZeroU32::new(75).unwrap();
+    let left = ControlledEither::<NonZeroU32, ()>::from(Either::Left(value));
+    assert_eq!(Either::from(left), Either::Left(value));
+    let right = ControlledEither::<NonZeroU32, ()>::from(Either::Right(()));
+    assert_eq!(Either::from(right), Either::<NonZeroU32, ()>::Right(()));
+}
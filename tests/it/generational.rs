@@ -0,0 +1,44 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use controlled_option::GenerationalId;
+
+#[test]
+fn can_bump_generations() {
+    let id = GenerationalId::new(7);
+    assert_eq!(id.index(), 7);
+    assert_eq!(id.generation().get(), 1);
+
+    let next = id.next_generation();
+    assert_eq!(next.index(), 7);
+    assert_eq!(next.generation().get(), 2);
+    assert!(next.supersedes(id));
+    assert!(!id.supersedes(next));
+    assert!(!next.supersedes(GenerationalId::new(8)));
+    assert!(next.is_live(NonZeroU32::new(2).unwrap()));
+    assert!(!id.is_live(NonZeroU32::new(2).unwrap()));
+
+    let last = GenerationalId::from_parts(7, NonZeroU32::new(u32::MAX).unwrap());
+    assert_eq!(last.next_generation(), id);
+}
+
+#[test]
+fn can_option_generational_ids() {
+    let id = GenerationalId::new(0);
+    let some = ControlledOption::some(id);
+    assert_eq!(some.into_option(), Some(id));
+    assert!(ControlledOption::<GenerationalId>::none().is_none());
+    const NONE: ControlledOption<GenerationalId> = ControlledOption::NONE;
+    assert!(NONE.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<GenerationalId>>(),
+        std::mem::size_of::<GenerationalId>()
+    );
+}
@@ -0,0 +1,34 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use controlled_option::ControlledOption;
+use generational_arena::Arena;
+use generational_arena::Index;
+
+#[test]
+fn can_option_arena_indices() {
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Index>>(),
+        std::mem::size_of::<Index>()
+    );
+
+    let mut arena = Arena::new();
+    let first = arena.insert("first");
+    arena.remove(first);
+    let second = arena.insert("second");
+    for index in [first, second] {
+        let some = ControlledOption::some(index);
+        assert!(some.is_some());
+        assert_eq!(some.into_option(), Some(index));
+    }
+    let some = ControlledOption::some(second);
+    assert_eq!(some.as_option().map(|index| arena[*index]), Some("second"));
+
+    let none = ControlledOption::<Index>::none();
+    assert!(none.is_none());
+    assert_eq!(none.into_option(), None);
+}
@@ -0,0 +1,52 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::convert::TryFrom;
+
+use controlled_option::ControlledOption;
+use controlled_option::Handle32;
+use controlled_option::Handle64;
+use controlled_option::InvalidHandle;
+
+#[test]
+fn can_pack_handles() {
+    let handle = Handle32::<24>::new(5, 2).unwrap();
+    assert_eq!(handle.index(), 5);
+    assert_eq!(handle.metadata(), 2);
+    assert_eq!(u32::from(handle), (2 << 24) | 5);
+    assert_eq!(
+        format!("{:?}", handle),
+        "Handle32 { index: 5, metadata: 2 }"
+    );
+
+    // Values that don't fit, or that would produce the invalid pattern, are rejected.
+    assert_eq!(Handle32::<24>::new(1 << 24, 0), None);
+    assert_eq!(Handle32::<24>::new(0, 1 << 8), None);
+    assert_eq!(Handle32::<24>::new((1 << 24) - 1, (1 << 8) - 1), None);
+
+    let handle = Handle64::<40>::new(1 << 39, 7).unwrap();
+    assert_eq!(handle.index(), 1 << 39);
+    assert_eq!(handle.metadata(), 7);
+}
+
+#[test]
+fn can_convert_raw_integers() {
+    let handle: Handle64 = Handle64::try_from(42).unwrap();
+    assert_eq!(handle.index(), 42);
+    assert_eq!(handle.metadata(), 0);
+    assert_eq!(Handle64::<32>::try_from(u64::MAX), Err(InvalidHandle));
+}
+
+#[test]
+fn can_option_handles() {
+    let handle = Handle32::<16>::new(1, 1).unwrap();
+    let some = ControlledOption::some(handle);
+    assert_eq!(some.into_option(), Some(handle));
+    assert!(ControlledOption::<Handle32>::none().is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<Handle32>>(), 4);
+    assert_eq!(std::mem::size_of::<ControlledOption<Handle64>>(), 8);
+}
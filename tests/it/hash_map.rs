@@ -0,0 +1,71 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledHashMap;
+
+fn nz(value: u32) -> NonZeroU32 {
+    NonZeroU32::new(value).unwrap()
+}
+
+#[test]
+fn can_insert_get_and_remove() {
+    let mut map = ControlledHashMap::new();
+    assert!(map.is_empty());
+    assert_eq!(map.insert(nz(1), "one"), None);
+    assert_eq!(map.insert(nz(2), "two"), None);
+    assert_eq!(map.insert(nz(1), "uno"), Some("one"));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&nz(1)), Some(&"uno"));
+    assert_eq!(map.get(&nz(3)), None);
+    *map.get_mut(&nz(2)).unwrap() = "dos";
+    assert_eq!(map.remove(&nz(2)), Some("dos"));
+    assert_eq!(map.remove(&nz(2)), None);
+    assert!(!map.contains_key(&nz(2)));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn matches_std_hash_map() {
+    let mut map = ControlledHashMap::new();
+    let mut expected = HashMap::new();
+    // A simple pseudo-random sequence of inserts and removes, with lots of collisions.
+    let mut state = 1u32;
+    for _ in 0..5000 {
+        state = state.wrapping_mul(1103515245).wrapping_add(12345);
+        let key = nz(state % 200 + 1);
+        if state & 0x10000 == 0 {
+            assert_eq!(map.remove(&key), expected.remove(&key));
+        } else {
+            assert_eq!(map.insert(key, state), expected.insert(key, state));
+        }
+        assert_eq!(map.len(), expected.len());
+    }
+    for (key, value) in &expected {
+        assert_eq!(map.get(key), Some(value));
+    }
+    let mut entries = map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+    let mut expected = expected.into_iter().collect::<Vec<_>>();
+    entries.sort();
+    expected.sort();
+    assert_eq!(entries, expected);
+}
+
+#[test]
+fn drops_owned_values() {
+    let mut map: ControlledHashMap<Box<u32>, Box<u32>> = (1..=100)
+        .map(|value| (Box::new(value), Box::new(value * 2)))
+        .collect();
+    assert_eq!(map.get(&50), Some(&Box::new(100)));
+    assert_eq!(map.remove(&50), Some(Box::new(100)));
+    assert_eq!(map.keys().count(), 99);
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.values().count(), 0);
+}
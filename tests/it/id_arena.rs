@@ -0,0 +1,33 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use controlled_option::ControlledOption;
+use id_arena::Arena;
+use id_arena::Id;
+
+struct Node {
+    parent: ControlledOption<Id<Node>>,
+}
+
+#[test]
+fn can_option_arena_ids() {
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Id<Node>>>(),
+        std::mem::size_of::<Id<Node>>()
+    );
+
+    let mut arena = Arena::<Node>::new();
+    let root = arena.alloc(Node {
+        parent: ControlledOption::none(),
+    });
+    let child = arena.alloc(Node {
+        parent: ControlledOption::some(root),
+    });
+    assert!(arena[root].parent.is_none());
+    assert_eq!(arena[child].parent.into_option(), Some(root));
+    assert_eq!(ControlledOption::<Id<Node>>::none().into_option(), None);
+}
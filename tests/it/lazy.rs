@@ -0,0 +1,45 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::cell::Cell;
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledLazy;
+
+#[test]
+fn initializes_on_first_access() {
+    let calls = Cell::new(0);
+    let lazy = ControlledLazy::new(|| {
+        calls.set(calls.get() + 1);
+        NonZeroU32::new(42).unwrap()
+    });
+    assert_eq!(ControlledLazy::get(&lazy), None);
+    assert_eq!(format!("{:?}", lazy), "ControlledLazy(<uninit>)");
+    assert_eq!(lazy.get(), 42);
+    assert_eq!(lazy.get(), 42);
+    assert_eq!(calls.get(), 1);
+    assert_eq!(ControlledLazy::into_inner(lazy).ok(), NonZeroU32::new(42));
+}
+
+#[test]
+fn can_recover_uncalled_function() {
+    let lazy: ControlledLazy<&'static str> = ControlledLazy::new(|| "hello");
+    let f = ControlledLazy::into_inner(lazy).unwrap_err();
+    assert_eq!(f(), "hello");
+}
+
+#[test]
+fn lazy_values_are_compact() {
+    assert_eq!(
+        std::mem::size_of::<ControlledLazy<&u64>>(),
+        2 * std::mem::size_of::<usize>()
+    );
+    let nodes = (1..=3)
+        .map(|value| ControlledLazy::new(move || Box::new(value * 10)))
+        .collect::<Vec<_>>();
+    assert_eq!(**nodes[1], 20);
+}
@@ -0,0 +1,91 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::panic::AssertUnwindSafe;
+
+use controlled_option::NicheLink;
+use controlled_option::NonMaxU32;
+
+fn index(i: u32) -> NonMaxU32 {
+    NonMaxU32::new(i).unwrap()
+}
+
+#[test]
+fn links_have_same_size_as_index() {
+    assert_eq!(
+        std::mem::size_of::<NicheLink<NonMaxU32>>(),
+        std::mem::size_of::<u32>()
+    );
+}
+
+#[test]
+fn can_use_links_as_free_list() {
+    let mut slots: Vec<NicheLink<NonMaxU32>> = vec![NicheLink::none(); 4];
+    let mut free = NicheLink::none();
+    assert!(free.is_none());
+    for i in 0..4 {
+        free.push_front(&mut slots, index(i), |slot| slot);
+    }
+    let order: Vec<_> = free.iter(&slots, |slot| slot).map(NonMaxU32::get).collect();
+    assert_eq!(order, vec![3, 2, 1, 0]);
+    assert_eq!(free.pop_front(&mut slots, |slot| slot), Some(index(3)));
+    assert_eq!(free.pop_front(&mut slots, |slot| slot), Some(index(2)));
+    free.push_front(&mut slots, index(3), |slot| slot);
+    assert_eq!(free.get(), Some(index(3)));
+    assert_eq!(free.pop_front(&mut slots, |slot| slot), Some(index(3)));
+    assert_eq!(free.pop_front(&mut slots, |slot| slot), Some(index(1)));
+    assert_eq!(free.pop_front(&mut slots, |slot| slot), Some(index(0)));
+    assert_eq!(free.pop_front(&mut slots, |slot| slot), None);
+    assert!(free.is_none());
+}
+
+#[test]
+fn out_of_bounds_push_leaves_list_unchanged() {
+    let mut slots: Vec<NicheLink<NonMaxU32>> = vec![NicheLink::none(); 2];
+    let mut free = NicheLink::none();
+    free.push_front(&mut slots, index(1), |slot| slot);
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        free.push_front(&mut slots, index(5), |slot| slot);
+    }));
+    assert!(result.is_err());
+    assert_eq!(free.get(), Some(index(1)));
+}
+
+#[test]
+fn can_thread_multiple_lists_through_nodes() {
+    struct Node {
+        value: u32,
+        next_even: NicheLink<NonMaxU32>,
+        next_odd: NicheLink<NonMaxU32>,
+    }
+    let mut nodes: Vec<Node> = (0..6)
+        .map(|value| Node {
+            value,
+            next_even: NicheLink::none(),
+            next_odd: NicheLink::none(),
+        })
+        .collect();
+    let mut evens = NicheLink::none();
+    let mut odds = NicheLink::none();
+    for i in 0..6 {
+        if nodes[i as usize].value.is_multiple_of(2) {
+            evens.push_front(&mut nodes, index(i), |node| &mut node.next_even);
+        } else {
+            odds.push_front(&mut nodes, index(i), |node| &mut node.next_odd);
+        }
+    }
+    let evens: Vec<_> = evens
+        .iter(&nodes, |node| &node.next_even)
+        .map(|i| nodes[i.get() as usize].value)
+        .collect();
+    let odds: Vec<_> = odds
+        .iter(&nodes, |node| &node.next_odd)
+        .map(|i| nodes[i.get() as usize].value)
+        .collect();
+    assert_eq!(evens, vec![4, 2, 0]);
+    assert_eq!(odds, vec![5, 3, 1]);
+}
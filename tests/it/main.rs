@@ -6,10 +6,73 @@
 // ------------------------------------------------------------------------------------------------
 
 use std::num::NonZeroU32;
+use std::ptr::NonNull;
 
 use controlled_option::ControlledOption;
 use controlled_option::Niche;
 
+#[cfg(feature = "abi_stable")]
+mod abi_stable;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "borsh")]
+mod borsh;
+#[cfg(feature = "bytemuck")]
+mod bytemuck;
+#[cfg(feature = "defmt")]
+mod defmt;
+#[cfg(feature = "generational-arena")]
+mod generational_arena;
+#[cfg(feature = "id-arena")]
+mod id_arena;
+#[cfg(feature = "petgraph")]
+mod petgraph;
+#[cfg(feature = "proptest")]
+mod proptest;
+#[cfg(feature = "quickcheck")]
+mod quickcheck;
+#[cfg(feature = "rkyv")]
+mod rkyv;
+#[cfg(feature = "schemars")]
+mod schemars;
+#[cfg(feature = "serde-with")]
+mod serde;
+#[cfg(feature = "slotmap")]
+mod slotmap;
+#[cfg(feature = "zerocopy")]
+mod zerocopy;
+
+mod array;
+mod atomic;
+mod bitmask;
+mod cell;
+mod const_niche;
+mod controlled;
+mod derive;
+mod either;
+mod generational;
+mod handle;
+mod hash_map;
+mod lazy;
+mod link;
+mod maybe_niche;
+mod multi_niche;
+mod nonmax;
+mod nonnan;
+mod once;
+mod option_ext;
+mod prelude;
+mod reserved;
+mod result;
+mod sentinel;
+mod slab;
+mod slice;
+mod std_option;
+mod strategy;
+mod symbol;
+mod tagged;
+mod vec;
+
 #[test]
 fn can_option_references() {
     let none = ControlledOption::<&u32>::none();
@@ -25,6 +88,553 @@ fn can_option_references() {
     assert_eq!(<&u32>::into_some(&value), &value);
 }
 
+#[test]
+fn can_option_non_null_pointers() {
+    let none = ControlledOption::<NonNull<u32>>::none();
+    assert!(none.is_none());
+    // `None` pointers should be represented by the null pointer.
+    assert_eq!(NonNull::<u32>::none(), std::ptr::null_mut());
+
+    let mut value = 75;
+    let ptr = NonNull::from(&mut value);
+    let some = ControlledOption::some(ptr);
+    assert!(some.is_some());
+    assert_eq!(some.into_option(), Some(ptr));
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<NonNull<u32>>>(),
+        std::mem::size_of::<*mut u32>()
+    );
+}
+
+#[test]
+fn can_option_fat_references() {
+    let none = ControlledOption::<&str>::none();
+    assert!(none.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<&str>>(),
+        std::mem::size_of::<&str>()
+    );
+    let some = ControlledOption::some("hello");
+    assert_eq!(some.into_option(), Some("hello"));
+    let empty = ControlledOption::some("");
+    assert_eq!(empty.into_option(), Some(""));
+
+    let slice = [1, 2, 3];
+    let none = ControlledOption::<&[u32]>::none();
+    assert!(none.is_none());
+    let some = ControlledOption::some(&slice[..]);
+    assert_eq!(some.into_option(), Some(&[1, 2, 3][..]));
+
+    let mut string = String::from("hello");
+    let some = ControlledOption::some(string.as_mut_str());
+    some.into_option().unwrap().make_ascii_uppercase();
+    assert_eq!(string, "HELLO");
+    assert!(ControlledOption::<&mut str>::none().is_none());
+
+    let mut slice = [1, 2, 3];
+    let some = ControlledOption::some(&mut slice[..]);
+    some.into_option().unwrap()[0] = 4;
+    assert_eq!(slice, [4, 2, 3]);
+    assert!(ControlledOption::<&mut [u32]>::none().is_none());
+}
+
+#[test]
+fn can_option_function_pointers() {
+    fn double(value: u32) -> u32 {
+        value * 2
+    }
+
+    extern "C" fn add(a: u32, b: u32) -> u32 {
+        a + b
+    }
+
+    let none = ControlledOption::<fn(u32) -> u32>::none();
+    assert!(none.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<fn(u32) -> u32>>(),
+        std::mem::size_of::<fn(u32) -> u32>()
+    );
+    let some = ControlledOption::some(double as fn(u32) -> u32);
+    assert_eq!(some.into_option().map(|f| f(21)), Some(42));
+
+    let none = ControlledOption::<extern "C" fn(u32, u32) -> u32>::none();
+    assert!(none.is_none());
+    let some = ControlledOption::some(add as extern "C" fn(u32, u32) -> u32);
+    assert_eq!(some.as_option().map(|f| f(40, 2)), Some(42));
+}
+
+#[test]
+fn can_option_chars() {
+    let none = ControlledOption::<char>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<char>>(), 4);
+    for ch in ['\0', 'a', '\u{D7FF}', '\u{E000}', char::MAX] {
+        let some = ControlledOption::some(ch);
+        assert!(some.is_some());
+        assert_eq!(some.as_option(), Some(&ch));
+        assert_eq!(some.into_option(), Some(ch));
+    }
+}
+
+#[test]
+fn options_are_ordered_like_std_options() {
+    // The niche for `char` is the first surrogate code point, `0xD800`.  That's larger than some
+    // valid `char`s and smaller than others, but `None` still sorts before all of them.
+    let none = ControlledOption::<char>::none();
+    let a = ControlledOption::some('a');
+    let b = ControlledOption::some('b');
+    let high = ControlledOption::some('\u{E000}');
+    let max = ControlledOption::some(char::MAX);
+    assert!(none < a);
+    assert!(a < b);
+    assert!(b < high);
+    assert!(none < high);
+    assert!(none < max);
+    assert!(high < max);
+    assert_eq!(none.cmp(&b), None.cmp(&Some('b')));
+    assert_eq!(none.cmp(&max), None.cmp(&Some(char::MAX)));
+    assert_eq!(max.cmp(&none), Some(char::MAX).cmp(&None));
+    assert_eq!(b.partial_cmp(&a), Some('b').partial_cmp(&Some('a')));
+}
+
+// A type whose equality is coarser than its bit pattern: it ignores the `hint` field.
+#[derive(Clone, Copy, Debug, Niche)]
+struct Keyed {
+    #[niche]
+    key: NonZeroU32,
+    hint: u32,
+}
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Keyed {}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl std::hash::Hash for Keyed {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key.hash(state)
+    }
+}
+
+#[test]
+fn options_are_equal_and_hashed_like_std_options() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hash;
+    use std::hash::Hasher;
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let key = NonZeroU32::new(75).unwrap();
+    let a = ControlledOption::some(Keyed { key, hint: 1 });
+    let b = ControlledOption::some(Keyed { key, hint: 2 });
+    assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+    assert_eq!(a, b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+    assert_eq!(hash_of(&a), hash_of(&a.as_option()));
+    assert_ne!(a, ControlledOption::none());
+    // The options are equal, but they still hold different values.
+    assert_eq!(b.into_option().map(|keyed| keyed.hint), Some(2));
+}
+
+#[test]
+fn can_option_bools() {
+    let none = ControlledOption::<bool>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<bool>>(), 1);
+    for value in [false, true] {
+        let some = ControlledOption::some(value);
+        assert!(some.is_some());
+        assert_eq!(some.as_option(), Some(&value));
+        assert_eq!(some.into_option(), Some(value));
+    }
+}
+
+#[test]
+fn can_option_orderings() {
+    use std::cmp::Ordering;
+    let none = ControlledOption::<Ordering>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<Ordering>>(), 1);
+    for value in [Ordering::Less, Ordering::Equal, Ordering::Greater] {
+        let some = ControlledOption::some(value);
+        assert!(some.is_some());
+        assert_eq!(some.as_option(), Some(&value));
+        assert_eq!(some.into_option(), Some(value));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn can_option_file_descriptors() {
+    use std::os::unix::io::AsFd;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::io::BorrowedFd;
+    use std::os::unix::io::OwnedFd;
+
+    assert!(ControlledOption::<OwnedFd>::none().is_none());
+    assert!(ControlledOption::<BorrowedFd>::none().is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<OwnedFd>>(),
+        std::mem::size_of::<OwnedFd>()
+    );
+
+    let file = std::fs::File::open("Cargo.toml").unwrap();
+    let borrowed = ControlledOption::some(file.as_fd());
+    assert_eq!(
+        borrowed.into_option().unwrap().as_raw_fd(),
+        file.as_raw_fd()
+    );
+
+    let owned = ControlledOption::some(OwnedFd::from(file));
+    let raw = owned.as_option().unwrap().as_raw_fd();
+    let file = std::fs::File::from(owned.into_option().unwrap());
+    assert_eq!(file.as_raw_fd(), raw);
+    drop(ControlledOption::some(OwnedFd::from(file)));
+}
+
+#[test]
+fn can_option_infallible() {
+    use std::convert::Infallible;
+    let none = ControlledOption::<Infallible>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<Infallible>>(), 0);
+    assert_eq!(none.into_option(), None);
+    assert_eq!(ControlledOption::<Infallible>::from(None), none);
+}
+
+#[test]
+fn can_option_pinned_pointers() {
+    use std::pin::Pin;
+
+    let none = ControlledOption::<Pin<Box<u32>>>::none();
+    assert!(none.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Pin<Box<u32>>>>(),
+        std::mem::size_of::<Box<u32>>()
+    );
+    let some = ControlledOption::some(Box::pin(75));
+    assert_eq!(some.as_option().map(|pinned| **pinned), Some(75));
+    assert_eq!(some.into_option(), Some(Box::pin(75)));
+
+    let value = 75;
+    let some = ControlledOption::some(Pin::new(&value));
+    assert_eq!(some.into_option().map(|pinned| *pinned), Some(75));
+    assert!(ControlledOption::<Pin<&u32>>::none().is_none());
+
+    let mut value = 75;
+    let some = ControlledOption::some(Pin::new(&mut value));
+    *some.into_option().unwrap() = 76;
+    assert_eq!(value, 76);
+    assert!(ControlledOption::<Pin<&mut u32>>::none().is_none());
+}
+
+#[test]
+fn can_option_manually_dropped_values() {
+    use std::mem::ManuallyDrop;
+
+    let none = ControlledOption::<ManuallyDrop<NonZeroU32>>::none();
+    assert!(none.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<ManuallyDrop<NonZeroU32>>>(),
+        4
+    );
+    let value = ManuallyDrop::new(NonZeroU32::new(75).unwrap());
+    let some = ControlledOption::some(value);
+    assert_eq!(some.as_option(), Some(&value));
+    assert_eq!(some.into_option(), Some(value));
+
+    // Dropping the option must not drop the wrapped value.
+    let rc = std::rc::Rc::new(75);
+    let mut unwrapped = {
+        let some = ControlledOption::some(ManuallyDrop::new(Box::new(rc.clone())));
+        assert_eq!(std::rc::Rc::strong_count(&rc), 2);
+        let cloned = some.clone();
+        assert_eq!(std::rc::Rc::strong_count(&rc), 3);
+        cloned.into_option().unwrap()
+    };
+    assert_eq!(std::rc::Rc::strong_count(&rc), 3);
+    unsafe { ManuallyDrop::drop(&mut unwrapped) };
+    assert_eq!(std::rc::Rc::strong_count(&rc), 2);
+}
+
+#[test]
+fn can_option_cells() {
+    use std::cell::Cell;
+    use std::cell::UnsafeCell;
+
+    let none = ControlledOption::<Cell<NonZeroU32>>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<Cell<NonZeroU32>>>(), 4);
+    let some = ControlledOption::some(Cell::new(NonZeroU32::new(75).unwrap()));
+    let cell = some.as_option().unwrap();
+    cell.set(NonZeroU32::new(76).unwrap());
+    assert_eq!(
+        some.into_option().map(Cell::into_inner),
+        NonZeroU32::new(76)
+    );
+
+    let none = ControlledOption::<UnsafeCell<NonZeroU32>>::none();
+    assert!(none.is_none());
+    let some = ControlledOption::some(UnsafeCell::new(NonZeroU32::new(75).unwrap()));
+    let cell = some.as_option().unwrap();
+    unsafe { *cell.get() = NonZeroU32::new(76).unwrap() };
+    assert_eq!(
+        some.into_option().map(UnsafeCell::into_inner),
+        NonZeroU32::new(76)
+    );
+}
+
+// A (buggy) implementation whose `as_some` doesn't return a reference into its argument.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Stray(u32);
+
+static STRAY: Stray = Stray(0);
+
+impl Niche for Stray {
+    type Output = u32;
+
+    fn none() -> Self::Output {
+        u32::MAX
+    }
+
+    fn is_none(value: &Self::Output) -> bool {
+        *value == u32::MAX
+    }
+
+    fn into_some(value: Self) -> Self::Output {
+        value.0
+    }
+
+    fn from_some(value: Self::Output) -> Self {
+        Stray(value)
+    }
+
+    fn as_some(_value: &Self::Output) -> &Self {
+        &STRAY
+    }
+}
+
+#[test]
+#[should_panic(expected = "must return a reference into the value")]
+fn cells_reject_stray_as_some_references() {
+    let some = ControlledOption::some(std::cell::Cell::new(Stray(75)));
+    let _ = some.as_option();
+}
+
+#[test]
+fn can_option_transparent_wrappers() {
+    use std::cmp::Reverse;
+    use std::num::Saturating;
+    use std::num::Wrapping;
+
+    let value = NonZeroU32::new(75).unwrap();
+
+    assert!(ControlledOption::<Reverse<NonZeroU32>>::none().is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Reverse<NonZeroU32>>>(),
+        4
+    );
+    let some = ControlledOption::some(Reverse(value));
+    assert_eq!(some.as_option(), Some(&Reverse(value)));
+    assert_eq!(some.into_option(), Some(Reverse(value)));
+
+    assert!(ControlledOption::<Wrapping<NonZeroU32>>::none().is_none());
+    let some = ControlledOption::some(Wrapping(value));
+    assert_eq!(some.into_option(), Some(Wrapping(value)));
+
+    assert!(ControlledOption::<Saturating<NonZeroU32>>::none().is_none());
+    let some = ControlledOption::some(Saturating(value));
+    assert_eq!(some.into_option(), Some(Saturating(value)));
+}
+
+#[test]
+fn can_option_tuples() {
+    let value = NonZeroU32::new(75).unwrap();
+
+    let none = ControlledOption::<(NonZeroU32, u32)>::none();
+    assert!(none.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<(NonZeroU32, u32)>>(),
+        8
+    );
+    let some = ControlledOption::some((value, 0));
+    assert_eq!(some.as_option(), Some(&(value, 0)));
+    assert_eq!(some.into_option(), Some((value, 0)));
+
+    let some = ControlledOption::some((value,));
+    assert_eq!(some.into_option(), Some((value,)));
+    let some = ControlledOption::some((value, 1u8, 2u16, 3u64));
+    assert_eq!(some.into_option(), Some((value, 1, 2, 3)));
+    assert!(ControlledOption::<(NonZeroU32, u8, u16, u64)>::none().is_none());
+}
+
+#[test]
+fn can_option_arrays() {
+    let values = [1, 2, 3].map(|value| NonZeroU32::new(value).unwrap());
+
+    let none = ControlledOption::<[NonZeroU32; 3]>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<[NonZeroU32; 3]>>(), 12);
+    let some = ControlledOption::some(values);
+    assert_eq!(some.as_option(), Some(&values));
+    assert_eq!(some.into_option(), Some(values));
+}
+
+#[test]
+fn can_option_layouts() {
+    use std::alloc::Layout;
+
+    let none = ControlledOption::<Layout>::none();
+    assert!(none.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Layout>>(),
+        std::mem::size_of::<Layout>()
+    );
+    for layout in [
+        Layout::new::<()>(),
+        Layout::new::<u64>(),
+        Layout::new::<[u8; 3]>(),
+    ] {
+        let some = ControlledOption::some(layout);
+        assert!(some.is_some());
+        assert_eq!(some.as_option(), Some(&layout));
+        assert_eq!(some.into_option(), Some(layout));
+    }
+}
+
+#[test]
+fn can_option_boxes() {
+    let none = ControlledOption::<Box<u32>>::none();
+    assert!(none.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Box<u32>>>(),
+        std::mem::size_of::<Box<u32>>()
+    );
+
+    let some = ControlledOption::some(Box::new(75));
+    assert!(some.is_some());
+    assert_eq!(some.as_option(), Some(&Box::new(75)));
+    assert_eq!(some.clone(), some);
+    assert_ne!(some, none);
+    assert_eq!(some.into_option(), Some(Box::new(75)));
+}
+
+#[test]
+fn boxed_options_drop_their_contents() {
+    let value = std::rc::Rc::new(75);
+    let some = ControlledOption::some(Box::new(value.clone()));
+    assert_eq!(std::rc::Rc::strong_count(&value), 2);
+    let cloned = some.clone();
+    assert_eq!(std::rc::Rc::strong_count(&value), 3);
+    drop(some);
+    drop(cloned);
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+
+    // Unwrapping the option moves the box out without dropping it.
+    let some = ControlledOption::some(Box::new(value.clone()));
+    let unwrapped = some.into_option().unwrap();
+    assert_eq!(std::rc::Rc::strong_count(&value), 2);
+    drop(unwrapped);
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn can_option_boxed_slices() {
+    let some = ControlledOption::some(Box::<str>::from("hello"));
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Box<str>>>(),
+        std::mem::size_of::<Box<str>>()
+    );
+    assert_eq!(some.clone().into_option().as_deref(), Some("hello"));
+    assert!(ControlledOption::<Box<str>>::none().is_none());
+
+    let some = ControlledOption::some(Box::<[u32]>::from(vec![1, 2, 3]));
+    assert_eq!(some.clone().into_option().as_deref(), Some(&[1, 2, 3][..]));
+    assert!(ControlledOption::<Box<[u32]>>::none().is_none());
+    let empty = ControlledOption::some(Box::<[u32]>::from(vec![]));
+    assert!(empty.is_some());
+
+    let cstring = std::ffi::CString::new("hello").unwrap();
+    let some = ControlledOption::some(cstring.clone().into_boxed_c_str());
+    assert_eq!(some.into_option().as_deref(), Some(cstring.as_c_str()));
+    assert!(ControlledOption::<Box<std::ffi::CStr>>::none().is_none());
+}
+
+#[test]
+fn can_option_owned_buffers() {
+    assert!(ControlledOption::<Vec<u32>>::none().is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Vec<u32>>>(),
+        std::mem::size_of::<Vec<u32>>()
+    );
+    let some = ControlledOption::some(vec![1, 2, 3]);
+    assert_eq!(some.clone().into_option(), Some(vec![1, 2, 3]));
+    assert_eq!(some.as_option().map(Vec::len), Some(3));
+    let empty = ControlledOption::some(Vec::<u32>::new());
+    assert_eq!(empty.into_option(), Some(vec![]));
+    let empty = ControlledOption::some(Vec::<()>::new());
+    assert!(empty.is_some());
+
+    assert!(ControlledOption::<String>::none().is_none());
+    let some = ControlledOption::some(String::from("hello"));
+    assert_eq!(some.clone().into_option().as_deref(), Some("hello"));
+    assert_eq!(
+        ControlledOption::some(String::new()).into_option(),
+        Some(String::new())
+    );
+
+    let cstring = std::ffi::CString::new("hello").unwrap();
+    assert!(ControlledOption::<std::ffi::CString>::none().is_none());
+    let some = ControlledOption::some(cstring.clone());
+    assert_eq!(some.into_option(), Some(cstring));
+}
+
+#[test]
+fn can_option_shared_pointers() {
+    let value = std::rc::Rc::new(75);
+    let some = ControlledOption::some(value.clone());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<std::rc::Rc<u32>>>(),
+        std::mem::size_of::<std::rc::Rc<u32>>()
+    );
+    assert_eq!(std::rc::Rc::strong_count(&value), 2);
+    let cloned = some.clone();
+    assert_eq!(std::rc::Rc::strong_count(&value), 3);
+    assert_eq!(cloned.as_option(), Some(&value));
+    drop(some);
+    drop(cloned);
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+    assert!(ControlledOption::<std::rc::Rc<u32>>::none().is_none());
+
+    let value = std::sync::Arc::new(75);
+    let some = ControlledOption::some(value.clone());
+    assert_eq!(std::sync::Arc::strong_count(&value), 2);
+    let unwrapped = some.into_option().unwrap();
+    assert_eq!(std::sync::Arc::strong_count(&value), 2);
+    drop(unwrapped);
+    assert_eq!(std::sync::Arc::strong_count(&value), 1);
+    assert!(ControlledOption::<std::sync::Arc<u32>>::none().is_none());
+}
+
 #[test]
 fn can_option_nonzeros() {
     let none = ControlledOption::from(NonZeroU32::new(0));
@@ -38,6 +648,24 @@ fn can_option_nonzeros() {
     assert_eq!(NonZeroU32::from_some(75), NonZeroU32::new(75).unwrap());
 }
 
+#[test]
+fn can_option_128_bit_nonzeros() {
+    use std::num::NonZeroI128;
+    use std::num::NonZeroU128;
+
+    assert!(ControlledOption::from(NonZeroU128::new(0)).is_none());
+    let some = ControlledOption::from(NonZeroU128::new(u128::MAX));
+    assert_eq!(some.into_option(), NonZeroU128::new(u128::MAX));
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<NonZeroU128>>(),
+        std::mem::size_of::<u128>()
+    );
+
+    assert!(ControlledOption::from(NonZeroI128::new(0)).is_none());
+    let some = ControlledOption::from(NonZeroI128::new(i128::MIN));
+    assert_eq!(some.into_option(), NonZeroI128::new(i128::MIN));
+}
+
 // This is a struct that has two fields that have niche values available.  We'll explicitly choose
 // to use the one from the second field as the niche for the struct as a whole.
 
@@ -135,3 +763,53 @@ fn can_option_generic_structs() {
     assert_eq!(some_repr.0, 75);
     assert_eq!(some_repr.1, 125);
 }
+
+// `ControlledOption` should be `Send` and `Sync` whenever its payload and the payload's `Output`
+// type both are.  (References are stored as raw pointers, which aren't.)
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn options_track_auto_traits_of_payload() {
+    assert_send_sync::<ControlledOption<NonZeroU32>>();
+    assert_send_sync::<ControlledOption<TestStruct>>();
+}
+
+#[test]
+fn can_convert_options_to_std() {
+    let value = TestStruct::new(75, 125);
+    let some = ControlledOption::some(value);
+    let none = ControlledOption::<TestStruct>::none();
+
+    // Borrowed conversions don't consume the option.
+    assert_eq!(some.as_option().map(|v| v.b.get()), Some(125));
+    assert!(Option::<&TestStruct>::from(&none).is_none());
+
+    assert_eq!(Option::from(some).map(|v: TestStruct| v.a.get()), Some(75));
+    let converted: Option<TestStruct> = none.into();
+    assert!(converted.is_none());
+
+    let number = 75;
+    let reference = ControlledOption::some(&number);
+    assert_eq!(reference.as_option(), Some(&&75));
+}
+
+#[test]
+fn can_compare_options_with_std_options_and_values() {
+    let id = NonZeroU32::new(75).unwrap();
+    let some = ControlledOption::some(id);
+    let none = ControlledOption::<NonZeroU32>::none();
+
+    assert_eq!(some, Some(id));
+    assert_eq!(Some(id), some);
+    assert_eq!(none, None);
+    assert_eq!(some, id);
+    assert_ne!(none, id);
+
+    let bigger = NonZeroU32::new(125).unwrap();
+    assert!(none < Some(id));
+    assert!(some < Some(bigger));
+    assert!(Some(bigger) > some);
+    assert!(some < bigger);
+    assert!(none < id);
+}
@@ -0,0 +1,59 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use controlled_option::DefaultNiche;
+use controlled_option::FallbackNiche;
+use controlled_option::MaybeNiche;
+use controlled_option::NicheStrategy;
+
+#[test]
+fn can_option_types_without_niches() {
+    let none = ControlledOption::<MaybeNiche<u32>>::none();
+    assert!(none.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<MaybeNiche<u32>>>(),
+        std::mem::size_of::<Option<u32>>()
+    );
+
+    let some = ControlledOption::some(MaybeNiche(0));
+    assert!(some.is_some());
+    assert_eq!(some.as_option(), Some(&MaybeNiche(0)));
+    assert_eq!(some.into_option().map(MaybeNiche::into_inner), Some(0));
+    assert_eq!(some, MaybeNiche(0));
+    assert_ne!(some, none);
+}
+
+#[test]
+fn maybe_niche_options_drop_their_contents() {
+    let value = std::rc::Rc::new(75);
+    let some = ControlledOption::some(MaybeNiche(value.clone()));
+    assert_eq!(std::rc::Rc::strong_count(&value), 2);
+    drop(some);
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+}
+
+fn round_trip<T, S>(value: T) -> (usize, Option<T>)
+where
+    S: NicheStrategy<T>,
+{
+    assert!(ControlledOption::<T, S>::none_with_strategy().is_none());
+    let some = ControlledOption::<T, S>::some_with_strategy(value);
+    (std::mem::size_of_val(&some), some.into_option())
+}
+
+#[test]
+fn strategies_choose_between_niche_and_fallback() {
+    let value = NonZeroU32::new(75).unwrap();
+    assert_eq!(round_trip::<_, DefaultNiche>(value), (4, Some(value)));
+    assert_eq!(round_trip::<_, FallbackNiche>(value), (4, Some(value)));
+    assert_eq!(round_trip::<_, FallbackNiche>(75u32), (8, Some(75u32)));
+    let (_, string) = round_trip::<_, FallbackNiche>(String::from("hello"));
+    assert_eq!(string.as_deref(), Some("hello"));
+}
@@ -0,0 +1,71 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::cmp::Ordering;
+
+use controlled_option::ControlledOption;
+use controlled_option::MultiNiche;
+use controlled_option::Niche;
+use controlled_option::NonNanF32;
+
+#[test]
+fn nested_options_stay_flat() {
+    type Nested = ControlledOption<ControlledOption<bool>>;
+    assert_eq!(std::mem::size_of::<Nested>(), 1);
+
+    let outer_none = Nested::none();
+    assert!(outer_none.is_none());
+
+    let inner_none = Nested::some(ControlledOption::none());
+    assert!(inner_none.is_some());
+    assert!(inner_none.into_option().unwrap().is_none());
+
+    let inner_some = Nested::some(ControlledOption::some(true));
+    assert_eq!(inner_some.into_option().unwrap().into_option(), Some(true));
+}
+
+#[test]
+fn can_nest_options_several_levels_deep() {
+    type Nested = ControlledOption<ControlledOption<ControlledOption<char>>>;
+    assert_eq!(std::mem::size_of::<Nested>(), 4);
+
+    let values = vec![
+        Nested::none(),
+        Nested::some(ControlledOption::none()),
+        Nested::some(ControlledOption::some(ControlledOption::none())),
+        Nested::some(ControlledOption::some(ControlledOption::some('x'))),
+    ];
+    let flattened = values
+        .into_iter()
+        .map(|value| {
+            value
+                .into_option()
+                .map(|value| value.into_option().map(ControlledOption::into_option))
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        flattened,
+        vec![None, Some(None), Some(Some(None)), Some(Some(Some('x')))]
+    );
+}
+
+#[test]
+fn extra_niches_are_distinct() {
+    for index in 0..<Ordering as MultiNiche>::EXTRA_NICHES {
+        let niche = Ordering::extra_niche(index);
+        assert!(!Ordering::is_none(&niche));
+        assert_eq!(Ordering::extra_niche_index(&niche), Some(index));
+    }
+    assert_eq!(Ordering::extra_niche_index(&Ordering::none()), None);
+
+    let nested = ControlledOption::some(ControlledOption::<NonNanF32>::none());
+    assert!(nested.into_option().unwrap().is_none());
+    assert_eq!(
+        NonNanF32::extra_niche_index(&NonNanF32::extra_niche(5)),
+        Some(5)
+    );
+}
@@ -0,0 +1,93 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use controlled_option::ControlledOption;
+use controlled_option::NonMaxU32;
+use controlled_option::NonMaxU8;
+use controlled_option::NonMaxUsize;
+use controlled_option::NonMinI32;
+use controlled_option::NonMinI8;
+
+#[test]
+fn can_create_nonmax_values() {
+    assert_eq!(NonMaxU32::new(u32::MAX), None);
+    assert_eq!(NonMaxU32::new(0).map(NonMaxU32::get), Some(0));
+    assert_eq!(NonMaxU32::MAX.get(), u32::MAX - 1);
+    assert_eq!(NonMaxU32::MIN.get(), 0);
+    assert_eq!(u8::from(NonMaxU8::new(7).unwrap()), 7);
+    assert_eq!(format!("{:?}", NonMaxU32::new(75).unwrap()), "75");
+    assert!(NonMaxU32::new(1).unwrap() < NonMaxU32::new(2).unwrap());
+}
+
+#[test]
+fn can_option_nonmax_values() {
+    let none = ControlledOption::<NonMaxU32>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<NonMaxU32>>(), 4);
+
+    let zero = ControlledOption::from(NonMaxU32::new(0));
+    assert!(zero.is_some());
+    assert_eq!(zero.into_option(), NonMaxU32::new(0));
+
+    let some = ControlledOption::some(NonMaxUsize::MAX);
+    assert_eq!(some.into_option(), Some(NonMaxUsize::MAX));
+    assert!(ControlledOption::<NonMaxU8>::none().is_none());
+}
+
+#[test]
+fn can_create_nonmin_values() {
+    assert_eq!(NonMinI32::new(i32::MIN), None);
+    assert_eq!(NonMinI32::new(-1).map(NonMinI32::get), Some(-1));
+    assert_eq!(NonMinI32::MIN.get(), i32::MIN + 1);
+    assert_eq!(NonMinI32::MAX.get(), i32::MAX);
+    assert_eq!(NonMinI32::NICHE, i32::MIN);
+    assert_eq!(i8::from(NonMinI8::new(-7).unwrap()), -7);
+}
+
+#[test]
+fn can_option_nonmin_values() {
+    let none = ControlledOption::<NonMinI32>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<NonMinI32>>(), 4);
+
+    let some = ControlledOption::from(NonMinI32::new(-1));
+    assert!(some.is_some());
+    assert_eq!(some.into_option(), NonMinI32::new(-1));
+    assert!(ControlledOption::<NonMinI8>::none().is_none());
+}
+
+#[cfg(feature = "nonmax")]
+#[test]
+fn can_option_nonmax_crate_values() {
+    let none = ControlledOption::<nonmax::NonMaxU32>::none();
+    assert!(none.is_none());
+    assert_eq!(none.into_option(), None);
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<nonmax::NonMaxU32>>(),
+        4
+    );
+
+    let zero = ControlledOption::from(nonmax::NonMaxU32::new(0));
+    assert!(zero.is_some());
+    assert_eq!(zero.as_option().map(nonmax::NonMaxU32::get), Some(0));
+    assert_eq!(zero.to_std(), nonmax::NonMaxU32::new(0));
+
+    let max = nonmax::NonMaxU64::new(u64::MAX - 1).unwrap();
+    let some = ControlledOption::some(max);
+    assert_eq!(some.into_option(), Some(max));
+
+    let negative = nonmax::NonMaxI8::new(-1).unwrap();
+    let some = ControlledOption::some(negative);
+    assert_eq!(some.as_option().map(nonmax::NonMaxI8::get), Some(-1));
+    assert!(ControlledOption::<nonmax::NonMaxI8>::none().is_none());
+
+    // Options are ordered by their values, just like a standard `Option`.
+    let small = ControlledOption::from(nonmax::NonMaxU32::new(1));
+    let large = ControlledOption::from(nonmax::NonMaxU32::new(2));
+    assert!(small < large);
+    assert!(ControlledOption::<nonmax::NonMaxU32>::none() < small);
+}
@@ -0,0 +1,44 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use controlled_option::ControlledOption;
+use controlled_option::Niche;
+use controlled_option::NonNanF32;
+use controlled_option::NonNanF64;
+
+#[test]
+fn can_option_non_nan_floats() {
+    assert_eq!(NonNanF32::new(f32::NAN), None);
+    assert_eq!(NonNanF64::new(-f64::NAN), None);
+
+    let value = NonNanF32::new(1.5).unwrap();
+    let some = ControlledOption::some(value);
+    assert_eq!(some.into_option(), Some(value));
+    assert_eq!(NonNanF32::none(), f32::NAN.to_bits());
+    assert!(ControlledOption::<NonNanF32>::none().is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<NonNanF32>>(), 4);
+    assert_eq!(std::mem::size_of::<ControlledOption<NonNanF64>>(), 8);
+
+    let infinity = ControlledOption::some(NonNanF64::new(f64::INFINITY).unwrap());
+    assert_eq!(
+        infinity.into_option().map(NonNanF64::get),
+        Some(f64::INFINITY)
+    );
+}
+
+#[test]
+fn non_nan_floats_are_totally_ordered() {
+    let mut values = [2.0, -0.0, f64::NEG_INFINITY, 0.0, -1.0]
+        .iter()
+        .map(|value| NonNanF64::new(*value).unwrap())
+        .collect::<Vec<_>>();
+    values.sort();
+    let values = values.into_iter().map(f64::from).collect::<Vec<_>>();
+    assert_eq!(values, vec![f64::NEG_INFINITY, -1.0, -0.0, 0.0, 2.0]);
+    assert!(values[2].is_sign_negative());
+    assert_ne!(NonNanF64::new(-0.0), NonNanF64::new(0.0));
+}
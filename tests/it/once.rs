@@ -0,0 +1,73 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOnceCell;
+use controlled_option::ControlledOnceLock;
+
+#[test]
+fn can_fill_once_cells() {
+    let one = NonZeroU32::new(1).unwrap();
+    let two = NonZeroU32::new(2).unwrap();
+
+    let mut cell = ControlledOnceCell::<NonZeroU32>::new();
+    assert_eq!(std::mem::size_of::<ControlledOnceCell<NonZeroU32>>(), 4);
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.get_or_init(|| one), &one);
+    assert_eq!(cell.set(two), Err(two));
+    assert_eq!(cell.get_or_init(|| two), &one);
+    *cell.get_mut().unwrap() = two;
+    assert_eq!(cell.get(), Some(&two));
+    assert_eq!(cell.take().into_option(), Some(two));
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.set(one), Ok(()));
+    assert_eq!(cell.into_inner().into_option(), Some(one));
+}
+
+#[test]
+fn can_fill_once_cells_with_owned_values() {
+    let cell = ControlledOnceCell::<Box<u32>>::new();
+    assert_eq!(cell.get_or_init(|| Box::new(75)), &Box::new(75));
+    assert_eq!(cell.set(Box::new(76)), Err(Box::new(76)));
+}
+
+#[test]
+fn can_fill_once_locks() {
+    let one = NonZeroU32::new(1).unwrap();
+    let two = NonZeroU32::new(2).unwrap();
+
+    let mut lock = ControlledOnceLock::<NonZeroU32>::new();
+    assert_eq!(std::mem::size_of::<ControlledOnceLock<NonZeroU32>>(), 4);
+    assert_eq!(lock.get(), None);
+    assert_eq!(lock.get_or_init(|| one), one);
+    assert_eq!(lock.set(two), Err(two));
+    assert_eq!(lock.get_or_init(|| two), one);
+    assert_eq!(lock.take().into_option(), Some(one));
+    assert_eq!(lock.set(two), Ok(()));
+    assert_eq!(lock.into_inner().into_option(), Some(two));
+}
+
+#[test]
+fn once_locks_are_initialized_exactly_once() {
+    static VALUES: [u32; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+    let lock = ControlledOnceLock::<&u32>::new();
+    let results = std::thread::scope(|scope| {
+        let handles = (0..8)
+            .map(|i| {
+                let lock = &lock;
+                scope.spawn(move || lock.get_or_init(|| &VALUES[i]))
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    let winner = lock.get().unwrap();
+    assert!(results.iter().all(|result| std::ptr::eq(*result, winner)));
+}
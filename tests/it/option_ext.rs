@@ -0,0 +1,24 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use controlled_option::OptionExt;
+
+#[test]
+fn can_convert_std_options() {
+    let some: ControlledOption<NonZeroU32> = Some(5).and_then(NonZeroU32::new).into_controlled();
+    assert_eq!(some.into_option(), NonZeroU32::new(5));
+
+    let none = Some(0).and_then(NonZeroU32::new).into_controlled();
+    assert!(none.is_none());
+
+    let value = 75;
+    let reference = Some(&value).filter(|value| **value > 50).into_controlled();
+    assert_eq!(reference.as_option(), Some(&&75));
+}
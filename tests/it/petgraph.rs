@@ -0,0 +1,39 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use controlled_option::ControlledOption;
+use petgraph::graph::EdgeIndex;
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+
+#[test]
+fn can_option_graph_indices() {
+    assert_eq!(std::mem::size_of::<ControlledOption<NodeIndex>>(), 4);
+    assert_eq!(std::mem::size_of::<ControlledOption<EdgeIndex<u16>>>(), 2);
+
+    let mut graph = Graph::<&str, ()>::new();
+    let a = graph.add_node("a");
+    let b = graph.add_node("b");
+    let edge = graph.add_edge(a, b, ());
+
+    let node = ControlledOption::some(b);
+    assert_eq!(node.as_option().map(|node| graph[*node]), Some("b"));
+    assert_eq!(node.into_option(), Some(b));
+    let edge = ControlledOption::some(edge);
+    assert_eq!(
+        edge.into_option()
+            .and_then(|edge| graph.edge_endpoints(edge)),
+        Some((a, b))
+    );
+
+    assert!(ControlledOption::<NodeIndex>::none().is_none());
+    assert_eq!(ControlledOption::<EdgeIndex>::none().into_option(), None);
+    assert_eq!(
+        ControlledOption::<NodeIndex<usize>>::none().into_option(),
+        None
+    );
+}
@@ -0,0 +1,38 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::prelude::*;
+
+#[derive(Clone, Copy, Debug, Niche)]
+struct Edge {
+    #[niche]
+    target: NonZeroU32,
+    weight: u32,
+}
+
+#[test]
+fn prelude_provides_common_items() {
+    let edge = Edge {
+        target: NonZeroU32::new(1).unwrap(),
+        weight: 10,
+    };
+    let edges = [ControlledOption::some(edge), ControlledOption::none()];
+    assert_eq!(edges.count_some(), 1);
+    assert_eq!(edges.position_none(), Some(1));
+
+    let mut vec = ControlledOptionVec::<Edge>::new();
+    vec.push_some(edge);
+    assert_eq!(
+        vec.iter_some().map(|(_, edge)| edge.weight).sum::<u32>(),
+        10
+    );
+
+    const NONE: ControlledOption<NonZeroU32> = ControlledOption::NONE;
+    assert!(NONE.is_none());
+}
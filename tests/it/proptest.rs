@@ -0,0 +1,32 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::TestRunner;
+
+proptest! {
+    #[test]
+    fn arbitrary_options_round_trip(value: ControlledOption<NonZeroU32>) {
+        prop_assert_eq!(ControlledOption::from(value.into_option()), value);
+    }
+}
+
+#[test]
+fn options_shrink_toward_none() {
+    let mut runner = TestRunner::deterministic();
+    let strategy = controlled_option::proptest::weighted(0.9, any::<NonZeroU32>());
+    let mut tree = strategy.new_tree(&mut runner).unwrap();
+    while tree.current().is_none() {
+        tree = strategy.new_tree(&mut runner).unwrap();
+    }
+    while tree.simplify() {}
+    assert!(tree.current().is_none());
+}
@@ -0,0 +1,25 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use quickcheck::quickcheck;
+use quickcheck::Arbitrary;
+
+quickcheck! {
+    fn arbitrary_options_round_trip(value: ControlledOption<NonZeroU32>) -> bool {
+        ControlledOption::from(value.into_option()) == value
+    }
+}
+
+#[test]
+fn options_shrink_toward_none() {
+    let value = ControlledOption::from(NonZeroU32::new(75));
+    let shrunk: Vec<_> = value.shrink().collect();
+    assert_eq!(shrunk.first(), Some(&ControlledOption::none()));
+}
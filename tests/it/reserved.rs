@@ -0,0 +1,52 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use controlled_option::impl_niche_for_reserved_value;
+use controlled_option::PackedOption;
+use controlled_option::ReservedValue;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Value(u32);
+
+impl ReservedValue for Value {
+    fn reserved_value() -> Value {
+        Value(u32::MAX)
+    }
+
+    fn is_reserved_value(&self) -> bool {
+        self.0 == u32::MAX
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Inst(u32);
+
+impl ReservedValue for Inst {
+    fn reserved_value() -> Inst {
+        Inst(0)
+    }
+
+    fn is_reserved_value(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl_niche_for_reserved_value!(Value, Inst);
+
+#[test]
+fn can_pack_reserved_values() {
+    let none = PackedOption::<Value>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<PackedOption<Value>>(), 4);
+    let some = PackedOption::from(Value(0));
+    assert!(some.is_some());
+    assert_eq!(some.into_option(), Some(Value(0)));
+    assert_eq!(PackedOption::from(Some(Value(7))), Value(7));
+
+    assert!(PackedOption::<Inst>::default().is_none());
+    assert_eq!(PackedOption::some(Inst(1)).as_option(), Some(&Inst(1)));
+}
@@ -0,0 +1,85 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledResult;
+use controlled_option::ResultNiche;
+
+#[test]
+fn can_pack_unit_errors_into_niches() {
+    let ok = ControlledResult::<NonZeroU32, ()>::ok(NonZeroU32::new(75).unwrap());
+    assert!(ok.is_ok());
+    assert_eq!(std::mem::size_of::<ControlledResult<NonZeroU32, ()>>(), 4);
+    assert_eq!(ok.as_result(), Ok(&NonZeroU32::new(75).unwrap()));
+    assert_eq!(ok.into_result(), Ok(NonZeroU32::new(75).unwrap()));
+
+    let err = ControlledResult::<NonZeroU32, ()>::err(());
+    assert!(err.is_err());
+    assert_eq!(err.into_result(), Err(()));
+    assert_ne!(ok, err);
+}
+
+// An index that uses the top of its range for error codes.
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+struct Index(u32);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+struct ErrorCode(u32);
+
+const FIRST_ERROR: u32 = 0xFFFF_0000;
+const OUT_OF_BOUNDS: ErrorCode = ErrorCode(FIRST_ERROR + 2);
+
+impl ResultNiche<ErrorCode> for Index {
+    type Output = u32;
+
+    fn is_ok(value: &u32) -> bool {
+        *value < FIRST_ERROR
+    }
+
+    fn from_ok(value: Index) -> u32 {
+        value.0
+    }
+
+    fn from_err(error: ErrorCode) -> u32 {
+        error.0
+    }
+
+    fn into_ok(value: u32) -> Index {
+        Index(value)
+    }
+
+    fn into_err(value: u32) -> ErrorCode {
+        ErrorCode(value)
+    }
+
+    fn as_ok(value: &u32) -> &Index {
+        unsafe { &*(value as *const u32 as *const Index) }
+    }
+
+    fn as_err(value: &u32) -> &ErrorCode {
+        unsafe { &*(value as *const u32 as *const ErrorCode) }
+    }
+}
+
+#[test]
+fn can_pack_error_codes_into_indices() {
+    assert_eq!(std::mem::size_of::<ControlledResult<Index, ErrorCode>>(), 4);
+
+    let ok = ControlledResult::<Index, ErrorCode>::from(Ok(Index(7)));
+    assert!(ok.is_ok());
+    assert_eq!(ok.as_result(), Ok(&Index(7)));
+    assert_eq!(ok.into_result(), Ok(Index(7)));
+
+    let err = ControlledResult::<Index, ErrorCode>::err(OUT_OF_BOUNDS);
+    assert!(err.is_err());
+    assert_eq!(err.as_result(), Err(&OUT_OF_BOUNDS));
+    assert_eq!(Result::from(err), Err(OUT_OF_BOUNDS));
+}
@@ -0,0 +1,39 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use rkyv::rancor::Error;
+use rkyv::Archive;
+use rkyv::Deserialize;
+use rkyv::Serialize;
+
+#[derive(Archive, Debug, Deserialize, PartialEq, Serialize)]
+struct Table {
+    ids: Vec<ControlledOption<NonZeroU32>>,
+}
+
+#[test]
+fn archived_options_keep_niche_layout() {
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<rkyv::Archived<NonZeroU32>>>(),
+        std::mem::size_of::<u32>()
+    );
+
+    let table = Table {
+        ids: vec![NonZeroU32::new(75).into(), NonZeroU32::new(0).into()],
+    };
+    let bytes = rkyv::to_bytes::<Error>(&table).unwrap();
+    let archived = rkyv::access::<ArchivedTable, Error>(&bytes).unwrap();
+    assert_eq!(archived.ids.len(), 2);
+    assert!(archived.ids[0].is_some());
+    assert!(archived.ids[1].is_none());
+
+    let deserialized = rkyv::deserialize::<Table, Error>(archived).unwrap();
+    assert_eq!(deserialized, table);
+}
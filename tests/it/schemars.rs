@@ -0,0 +1,45 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use schemars::schema_for;
+use schemars::JsonSchema;
+
+mod std_option {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    pub struct Node {
+        pub id: NonZeroU32,
+        #[serde(default)]
+        pub parent: Option<NonZeroU32>,
+    }
+}
+
+mod controlled {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    pub struct Node {
+        pub id: NonZeroU32,
+        #[serde(default)]
+        pub parent: ControlledOption<NonZeroU32>,
+    }
+}
+
+#[test]
+fn options_have_same_schema_as_std_option() {
+    assert_eq!(
+        schema_for!(ControlledOption<NonZeroU32>),
+        schema_for!(Option<NonZeroU32>)
+    );
+    assert_eq!(schema_for!(controlled::Node), schema_for!(std_option::Node));
+}
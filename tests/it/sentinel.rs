@@ -0,0 +1,73 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use controlled_option::ControlledOption;
+use controlled_option::Niche;
+use controlled_option::SentinelI32;
+use controlled_option::SentinelU16;
+use controlled_option::SentinelU32;
+
+type Port = SentinelU16<0xFFFF>;
+type Magic = SentinelU32<0xDEADBEEF>;
+type Errno = SentinelI32<-1>;
+
+#[test]
+fn can_create_sentinel_values() {
+    assert_eq!(Magic::new(0xDEADBEEF), None);
+    assert_eq!(Magic::new(0).map(Magic::get), Some(0));
+    assert_eq!(Magic::NICHE, 0xDEADBEEF);
+    assert_eq!(Magic::MIN.get(), u32::MIN);
+    assert_eq!(Magic::MAX.get(), u32::MAX);
+    assert_eq!(Port::MAX.get(), 0xFFFE);
+    assert_eq!(Errno::new(-1), None);
+    assert_eq!(Errno::MIN.get(), i32::MIN);
+}
+
+#[test]
+fn can_option_sentinel_values() {
+    let none = ControlledOption::<Magic>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<Magic>>(), 4);
+    assert_eq!(Magic::none(), 0xDEADBEEF);
+
+    let some = ControlledOption::from(Magic::new(u32::MAX));
+    assert!(some.is_some());
+    assert_eq!(some.into_option(), Magic::new(u32::MAX));
+
+    assert!(ControlledOption::<Errno>::none().is_none());
+    let some = ControlledOption::from(Errno::new(0));
+    assert_eq!(some.into_option().map(Errno::get), Some(0));
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct NodeId(u32);
+
+controlled_option::impl_niche_with_sentinel!(NodeId, u32, u32::MAX);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Offset(i64);
+
+controlled_option::impl_niche_with_sentinel!(Offset, i64, -1);
+
+#[test]
+fn can_option_sentinel_newtypes() {
+    assert_eq!(NodeId::new(u32::MAX), None);
+    assert_eq!(NodeId::new(0).map(NodeId::get), Some(0));
+    assert_eq!(NodeId::SENTINEL, u32::MAX);
+
+    let none = ControlledOption::<NodeId>::none();
+    assert!(none.is_none());
+    assert_eq!(std::mem::size_of::<ControlledOption<NodeId>>(), 4);
+    let some = ControlledOption::from(NodeId::new(0));
+    assert_eq!(some.as_option(), Some(&NodeId(0)));
+    assert_eq!(some.into_option(), Some(NodeId(0)));
+
+    assert!(ControlledOption::<Offset>::none().is_none());
+    let some = ControlledOption::from(Offset::new(0));
+    assert_eq!(some.into_option(), Some(Offset(0)));
+    assert_eq!(Offset::new(-1), None);
+}
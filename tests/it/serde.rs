@@ -0,0 +1,99 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[cfg(feature = "serde")]
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct WithOption {
+    id: Option<NonZeroU32>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct WithControlledOption {
+    id: ControlledOption<NonZeroU32>,
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn options_serialize_like_std_option() {
+    for id in [None, NonZeroU32::new(75)] {
+        let expected = serde_json::to_string(&WithOption { id }).unwrap();
+        let actual = serde_json::to_string(&WithControlledOption { id: id.into() }).unwrap();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn options_deserialize_like_std_option() {
+    let none: WithControlledOption = serde_json::from_str(r#"{"id":null}"#).unwrap();
+    assert!(none.id.is_none());
+    let some: WithControlledOption = serde_json::from_str(r#"{"id":75}"#).unwrap();
+    assert_eq!(some.id, ControlledOption::from(NonZeroU32::new(75)));
+    assert!(serde_json::from_str::<WithControlledOption>(r#"{"id":0}"#).is_err());
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct WithFieldAttributes {
+    #[serde(with = "controlled_option::serde", default)]
+    parent: ControlledOption<NonZeroU32>,
+    #[serde(with = "controlled_option::serde::vec")]
+    children: Vec<ControlledOption<NonZeroU32>>,
+}
+
+#[test]
+fn can_use_serde_with_attributes() {
+    let value = WithFieldAttributes {
+        parent: ControlledOption::none(),
+        children: vec![NonZeroU32::new(1).into(), NonZeroU32::new(0).into()],
+    };
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"parent":null,"children":[1,null]}"#);
+    assert_eq!(
+        serde_json::from_str::<WithFieldAttributes>(&json).unwrap(),
+        value
+    );
+
+    // Missing fields fall back on the `default` attribute.
+    let missing: WithFieldAttributes = serde_json::from_str(r#"{"children":[]}"#).unwrap();
+    assert!(missing.parent.is_none());
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct WithRawSentinel {
+    #[serde(with = "controlled_option::serde::raw")]
+    id: ControlledOption<NonZeroU32>,
+}
+
+#[test]
+fn can_serialize_raw_sentinels() {
+    let none = WithRawSentinel {
+        id: ControlledOption::none(),
+    };
+    let json = serde_json::to_string(&none).unwrap();
+    assert_eq!(json, r#"{"id":0}"#);
+    assert_eq!(
+        serde_json::from_str::<WithRawSentinel>(&json).unwrap(),
+        none
+    );
+
+    let some = WithRawSentinel {
+        id: NonZeroU32::new(75).into(),
+    };
+    let json = serde_json::to_string(&some).unwrap();
+    assert_eq!(json, r#"{"id":75}"#);
+    assert_eq!(
+        serde_json::from_str::<WithRawSentinel>(&json).unwrap(),
+        some
+    );
+}
@@ -0,0 +1,63 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledSlab;
+
+fn nz(value: u32) -> NonZeroU32 {
+    NonZeroU32::new(value).unwrap()
+}
+
+#[test]
+fn can_insert_and_remove_values() {
+    let mut slab = ControlledSlab::new();
+    let one = slab.insert(nz(1));
+    let two = slab.insert(nz(2));
+    let three = slab.insert(nz(3));
+    assert_eq!(slab.len(), 3);
+    assert_eq!(slab.get(two), Some(&nz(2)));
+
+    assert_eq!(slab.remove(two), Some(nz(2)));
+    assert_eq!(slab.remove(two), None);
+    assert!(!slab.contains(two));
+    assert_eq!(slab.len(), 2);
+
+    // Vacant slots are reused before the slab grows.
+    let four = slab.insert(nz(4));
+    assert_eq!(four, two);
+    assert_eq!(slab.get(one), Some(&nz(1)));
+    assert_eq!(slab.get(three), Some(&nz(3)));
+    assert_eq!(slab.get(four), Some(&nz(4)));
+    assert_eq!(slab.get(10), None);
+}
+
+#[test]
+fn can_iterate_and_retain() {
+    let mut slab = ControlledSlab::new();
+    for value in 1..=6 {
+        slab.insert(nz(value));
+    }
+    *slab.get_mut(0).unwrap() = nz(10);
+    slab.retain(|_, value| value.get() % 2 == 0);
+    let values = slab.iter().map(|(key, value)| (key, value.get()));
+    assert_eq!(
+        values.collect::<Vec<_>>(),
+        vec![(0, 10), (1, 2), (3, 4), (5, 6)]
+    );
+    assert_eq!(slab.len(), 4);
+}
+
+#[test]
+fn can_store_owned_values() {
+    let mut slab = ControlledSlab::new();
+    let key = slab.insert(Box::new(75));
+    slab.insert(Box::new(76));
+    **slab.get_mut(key).unwrap() += 1;
+    assert_eq!(slab.remove(key), Some(Box::new(76)));
+    assert_eq!(format!("{:?}", slab), "{1: 76}");
+}
@@ -0,0 +1,94 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use controlled_option::OptionSliceExt;
+
+fn nz(value: u32) -> ControlledOption<NonZeroU32> {
+    ControlledOption::from(NonZeroU32::new(value))
+}
+
+#[test]
+fn can_count_and_find_options() {
+    let slice = [nz(0), nz(2), nz(0), nz(4), nz(5)];
+    assert_eq!(slice.count_some(), 3);
+    assert_eq!(slice.position_some(), Some(1));
+    assert_eq!(slice.position_none(), Some(0));
+    assert_eq!(slice[1..].position_none(), Some(1));
+    assert_eq!(slice[3..].position_none(), None);
+    assert_eq!(slice[..1].position_some(), None);
+}
+
+#[test]
+fn can_split_at_first_none() {
+    let slice = [nz(1), nz(2), nz(0), nz(4)];
+    let (head, tail) = slice.split_at_first_none();
+    assert_eq!(head, &[nz(1), nz(2)]);
+    assert_eq!(tail, &[nz(0), nz(4)]);
+
+    let (head, tail) = slice[3..].split_at_first_none();
+    assert_eq!(head, &[nz(4)]);
+    assert!(tail.is_empty());
+}
+
+#[test]
+fn can_partition_in_place() {
+    let mut slice = [nz(0), nz(2), nz(0), nz(4), nz(5), nz(0)];
+    let count = slice.partition_in_place();
+    assert_eq!(count, 3);
+    assert!(slice[..count].iter().all(ControlledOption::is_some));
+    assert!(slice[count..].iter().all(ControlledOption::is_none));
+    let mut values = slice[..count]
+        .iter()
+        .map(|value| value.into_option().unwrap().get())
+        .collect::<Vec<_>>();
+    values.sort_unstable();
+    assert_eq!(values, vec![2, 4, 5]);
+
+    let mut empty: [ControlledOption<NonZeroU32>; 0] = [];
+    assert_eq!(empty.partition_in_place(), 0);
+}
+
+#[test]
+fn can_fill_with_none() {
+    let mut slice = [nz(1), nz(0), nz(3)];
+    slice.fill_none();
+    assert_eq!(slice.count_some(), 0);
+
+    let mut slice = [nz(1), nz(0), nz(3)];
+    slice.fill_none_zeroed();
+    assert_eq!(slice.count_some(), 0);
+
+    let value = std::rc::Rc::new(75);
+    let mut slice = [
+        ControlledOption::some(Box::new(value.clone())),
+        ControlledOption::none(),
+        ControlledOption::some(Box::new(value.clone())),
+    ];
+    assert_eq!(std::rc::Rc::strong_count(&value), 3);
+    slice.fill_none_zeroed();
+    assert_eq!(slice.count_some(), 0);
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn can_iterate_over_some_elements() {
+    let slice = [nz(0), nz(2), nz(0), nz(4)];
+    let some = slice
+        .iter_some()
+        .map(|(index, value)| (index, value.get()))
+        .collect::<Vec<_>>();
+    assert_eq!(some, vec![(1, 2), (3, 4)]);
+    let reversed = slice
+        .iter_some()
+        .rev()
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    assert_eq!(reversed, vec![3, 1]);
+}
@@ -0,0 +1,56 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use controlled_option::impl_niche_for_slotmap_key;
+use controlled_option::ControlledOption;
+use slotmap::DefaultKey;
+use slotmap::Key;
+use slotmap::KeyData;
+use slotmap::SlotMap;
+
+slotmap::new_key_type! { struct EntityKey; }
+
+impl_niche_for_slotmap_key!(EntityKey);
+
+#[test]
+fn can_option_default_keys() {
+    let mut map = SlotMap::new();
+    let key = map.insert(75);
+    assert_eq!(std::mem::size_of::<ControlledOption<DefaultKey>>(), 8);
+
+    let some = ControlledOption::some(key);
+    assert!(some.is_some());
+    assert_eq!(some.into_option(), Some(key));
+
+    let none = ControlledOption::<DefaultKey>::none();
+    assert!(none.is_none());
+    assert!(none.into_option().is_none());
+}
+
+#[test]
+fn can_option_key_data() {
+    let mut map = SlotMap::new();
+    let key: DefaultKey = map.insert(75);
+    let data = key.data();
+    assert_eq!(std::mem::size_of::<ControlledOption<KeyData>>(), 8);
+    assert_eq!(ControlledOption::some(data).into_option(), Some(data));
+    assert!(ControlledOption::<KeyData>::none().is_none());
+}
+
+#[test]
+fn can_option_custom_keys() {
+    let mut map = SlotMap::<EntityKey, _>::with_key();
+    let key = map.insert("entity");
+    map.remove(key);
+    let key = map.insert("entity");
+    assert_eq!(std::mem::size_of::<ControlledOption<EntityKey>>(), 8);
+
+    let some = ControlledOption::some(key);
+    assert_eq!(some.as_option(), Some(&key));
+    assert_eq!(some.into_option().map(|key| map[key]), Some("entity"));
+    assert!(ControlledOption::<EntityKey>::none().is_none());
+}
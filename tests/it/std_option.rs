@@ -0,0 +1,65 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use controlled_option::ControlledOption;
+
+fn nz(value: u32) -> Option<NonZeroU32> {
+    NonZeroU32::new(value)
+}
+
+#[test]
+fn can_convert_to_and_from_std_options() {
+    assert_eq!(ControlledOption::from_std(nz(5)).into_option(), nz(5));
+    assert!(ControlledOption::from_std(nz(0)).is_none());
+    assert_eq!(ControlledOption::from(nz(5)).to_std(), nz(5));
+    assert_eq!(ControlledOption::<NonZeroU32>::none().to_std(), None);
+
+    let value = 75;
+    let option = ControlledOption::some(&value);
+    assert_eq!(option.as_std(), &Some(&value));
+    assert_eq!(ControlledOption::<&u32>::none().as_std(), &None);
+
+    let mut option = ControlledOption::from(nz(1));
+    *option.as_std_mut() = None;
+    assert!(option.is_none());
+    *option.as_std_mut() = nz(2);
+    assert_eq!(option.into_option(), nz(2));
+}
+
+#[test]
+fn can_convert_std_option_slices() {
+    let mut std = [nz(1), nz(0), nz(3)];
+    let controlled = ControlledOption::slice_from_std(&std);
+    assert_eq!(
+        controlled
+            .iter()
+            .map(|value| value.is_some())
+            .collect::<Vec<_>>(),
+        vec![true, false, true],
+    );
+    assert_eq!(ControlledOption::slice_to_std(controlled), &std);
+
+    let controlled = ControlledOption::slice_from_std_mut(&mut std);
+    controlled[0] = ControlledOption::none();
+    controlled[1] = ControlledOption::from(nz(2));
+    ControlledOption::slice_to_std_mut(controlled)[2] = None;
+    assert_eq!(std, [nz(0), nz(2), nz(0)]);
+}
+
+#[test]
+fn std_conversions_preserve_ownership() {
+    let value = Rc::new(75);
+    let option = ControlledOption::from_std(Some(Box::new(value.clone())));
+    assert_eq!(Rc::strong_count(&value), 2);
+    let option = option.to_std();
+    assert_eq!(Rc::strong_count(&value), 2);
+    drop(option);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
@@ -0,0 +1,163 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::mem::MaybeUninit;
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use controlled_option::DefaultNiche;
+use controlled_option::NicheStrategy;
+
+//-------------------------------------------------------------------------------------------------
+// Integers with a choice of niche
+
+struct ZeroIsNone;
+
+impl NicheStrategy<u32> for ZeroIsNone {
+    type Output = u32;
+    fn none() -> u32 {
+        0
+    }
+    fn is_none(value: &u32) -> bool {
+        *value == 0
+    }
+    fn into_some(value: u32) -> u32 {
+        value
+    }
+    fn from_some(value: u32) -> u32 {
+        value
+    }
+    fn as_some(value: &u32) -> &u32 {
+        value
+    }
+}
+
+struct MaxIsNone;
+
+impl NicheStrategy<u32> for MaxIsNone {
+    type Output = u32;
+    fn none() -> u32 {
+        u32::MAX
+    }
+    fn is_none(value: &u32) -> bool {
+        *value == u32::MAX
+    }
+    fn into_some(value: u32) -> u32 {
+        value
+    }
+    fn from_some(value: u32) -> u32 {
+        value
+    }
+    fn as_some(value: &u32) -> &u32 {
+        value
+    }
+}
+
+#[test]
+fn can_choose_integer_niches() {
+    let none = ControlledOption::<u32, ZeroIsNone>::none_with_strategy();
+    assert!(none.is_none());
+    let some = ControlledOption::<u32, ZeroIsNone>::some_with_strategy(u32::MAX);
+    assert_eq!(some.into_option(), Some(u32::MAX));
+    assert_eq!(std::mem::size_of::<ControlledOption<u32, ZeroIsNone>>(), 4);
+
+    let none = ControlledOption::<u32, MaxIsNone>::none_with_strategy();
+    assert!(none.is_none());
+    let some = ControlledOption::<u32, MaxIsNone>::some_with_strategy(0);
+    assert_eq!(some.into_option(), Some(0));
+    assert_eq!(std::mem::size_of::<ControlledOption<u32, MaxIsNone>>(), 4);
+}
+
+//-------------------------------------------------------------------------------------------------
+// Structs with more than one niche field
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+struct Pair {
+    a: NonZeroU32,
+    b: NonZeroU32,
+}
+
+impl Pair {
+    fn new(a: u32, b: u32) -> Pair {
+        Pair {
+            a: NonZeroU32::new(a).unwrap(),
+            b: NonZeroU32::new(b).unwrap(),
+        }
+    }
+}
+
+struct FirstField;
+
+impl NicheStrategy<Pair> for FirstField {
+    type Output = MaybeUninit<Pair>;
+    fn none() -> Self::Output {
+        let mut value = MaybeUninit::<Pair>::uninit();
+        let ptr = value.as_mut_ptr() as *mut u32;
+        unsafe { ptr.write(0) };
+        value
+    }
+    fn is_none(value: &Self::Output) -> bool {
+        let ptr = value.as_ptr() as *const u32;
+        unsafe { ptr.read() == 0 }
+    }
+    fn into_some(value: Pair) -> Self::Output {
+        MaybeUninit::new(value)
+    }
+    fn from_some(value: Self::Output) -> Pair {
+        unsafe { value.assume_init() }
+    }
+    fn as_some(value: &Self::Output) -> &Pair {
+        unsafe { &*value.as_ptr() }
+    }
+}
+
+struct SecondField;
+
+impl NicheStrategy<Pair> for SecondField {
+    type Output = MaybeUninit<Pair>;
+    fn none() -> Self::Output {
+        let mut value = MaybeUninit::<Pair>::uninit();
+        let ptr = value.as_mut_ptr() as *mut u32;
+        unsafe { ptr.add(1).write(0) };
+        value
+    }
+    fn is_none(value: &Self::Output) -> bool {
+        let ptr = value.as_ptr() as *const u32;
+        unsafe { ptr.add(1).read() == 0 }
+    }
+    fn into_some(value: Pair) -> Self::Output {
+        MaybeUninit::new(value)
+    }
+    fn from_some(value: Self::Output) -> Pair {
+        unsafe { value.assume_init() }
+    }
+    fn as_some(value: &Self::Output) -> &Pair {
+        unsafe { &*value.as_ptr() }
+    }
+}
+
+#[test]
+fn can_choose_struct_field_niches() {
+    let first = ControlledOption::<Pair, FirstField>::some_with_strategy(Pair::new(1, 2));
+    let second = ControlledOption::<Pair, SecondField>::some_with_strategy(Pair::new(1, 2));
+    assert_eq!(first.as_option(), Some(&Pair::new(1, 2)));
+    assert_eq!(second.into_option(), Some(Pair::new(1, 2)));
+    assert!(ControlledOption::<Pair, FirstField>::none_with_strategy().is_none());
+    assert!(ControlledOption::<Pair, SecondField>::none_with_strategy().is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<Pair, FirstField>>(),
+        std::mem::size_of::<Pair>()
+    );
+}
+
+#[test]
+fn default_strategy_uses_niche_impl() {
+    let value = ControlledOption::<NonZeroU32, DefaultNiche>::from(NonZeroU32::new(7));
+    let same: ControlledOption<NonZeroU32> = value;
+    assert_eq!(same.into_option(), NonZeroU32::new(7));
+}
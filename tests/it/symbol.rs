@@ -0,0 +1,41 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use controlled_option::ControlledOption;
+use controlled_option::Interner;
+use controlled_option::Symbol;
+
+#[test]
+fn can_intern_strings() {
+    let mut interner = Interner::new();
+    let hello = interner.intern("hello");
+    let world = interner.intern("world");
+    assert_eq!(interner.intern("hello"), hello);
+    assert_ne!(hello, world);
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.resolve(world), "world");
+    assert_eq!(interner.lookup("world"), Some(world));
+    assert_eq!(interner.lookup("other"), None);
+    assert_eq!(interner.get(Symbol::from_index(5)), None);
+    assert_eq!(format!("{:?}", world), "Symbol(1)");
+}
+
+#[test]
+fn can_option_symbols() {
+    struct Token {
+        _kind: u32,
+        name: ControlledOption<Symbol>,
+    }
+    assert_eq!(std::mem::size_of::<Token>(), 8);
+
+    let token = Token {
+        _kind: 0,
+        name: ControlledOption::some(Symbol::from_index(0)),
+    };
+    assert_eq!(token.name.into_option().map(Symbol::index), Some(0));
+    assert!(ControlledOption::<Symbol>::none().is_none());
+}
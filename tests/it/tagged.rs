@@ -0,0 +1,52 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::ptr::NonNull;
+
+use controlled_option::ControlledOption;
+use controlled_option::TaggedPtr;
+
+#[test]
+fn can_tag_pointers() {
+    let mut value = 75u64;
+    let ptr = NonNull::from(&mut value);
+    let tagged = TaggedPtr::<u64, 3>::new(ptr, 5);
+    assert_eq!(tagged.ptr(), ptr);
+    assert_eq!(tagged.tag(), 5);
+    let retagged = tagged.with_tag(2);
+    assert_eq!(retagged.ptr(), ptr);
+    assert_eq!(retagged.tag(), 2);
+    assert_ne!(tagged, retagged);
+    assert_eq!(unsafe { *retagged.ptr().as_ptr() }, 75);
+}
+
+#[test]
+#[should_panic]
+fn cannot_store_oversized_tags() {
+    let mut value = 75u64;
+    TaggedPtr::<u64, 3>::new(NonNull::from(&mut value), 8);
+}
+
+#[test]
+fn can_option_tagged_pointers() {
+    let none = ControlledOption::<TaggedPtr<u64, 3>>::none();
+    assert!(none.is_none());
+    assert_eq!(
+        std::mem::size_of::<ControlledOption<TaggedPtr<u64, 3>>>(),
+        std::mem::size_of::<usize>()
+    );
+
+    let mut value = 75u64;
+    let ptr = NonNull::from(&mut value);
+    // Even a tagged pointer with a zero tag is not `None`.
+    let some = ControlledOption::some(TaggedPtr::<u64, 3>::new(ptr, 0));
+    assert!(some.is_some());
+    let some = ControlledOption::some(TaggedPtr::<u64, 3>::new(ptr, 7));
+    let tagged = some.into_option().unwrap();
+    assert_eq!(tagged.ptr(), ptr);
+    assert_eq!(tagged.tag(), 7);
+}
@@ -0,0 +1,86 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+
+use controlled_option::ControlledOption;
+use controlled_option::ControlledOptionVec;
+
+fn nz(value: u32) -> NonZeroU32 {
+    NonZeroU32::new(value).unwrap()
+}
+
+#[test]
+fn can_push_and_pop() {
+    let mut vec = ControlledOptionVec::<NonZeroU32>::new();
+    vec.push_some(nz(1));
+    vec.push_none();
+    vec.push(ControlledOption::some(nz(3)));
+    assert_eq!(vec.len(), 3);
+    assert_eq!(vec.count_some(), 2);
+    assert_eq!(vec[0], nz(1));
+    assert!(vec[1].is_none());
+    assert_eq!(vec.get(2), Some(&ControlledOption::some(nz(3))));
+    assert_eq!(vec.get(3), None);
+    assert_eq!(vec.pop(), Some(ControlledOption::some(nz(3))));
+    assert_eq!(vec.len(), 2);
+}
+
+#[test]
+fn can_set_and_take_elements() {
+    let mut vec = ControlledOptionVec::<NonZeroU32>::from_none(3);
+    assert_eq!(vec.count_some(), 0);
+    assert!(vec.set(1, ControlledOption::some(nz(2))).is_none());
+    assert_eq!(vec.take(1), nz(2));
+    assert!(vec[1].is_none());
+    vec[2] = ControlledOption::some(nz(3));
+    assert_eq!(vec.count_some(), 1);
+}
+
+#[test]
+fn can_iterate_over_some_elements() {
+    let vec = vec![Some(nz(1)), None, None, Some(nz(4))]
+        .into_iter()
+        .collect::<ControlledOptionVec<_>>();
+    let some = vec.iter_some().collect::<Vec<_>>();
+    assert_eq!(some, vec![(0, &nz(1)), (3, &nz(4))]);
+}
+
+#[test]
+fn can_fill_and_resize() {
+    let mut vec = vec![Some(nz(1)), Some(nz(2))]
+        .into_iter()
+        .collect::<ControlledOptionVec<_>>();
+    vec.fill_none();
+    assert_eq!(vec.len(), 2);
+    assert_eq!(vec.count_some(), 0);
+    vec[1] = ControlledOption::some(nz(3));
+    vec.fill_none_zeroed();
+    assert_eq!(vec.len(), 2);
+    assert_eq!(vec.count_some(), 0);
+    vec.resize_none(4);
+    assert_eq!(vec.len(), 4);
+    vec.truncate(1);
+    assert_eq!(vec.len(), 1);
+    vec.clear();
+    assert!(vec.is_empty());
+}
+
+#[test]
+fn vectors_drop_their_contents() {
+    let value = std::rc::Rc::new(75);
+    let mut vec = ControlledOptionVec::new();
+    vec.push_some(Box::new(value.clone()));
+    vec.push_some(Box::new(value.clone()));
+    vec.push_none();
+    assert_eq!(std::rc::Rc::strong_count(&value), 3);
+    vec.fill_none();
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+    vec.push_some(Box::new(value.clone()));
+    drop(vec);
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+}
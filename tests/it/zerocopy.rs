@@ -0,0 +1,58 @@
+// -*- coding: utf-8 -*-
+// ------------------------------------------------------------------------------------------------
+// Copyright © 2021, Douglas Creager.
+// Licensed under either of Apache License, Version 2.0, or MIT license, at your option.
+// Please see the LICENSE-APACHE or LICENSE-MIT files in this distribution for license details.
+// ------------------------------------------------------------------------------------------------
+
+use std::num::NonZeroU32;
+use std::num::NonZeroU8;
+
+use controlled_option::ControlledOption;
+use zerocopy::FromBytes;
+use zerocopy::Immutable;
+use zerocopy::IntoBytes;
+use zerocopy::KnownLayout;
+use zerocopy::Unaligned;
+
+#[derive(Immutable, IntoBytes, KnownLayout)]
+#[repr(C)]
+struct Header {
+    id: ControlledOption<NonZeroU32>,
+    parent: ControlledOption<NonZeroU32>,
+}
+
+#[derive(Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C)]
+struct Flags {
+    a: ControlledOption<NonZeroU8>,
+    b: ControlledOption<NonZeroU8>,
+}
+
+#[test]
+fn can_write_options_as_bytes() {
+    let header = Header {
+        id: NonZeroU32::new(0x01020304).into(),
+        parent: ControlledOption::none(),
+    };
+    let mut expected = 0x01020304u32.to_ne_bytes().to_vec();
+    expected.extend_from_slice(&[0, 0, 0, 0]);
+    assert_eq!(header.as_bytes(), &expected[..]);
+
+    let flags = Flags {
+        a: ControlledOption::none(),
+        b: NonZeroU8::new(7).into(),
+    };
+    assert_eq!(flags.as_bytes(), &[0, 7]);
+}
+
+#[test]
+fn can_read_dense_options_from_bytes() {
+    let bytes = 0x01020304u32.to_ne_bytes();
+    let id = ControlledOption::<NonZeroU32>::from_output(u32::read_from_bytes(&bytes).unwrap());
+    assert_eq!(id.into_option(), NonZeroU32::new(0x01020304));
+
+    let bytes = [0u8; 4];
+    let id = ControlledOption::<NonZeroU32>::from_output(u32::read_from_bytes(&bytes).unwrap());
+    assert!(id.is_none());
+}